@@ -1,75 +1,885 @@
 use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use chrono::{DateTime, Local, NaiveTime};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::{Position, Rect};
 use ratatui_input_manager::{keymap, KeyMap};
 
-use crate::melodies::{TWO_TONE, VICTORY_FANFARE};
-use crate::notifications::{send_notification, AudioPlayer};
-use crate::panels::{PanelId, TasksPanel, TimerPanel, TIMER_MIN_WIDTH};
-use crate::timer::{SessionType, Timer};
+use crate::config::{Config, ConfigWatcher, ScheduleEntry};
+use crate::eye_breaks::EyeBreaks;
+use crate::history::{History, SessionRecord};
+use crate::idle::{IdleMonitor, Presence};
+use crate::interruptions::{Interruption, InterruptionLog};
+use crate::melodies::{GET_READY, SOFT_CHIME, TWO_TONE, VICTORY_FANFARE};
+use crate::notification_policy;
+use crate::notifications::{
+    send_actionable_notification, send_notification_via, AudioPlayer, NotificationAction,
+    NotificationBackend, NotificationSerial,
+};
+use crate::overlays::{
+    CommandPaletteOverlay, PauseReasonOverlay, PresetOverlay, QuitChoice, QuitOverlay,
+    RecoveryChoice, RecoveryOverlay, SessionChoice, SessionCompleteOverlay, SleepChoice,
+    SleptOverlay, TaskOutcome, TaskSwitcherOverlay, Toast, UntilOverlay,
+};
+use crate::panels::{HistoryPanel, PanelId, PluginPanel, TasksPanel, TimerPanel, TIMER_MIN_WIDTH};
+use crate::recovery::{SessionFile, SessionSnapshot};
+use crate::scheduler::Scheduler;
+use crate::scripting::{ScriptAction, ScriptEngine, ScriptEvent, ScriptState};
+use crate::task::Task;
+use crate::timer::{SessionType, Timer, TimerEvent, TimerMode, MAX_SNOOZES, SNOOZE_LENGTH};
+use crate::ui::LayoutPreset;
+
+/// Time added by the "+5 min" notification action
+const EXTEND_DURATION: Duration = Duration::from_mins(5);
+
+/// How soon the second press must follow to reset in strict mode
+const RESET_CONFIRM_WINDOW: Duration = Duration::from_secs(2);
+
+type NotificationResponse = Result<NotificationAction, String>;
+
+/// Actions offered by the command palette, each run by pressing its key binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    StartPause,
+    ResetTimer,
+    CycleSession,
+    ChoosePreset,
+    SwitchTask,
+    CountDownUntil,
+    ScheduleWork,
+    SnoozeBreak,
+    ToggleTicking,
+    ToggleStrict,
+    AddTask,
+    SearchTasks,
+    FilterByTag,
+    SyncTasks,
+    ArchiveTasks,
+    MergeDuplicates,
+    PickRandomTask,
+    ResetDay,
+    RestoreBackup,
+    NextSection,
+    PrevSection,
+    FocusTimer,
+    FocusTasks,
+    FocusHistory,
+    ToggleTasks,
+    CycleLayout,
+    ToggleDnd,
+    Help,
+    Quit,
+}
+
+impl Command {
+    pub const ALL: [Self; 29] = [
+        Self::StartPause,
+        Self::ResetTimer,
+        Self::CycleSession,
+        Self::ChoosePreset,
+        Self::SwitchTask,
+        Self::CountDownUntil,
+        Self::ScheduleWork,
+        Self::SnoozeBreak,
+        Self::ToggleTicking,
+        Self::ToggleStrict,
+        Self::AddTask,
+        Self::SearchTasks,
+        Self::FilterByTag,
+        Self::SyncTasks,
+        Self::ArchiveTasks,
+        Self::MergeDuplicates,
+        Self::PickRandomTask,
+        Self::ResetDay,
+        Self::RestoreBackup,
+        Self::NextSection,
+        Self::PrevSection,
+        Self::FocusTimer,
+        Self::FocusTasks,
+        Self::FocusHistory,
+        Self::ToggleTasks,
+        Self::CycleLayout,
+        Self::ToggleDnd,
+        Self::Help,
+        Self::Quit,
+    ];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::StartPause => "Start or pause timer",
+            Self::ResetTimer => "Reset timer",
+            Self::CycleSession => "Cycle session type",
+            Self::ChoosePreset => "Choose a preset",
+            Self::SwitchTask => "Change the active task",
+            Self::CountDownUntil => "Count down to a clock time",
+            Self::ScheduleWork => "Schedule a work session",
+            Self::SnoozeBreak => "Snooze the coming break",
+            Self::ToggleTicking => "Toggle ticking sound",
+            Self::ToggleStrict => "Toggle strict mode",
+            Self::AddTask => "Add task",
+            Self::SearchTasks => "Search tasks",
+            Self::FilterByTag => "Filter tasks by tag",
+            Self::SyncTasks => "Sync tasks with file",
+            Self::ArchiveTasks => "Archive old completed tasks",
+            Self::MergeDuplicates => "Merge duplicate tasks",
+            Self::PickRandomTask => "Start a random Backlog task",
+            Self::ResetDay => "Reset day: move Current back to Backlog",
+            Self::RestoreBackup => "Restore task file from a backup",
+            Self::NextSection => "Next task section",
+            Self::PrevSection => "Previous task section",
+            Self::FocusTimer => "Focus timer panel",
+            Self::FocusTasks => "Focus tasks panel",
+            Self::FocusHistory => "Focus history panel",
+            Self::ToggleTasks => "Toggle tasks panel visibility",
+            Self::CycleLayout => "Cycle layout preset",
+            Self::ToggleDnd => "Toggle do not disturb",
+            Self::Help => "Show help",
+            Self::Quit => "Quit",
+        }
+    }
+
+    /// The panel to focus before pressing the key, or None for app-wide keys
+    const fn binding(self) -> (Option<PanelId>, KeyCode) {
+        match self {
+            Self::StartPause => (Some(PanelId::Timer), KeyCode::Char(' ')),
+            Self::ResetTimer => (Some(PanelId::Timer), KeyCode::Char('r')),
+            Self::CycleSession => (Some(PanelId::Timer), KeyCode::Tab),
+            Self::ChoosePreset => (Some(PanelId::Timer), KeyCode::Char('p')),
+            Self::SwitchTask => (Some(PanelId::Timer), KeyCode::Char('c')),
+            Self::CountDownUntil => (Some(PanelId::Timer), KeyCode::Char('u')),
+            Self::ScheduleWork => (Some(PanelId::Timer), KeyCode::Char('@')),
+            Self::SnoozeBreak => (Some(PanelId::Timer), KeyCode::Char('z')),
+            Self::ToggleTicking => (Some(PanelId::Timer), KeyCode::Char('m')),
+            Self::ToggleStrict => (Some(PanelId::Timer), KeyCode::Char('!')),
+            Self::AddTask => (Some(PanelId::Tasks), KeyCode::Char('a')),
+            Self::SearchTasks => (Some(PanelId::Tasks), KeyCode::Char('/')),
+            Self::FilterByTag => (Some(PanelId::Tasks), KeyCode::Char('f')),
+            Self::SyncTasks => (Some(PanelId::Tasks), KeyCode::Char('s')),
+            Self::ArchiveTasks => (Some(PanelId::Tasks), KeyCode::Char('A')),
+            Self::MergeDuplicates => (Some(PanelId::Tasks), KeyCode::Char('M')),
+            Self::PickRandomTask => (Some(PanelId::Tasks), KeyCode::Char('r')),
+            Self::ResetDay => (Some(PanelId::Tasks), KeyCode::Char('R')),
+            Self::RestoreBackup => (Some(PanelId::Tasks), KeyCode::Char('B')),
+            Self::NextSection => (Some(PanelId::Tasks), KeyCode::Tab),
+            Self::PrevSection => (Some(PanelId::Tasks), KeyCode::BackTab),
+            Self::FocusTimer => (None, KeyCode::Char('1')),
+            Self::FocusTasks => (None, KeyCode::Char('2')),
+            Self::FocusHistory => (None, KeyCode::Char('3')),
+            Self::ToggleTasks => (None, KeyCode::Char('T')),
+            Self::CycleLayout => (None, KeyCode::Char('L')),
+            Self::ToggleDnd => (None, KeyCode::Char('D')),
+            Self::Help => (None, KeyCode::Char('?')),
+            Self::Quit => (None, KeyCode::Char('q')),
+        }
+    }
+}
 
 /// Main application state coordinating timer, tasks, panels, and overlays
 pub struct App {
     audio: Option<AudioPlayer>,
+    /// User Lua scripts, if `init.lua` exists and the `lua` feature is enabled
+    pub scripts: Option<ScriptEngine>,
     pub timer: Timer,
     pub focused_panel: PanelId,
     pub timer_panel: TimerPanel,
     pub tasks_panel: TasksPanel,
+    /// Shares the tasks column, shown while focused
+    pub history_panel: HistoryPanel,
+    /// Panels fed by external commands, stacked below the timer
+    pub plugin_panels: Vec<PluginPanel>,
+    /// Currently applied configuration
+    pub config: Config,
+    /// Reloads the config file when it changes on disk
+    pub config_watcher: Option<ConfigWatcher>,
     /// Error message displayed in overlay, if Some
     pub error_message: Option<String>,
+    /// Completed sessions, in memory only unless loaded from the history file
+    pub history: History,
+    /// Reasons given for pausing work sessions
+    pub interruptions: InterruptionLog,
+    /// Asks what to do next after a session finishes
+    pub session_complete: Option<SessionCompleteOverlay>,
+    /// The session in progress, kept on disk in case the app dies
+    pub session_file: SessionFile,
+    /// Asks what to do with the session the last run died during
+    pub recovery: Option<RecoveryOverlay>,
+    /// Asks what to do with a session the machine slept through
+    pub sleep_prompt: Option<SleptOverlay>,
+    /// Lists the configured presets to switch to
+    pub preset_picker: Option<PresetOverlay>,
+    /// Lists the Current tasks to pick the active one from
+    pub task_switcher: Option<TaskSwitcherOverlay>,
+    /// Asks what to do with unsynced task changes before quitting
+    pub quit_prompt: Option<QuitOverlay>,
+    /// Starts sessions at set times of day
+    pub scheduler: Scheduler,
+    /// Asks for a clock time to schedule a work session at
+    pub schedule_input: Option<UntilOverlay>,
+    /// Asks for a clock time to count down to
+    pub until_input: Option<UntilOverlay>,
+    /// Asks why the work session was just paused
+    pub pause_reason: Option<PauseReasonOverlay>,
+    /// Runs a command picked by name
+    pub command_palette: Option<CommandPaletteOverlay>,
+    /// Transient non-modal message, cleared once expired
+    pub toast: Option<Toast>,
+    /// Actions clicked on completion notifications, sent from their background threads
+    notification_tx: Sender<NotificationResponse>,
+    notification_rx: Receiver<NotificationResponse>,
+    notification_serial: NotificationSerial,
+    /// Session that last completed, resumed by the extend notification action
+    last_completed: Option<SessionType>,
+    /// Do not disturb: mute sounds and desktop notifications while the timer keeps running
+    pub dnd: bool,
+    /// Tick quietly while the timer runs
+    pub ticking: bool,
+    /// Work sessions can't be paused and reset needs pressing twice
+    pub strict: bool,
+    /// Strict mode from a config reload mid-session, applied once the timer is idle
+    pending_strict: Option<bool>,
+    /// When reset was first pressed in strict mode, awaiting the second press
+    reset_pressed_at: Option<Instant>,
+    /// Watches for the user stepping away, if `timer.idle_pause` is set
+    idle_monitor: Option<IdleMonitor>,
+    /// The running session was paused because the user was away
+    paused_while_away: bool,
+    /// Counts unbroken work towards the next reminder to look away, if `timer.eye_breaks` is set
+    eye_breaks: Option<EyeBreaks>,
     /// Whether the shortcuts
     pub shortcuts_visible: bool,
+    /// First help row shown, clamped to the content on each render
+    pub help_scroll: usize,
     /// Whether the tasks panel is visible
     pub tasks_visible: bool,
     /// Whether in two column or single column layout
     pub two_columns: bool,
+    /// How the timer and tasks columns are arranged
+    pub layout_preset: LayoutPreset,
+    /// Last terminal width, to recompute the columns when the preset changes
+    width: u16,
+    /// Where the tasks column was last drawn, to route mouse wheel events
+    pub tasks_area: Option<Rect>,
     /// Flag to trigger application exit
     pub should_quit: bool,
 }
 
 impl App {
-    pub fn new(task_file: Option<PathBuf>) -> Self {
-        let (tasks_panel, error_message) = TasksPanel::from_file(task_file);
+    pub fn new(task_file: Option<PathBuf>, config: Config) -> Self {
+        let plugin_panels = config
+            .plugins
+            .iter()
+            .cloned()
+            .map(PluginPanel::new)
+            .collect();
+        let mut timer_panel = TimerPanel::default();
+        timer_panel.set_display(config.display);
+        let mut timer = Timer::default();
+        let (work, short_break, long_break) = config.timer.durations();
+        timer.set_durations(work, short_break, long_break);
+        timer.set_max_duration(config.timer.max_session());
+        timer.set_overtime(config.timer.overtime);
+        timer.set_cues(config.sound.halfway_chime, config.sound.warn_before_end());
+        timer.set_flow_break_divisor(config.timer.flow_break_divisor);
+        timer.set_sleep_policy(config.timer.on_sleep);
+        timer.set_grace_period(config.timer.grace_period());
+        timer.set_sequence(config.timer.sequence());
+
+        let (notification_tx, notification_rx) = mpsc::channel();
 
-        Self {
+        let mut app = Self {
             should_quit: false,
-            timer: Timer::default(),
-            timer_panel: TimerPanel::default(),
-            tasks_panel,
+            strict: config.timer.strict,
+            pending_strict: None,
+            scheduler: Scheduler::new(config.schedule.clone()),
+            eye_breaks: config.timer.eye_breaks.then(EyeBreaks::default),
+            idle_monitor: (config.timer.idle_pause > 0)
+                .then(IdleMonitor::new)
+                .flatten(),
+            timer,
+            timer_panel,
+            tasks_panel: TasksPanel::default(),
+            history_panel: HistoryPanel::default(),
+            plugin_panels,
+            config,
+            config_watcher: None,
             focused_panel: PanelId::Timer,
             tasks_visible: true,
             shortcuts_visible: false,
+            help_scroll: 0,
             two_columns: false,
-            error_message,
+            layout_preset: LayoutPreset::default(),
+            width: 0,
+            tasks_area: None,
+            error_message: None,
+            history: History::default(),
+            interruptions: InterruptionLog::default(),
+            session_complete: None,
+            session_file: SessionFile::default(),
+            recovery: None,
+            sleep_prompt: None,
+            preset_picker: None,
+            task_switcher: None,
+            quit_prompt: None,
+            schedule_input: None,
+            until_input: None,
+            pause_reason: None,
+            command_palette: None,
+            toast: None,
+            dnd: false,
+            ticking: false,
+            reset_pressed_at: None,
+            paused_while_away: false,
+            notification_tx,
+            notification_rx,
+            notification_serial: NotificationSerial::default(),
+            last_completed: None,
             audio: AudioPlayer::new(),
+            scripts: None,
+        };
+        app.load_tasks(task_file, None);
+        app
+    }
+
+    /// Open the task file at `path`, or go without one until a task is added, locking it
+    /// through a lock file in `locks` so a second instance opens it read-only
+    pub fn load_tasks(&mut self, path: Option<PathBuf>, locks: Option<PathBuf>) {
+        let (mut tasks_panel, error) = TasksPanel::from_file(path, locks);
+        tasks_panel.set_config(self.config.tasks);
+        self.tasks_panel = tasks_panel;
+        self.error_message = self.error_message.take().or(error);
+        // Straight to asking whether to edit a task file another instance has open
+        if self.tasks_panel.is_read_only() {
+            self.focused_panel = PanelId::Tasks;
         }
     }
 
     /// Ticks the timer countdown and animation counter, notifying on session completion
     pub fn tick(&mut self) {
-        let session_completed = self.timer.tick();
-
-        if session_completed {
-            if let Some(ref audio) = self.audio {
-                // After completion the timer has already transitioned to the next session type.
-                // If the new session is a break, a work session just finished → play the fanfare.
-                if matches!(
-                    self.timer.session_type(),
-                    SessionType::ShortBreak | SessionType::LongBreak
-                ) {
-                    audio.play_melody(VICTORY_FANFARE);
-                } else {
-                    audio.play_melody(TWO_TONE);
+        if self.timer.is_idle() {
+            if let Some(strict) = self.pending_strict.take() {
+                self.strict = strict;
+            }
+        }
+        let session = self.timer.session_type();
+        if let Some(event) = self.timer.tick() {
+            let now = Local::now().time();
+            let alerts = notification_policy::resolve(&self.config, session, now, self.dnd);
+            match event {
+                TimerEvent::Completed => {
+                    if alerts.sound {
+                        self.play_completion_melody();
+                    }
+                    if alerts.desktop {
+                        self.send_completion_notification();
+                    }
+                    self.record_completed_session(session);
+                }
+                TimerEvent::OvertimeStarted => {
+                    if alerts.sound {
+                        if let Some(ref audio) = self.audio {
+                            audio.play_melody(TWO_TONE);
+                        }
+                    }
+                    if alerts.desktop {
+                        self.notify("Time's up! Counting overtime until you stop");
+                    }
+                }
+                TimerEvent::Slept(slept) => self.sleep_prompt = Some(SleptOverlay::new(slept)),
+                TimerEvent::GraceOver => {
+                    if alerts.sound {
+                        if let Some(ref audio) = self.audio {
+                            audio.play_melody(GET_READY);
+                        }
+                    }
+                    // The next session started, so only the task question is left to answer
+                    self.session_complete.take_if(|o| !o.asks_about_task());
+                }
+                TimerEvent::Halfway | TimerEvent::EndingSoon => {
+                    if alerts.sound {
+                        if let Some(ref audio) = self.audio {
+                            audio.play_soft(SOFT_CHIME);
+                        }
+                    }
                 }
             }
-            if let Some(err) = send_notification("Pomo-TUI", "Session completed!") {
-                self.error_message = Some(err);
+        }
+
+        // Left alone until the interrupted session is dealt with
+        if self.recovery.is_none() {
+            let task = self.tasks_panel.active_task().map(|t| t.text.as_str());
+            let snapshot = SessionSnapshot::of(&self.timer, task);
+            if let Err(e) = self.session_file.update(snapshot) {
+                self.error_message = Some(format!("Failed to save session: {e}"));
+            }
+        }
+
+        let task_duration = self.tasks_panel.active_task().and_then(Task::duration);
+        self.timer.set_task_duration(task_duration);
+
+        for entry in self.scheduler.tick(Local::now().naive_local()) {
+            self.start_scheduled(&entry);
+        }
+
+        let working = (self.timer.is_running() || self.timer.is_overtime())
+            && self.timer.session_type() == SessionType::Work;
+        if let Some(ref mut eye_breaks) = self.eye_breaks {
+            if eye_breaks.tick(working, Instant::now()) {
+                self.toast = Some(Toast::new(
+                    "👀 Look at something 20 feet away for 20 seconds".to_string(),
+                ));
             }
         }
 
+        if let Some(presence) = self.idle_monitor.as_mut().and_then(IdleMonitor::poll) {
+            self.handle_presence(presence);
+        }
+
+        let ticking =
+            self.ticking && self.timer.is_running() && self.config.sound.enabled && !self.dnd;
+        if let Some(ref mut audio) = self.audio {
+            audio.set_ticking(ticking);
+        }
+
+        while let Ok(response) = self.notification_rx.try_recv() {
+            match response {
+                Ok(action) => self.apply_notification_action(action),
+                Err(e) => self.error_message = Some(e),
+            }
+        }
+
+        for panel in &mut self.plugin_panels {
+            panel.tick();
+        }
+
+        if let Err(e) = self.tasks_panel.auto_save() {
+            self.error_message = Some(e);
+        }
+        self.auto_sync_tasks();
+
+        if let Some(result) = self.config_watcher.as_mut().and_then(ConfigWatcher::poll) {
+            match result {
+                Ok(config) => self.apply_config(&config),
+                Err(e) => self.error_message = Some(e),
+            }
+        }
+
+        self.toast.take_if(|t| t.is_expired());
+
         self.timer_panel.next_animation_frame();
     }
 
+    /// Sync the tasks with their file in the background, asking about any conflicts
+    fn auto_sync_tasks(&mut self) {
+        // The overlay only takes keys with the panel focused, so it waits for `s` otherwise
+        let focused = self.focused_panel == PanelId::Tasks;
+        match self.tasks_panel.auto_sync(focused) {
+            Ok(true) if !focused => {
+                self.toast = Some(Toast::new(
+                    "Tasks changed in both the app and the file: press s in Tasks to sync"
+                        .to_string(),
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    /// Complete the active task, toasting any Backlog task promoted to take its place
+    fn complete_current_task(&mut self) {
+        if let Some(text) = self.tasks_panel.complete_current_task() {
+            self.toast = Some(Toast::new(format!("Up next: {text}")));
+        }
+    }
+
+    /// Start a session that came due, or set it up and ask, if the timer isn't in use
+    fn start_scheduled(&mut self, entry: &ScheduleEntry) {
+        let label = entry.session.label();
+        let at = entry.at.format("%H:%M");
+        if !self.timer.is_idle() {
+            self.toast = Some(Toast::new(format!(
+                "Skipped {label} scheduled for {at}, the timer is busy"
+            )));
+            return;
+        }
+        self.timer.set_session_type(entry.session);
+        let message = if entry.auto_start {
+            self.timer.start();
+            format!("Started {label} scheduled for {at}")
+        } else {
+            format!("{label} scheduled for {at}, press space to start")
+        };
+        self.notify(&message);
+        self.toast = Some(Toast::new(message));
+    }
+
+    /// Ask about the session the last run died during, if any
+    pub fn offer_recovery(&mut self) {
+        self.recovery = self.session_file.saved().cloned().map(RecoveryOverlay::new);
+    }
+
+    fn apply_recovery_choice(&mut self, choice: RecoveryChoice, snapshot: &SessionSnapshot) {
+        let session = snapshot.session_type();
+        match choice {
+            RecoveryChoice::Resume => {
+                let remaining = snapshot.remaining(Local::now());
+                let elapsed = snapshot.length().saturating_sub(remaining);
+                self.timer.restore(session, elapsed, remaining);
+                if snapshot.ends_at.is_some() {
+                    self.timer.start();
+                }
+            }
+            RecoveryChoice::Reset => {}
+            RecoveryChoice::Log => {
+                let record = SessionRecord {
+                    end: Local::now(),
+                    session,
+                    duration: snapshot.length(),
+                    interruptions: 0,
+                    task: snapshot.task.clone(),
+                    paused_at: Vec::new(),
+                };
+                if let Err(e) = self.history.record(record) {
+                    self.error_message = Some(format!("Failed to save history: {e}"));
+                }
+            }
+        }
+    }
+
+    /// Pause work when the user has been away past `timer.idle_pause`, then resume or ask on return
+    fn handle_presence(&mut self, presence: Presence) {
+        let away = presence.locked || presence.idle >= self.config.timer.idle_pause();
+        if away {
+            if self.timer.is_running() && self.timer.session_type() == SessionType::Work {
+                self.timer.pause();
+                // Time since the last input wasn't spent working
+                self.timer.rewind(presence.idle);
+                self.paused_while_away = true;
+            }
+        } else if self.paused_while_away {
+            self.paused_while_away = false;
+            if !self.timer.is_paused() {
+                return;
+            }
+            let message = if self.config.timer.idle_resume {
+                self.timer.start();
+                "Welcome back, work resumed"
+            } else {
+                "Paused while you were away, press space to resume"
+            };
+            self.toast = Some(Toast::new(message.to_string()));
+        }
+    }
+
+    /// Play the fanfare after work, or the two-tone chime after a break
+    fn play_completion_melody(&self) {
+        if let Some(ref audio) = self.audio {
+            // After completion the timer has already transitioned to the next session type.
+            // If the new session is a break, a work session just finished → play the fanfare.
+            if matches!(
+                self.timer.session_type(),
+                SessionType::ShortBreak | SessionType::LongBreak
+            ) {
+                audio.play_melody(VICTORY_FANFARE);
+            } else {
+                audio.play_melody(TWO_TONE);
+            }
+        }
+    }
+
+    fn send_completion_notification(&mut self) {
+        let backend = self.config.notifications.backend;
+        if backend != NotificationBackend::NotifySend {
+            if let Some(err) = send_notification_via(backend, "Pomo-TUI", "Session completed!") {
+                self.error_message = Some(err);
+            }
+            return;
+        }
+        let start_label = match self.timer.session_type() {
+            SessionType::Work => "Start work",
+            SessionType::ShortBreak | SessionType::LongBreak => "Start break",
+        };
+        send_actionable_notification(
+            "Pomo-TUI",
+            "Session completed!",
+            start_label,
+            &self.notification_serial,
+            self.notification_tx.clone(),
+        );
+    }
+
+    /// Log a finished session to history and ask what comes next
+    fn record_completed_session(&mut self, session: SessionType) {
+        self.last_completed = Some(session);
+        // Work done in place of a snoozed break belongs to the pomodoro already recorded
+        let snoozed = session == SessionType::Work && self.timer.snoozes() > 0;
+        let task = (session == SessionType::Work && !snoozed)
+            .then(|| self.tasks_panel.active_task().map(|t| t.text.clone()))
+            .flatten();
+        if !snoozed {
+            let record = SessionRecord {
+                end: Local::now(),
+                session,
+                duration: self.timer.last_elapsed(),
+                interruptions: self.timer.last_pauses(),
+                task: task.clone(),
+                paused_at: self.timer.last_paused_at().to_vec(),
+            };
+            if let Err(e) = self.history.record(record) {
+                self.error_message = Some(format!("Failed to save history: {e}"));
+            }
+        }
+        let goal = self.config.timer.daily_goal;
+        if session == SessionType::Work && !snoozed && goal > 0 && self.pomodoros_today() == goal {
+            self.toast = Some(Toast::new(format!(
+                "🎉 Daily goal reached: {goal} pomodoros"
+            )));
+        }
+        self.session_complete = Some(SessionCompleteOverlay::new(
+            session,
+            self.timer.session_type(),
+            self.timer.minutes(),
+            self.timer.sessions_completed(),
+            task,
+        ));
+
+        self.emit_script_event(&ScriptEvent::SessionCompleted(session));
+    }
+
+    fn log_interruption(&mut self, reason: String, at: DateTime<Local>) {
+        let interruption = Interruption {
+            at,
+            reason,
+            task: self.tasks_panel.active_task().map(|t| t.text.clone()),
+        };
+        if let Err(e) = self.interruptions.record(interruption) {
+            self.error_message = Some(format!("Failed to save interruption: {e}"));
+        }
+    }
+
+    /// Run the current session until `time` today
+    fn count_down_until(&mut self, time: NaiveTime) {
+        let target = Local::now()
+            .date_naive()
+            .and_time(time)
+            .and_local_timezone(Local)
+            .earliest();
+        if !target.is_some_and(|target| self.timer.set_target_time(target)) {
+            self.toast = Some(Toast::new(format!(
+                "Can't count down to {}",
+                time.format("%H:%M")
+            )));
+        }
+    }
+
+    /// Respond to a notification button, unless the timer was already started from the app
+    fn apply_notification_action(&mut self, action: NotificationAction) {
+        if !self.timer.is_idle() {
+            return;
+        }
+        self.session_complete = None;
+        self.apply_session_choice(match action {
+            NotificationAction::StartNext => SessionChoice::StartNext,
+            NotificationAction::Skip => SessionChoice::Skip,
+            NotificationAction::Extend => SessionChoice::Extend,
+        });
+    }
+
+    fn apply_session_choice(&mut self, choice: SessionChoice) {
+        match choice {
+            SessionChoice::StartNext => self.timer.start(),
+            SessionChoice::Skip => self.timer.skip_session(),
+            SessionChoice::Extend => {
+                if let Some(session) = self.last_completed {
+                    self.timer.extend_session(session, EXTEND_DURATION);
+                }
+            }
+            SessionChoice::Snooze => self.snooze(),
+        }
+    }
+
+    /// Put off the coming break, logging it like an interruption
+    fn snooze(&mut self) {
+        let label = self.timer.session_type().label();
+        let message = if let Some(snoozes) = self.timer.snooze_break() {
+            self.log_interruption(
+                format!("Snoozed {} ({snoozes}/{MAX_SNOOZES})", label.to_lowercase()),
+                Local::now(),
+            );
+            format!(
+                "{label} snoozed for {} min ({snoozes}/{MAX_SNOOZES})",
+                SNOOZE_LENGTH.as_secs() / 60
+            )
+        } else if self.timer.session_type() == SessionType::Work {
+            "No break to snooze".to_string()
+        } else if self.timer.snoozes() >= MAX_SNOOZES {
+            format!("{label} already snoozed {MAX_SNOOZES} times")
+        } else {
+            format!("Too late to snooze this {}", label.to_lowercase())
+        };
+        self.toast = Some(Toast::new(message));
+    }
+
+    /// Apply a reloaded config, toasting what changed and what still needs a restart
+    fn apply_config(&mut self, config: &Config) {
+        let changes = self.config.changes(config);
+        if changes.is_empty() {
+            return;
+        }
+
+        // Only what changed is passed on, so a reload keeps the countdown and any preset
+        let (old, new) = (&self.config.timer, &config.timer);
+        if new.durations() != old.durations() {
+            let (work, short_break, long_break) = new.durations();
+            self.timer.set_durations(work, short_break, long_break);
+        }
+        if new.max_session != old.max_session {
+            self.timer.set_max_duration(new.max_session());
+        }
+        if new.overtime != old.overtime {
+            self.timer.set_overtime(new.overtime);
+        }
+        if config.sound.halfway_chime != self.config.sound.halfway_chime
+            || config.sound.warn_before_end != self.config.sound.warn_before_end
+        {
+            self.timer
+                .set_cues(config.sound.halfway_chime, config.sound.warn_before_end());
+        }
+        if new.flow_break_divisor != old.flow_break_divisor {
+            self.timer.set_flow_break_divisor(new.flow_break_divisor);
+        }
+        if new.on_sleep != old.on_sleep {
+            self.timer.set_sleep_policy(new.on_sleep);
+        }
+        if new.grace_period != old.grace_period {
+            self.timer.set_grace_period(new.grace_period());
+        }
+        if config.timer.sequence != self.config.timer.sequence {
+            self.timer.set_sequence(config.timer.sequence());
+        }
+
+        if config.schedule != self.config.schedule {
+            self.scheduler.set_daily(config.schedule.clone());
+        }
+        if config.timer.idle_pause != self.config.timer.idle_pause {
+            self.idle_monitor = (config.timer.idle_pause > 0)
+                .then(IdleMonitor::new)
+                .flatten();
+        }
+        if config.timer.eye_breaks != self.config.timer.eye_breaks {
+            self.eye_breaks = config.timer.eye_breaks.then(EyeBreaks::default);
+        }
+        if config.timer.strict != self.config.timer.strict {
+            if self.timer.is_idle() {
+                self.strict = config.timer.strict;
+                self.pending_strict = None;
+            } else {
+                self.pending_strict = Some(config.timer.strict);
+            }
+        }
+
+        // Plugins keep running as configured at startup until restart
+        self.config.theme = config.theme;
+        self.config.schedule.clone_from(&config.schedule);
+        self.config.timer = config.timer.clone();
+        self.config.sound = config.sound;
+        self.config.notifications = config.notifications;
+        self.config.tasks = config.tasks;
+        self.tasks_panel.set_config(config.tasks);
+        self.config.display = config.display;
+        self.timer_panel.set_display(config.display);
+
+        self.toast = Some(Toast::new(changes.summary()));
+    }
+
+    /// Work sessions finished today, including before a restart when history is saved
+    pub fn pomodoros_today(&self) -> u32 {
+        self.history.pomodoros_on(Local::now().date_naive())
+    }
+
+    /// Work time today, including the session in progress
+    pub fn focused_today(&self) -> Duration {
+        let in_progress = if self.timer.session_type() == SessionType::Work {
+            self.timer.elapsed()
+        } else {
+            Duration::ZERO
+        };
+        self.history.focused_on(Local::now().date_naive()) + in_progress
+    }
+
+    /// Send a desktop notification unless do not disturb or quiet hours are on
+    fn notify(&mut self, message: &str) {
+        if self.dnd || self.config.notifications.is_quiet(Local::now().time()) {
+            return;
+        }
+        if let Some(err) =
+            send_notification_via(self.config.notifications.backend, "Pomo-TUI", message)
+        {
+            self.error_message = Some(err);
+        }
+    }
+
+    fn script_state(&self) -> ScriptState {
+        ScriptState {
+            session: self.timer.session_type(),
+            running: self.timer.is_running(),
+            remaining_secs: self.timer.minutes() * 60 + self.timer.seconds(),
+            active_task: self.tasks_panel.active_task().map(|t| t.text.clone()),
+        }
+    }
+
+    fn emit_script_event(&mut self, event: &ScriptEvent) {
+        let Some(ref scripts) = self.scripts else {
+            return;
+        };
+        match scripts.emit(event, &self.script_state()) {
+            Ok(actions) => self.apply_script_actions(actions),
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    /// Run a script key binding, returning whether a script handled the key
+    fn handle_script_key(&mut self, event: &Event) -> bool {
+        let Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return false;
+        };
+        let Some(ref scripts) = self.scripts else {
+            return false;
+        };
+        match scripts.handle_key(*c, &self.script_state()) {
+            Ok(Some(actions)) => {
+                self.apply_script_actions(actions);
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                self.error_message = Some(e);
+                true
+            }
+        }
+    }
+
+    fn apply_script_actions(&mut self, actions: Vec<ScriptAction>) {
+        for action in actions {
+            match action {
+                ScriptAction::StartTimer => self.timer.start(),
+                ScriptAction::PauseTimer => self.timer.pause(),
+                ScriptAction::ResetTimer => self.timer.reset(),
+                ScriptAction::AddTask(text, section) => self.tasks_panel.add_task(&text, section),
+                ScriptAction::Notify(message) => self.notify(&message),
+            }
+        }
+    }
+
     fn toggle_tasks_visibility(&mut self) {
         self.tasks_visible = !self.tasks_visible;
 
@@ -77,18 +887,55 @@ impl App {
             self.focused_panel = PanelId::Tasks;
         }
 
-        if !self.tasks_visible && self.focused_panel == PanelId::Tasks {
+        if !self.tasks_visible && self.focused_panel != PanelId::Timer {
             self.focused_panel = PanelId::Timer;
         }
     }
 
+    /// Focus a panel by its number, showing the tasks column first if the panel lives there
+    fn focus_number(&mut self, n: char) {
+        if let Some(panel) = PanelId::from_number(n) {
+            self.focus_panel(panel);
+        }
+    }
+
+    fn focus_panel(&mut self, panel: PanelId) {
+        if panel != PanelId::Timer && !self.tasks_visible {
+            self.tasks_visible = true;
+            // Keys handled before the next draw see the columns that will be shown
+            self.compute_column_layout(self.width);
+        }
+        self.focused_panel = panel;
+    }
+
+    /// Press the command's key, in the panel it belongs to
+    fn run_command(&mut self, command: Command) {
+        let (panel, code) = command.binding();
+        if let Some(panel) = panel {
+            self.focus_panel(panel);
+        }
+        self.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+
     /// Compute the column layout based on terminal width
     pub fn compute_column_layout(&mut self, width: u16) {
-        self.two_columns = self.tasks_visible && (width / 2) >= TIMER_MIN_WIDTH;
+        self.width = width;
+        let timer_width = match self.layout_preset {
+            LayoutPreset::TimerOnly => 0,
+            LayoutPreset::Split => width / 2,
+            LayoutPreset::TasksDominant => width * 3 / 10,
+            LayoutPreset::Stacked => width,
+        };
+        self.two_columns = self.tasks_visible && timer_width >= TIMER_MIN_WIDTH;
     }
 
     /// Handle a terminal event
     pub fn handle(&mut self, event: &Event) {
+        if let Event::Resize(width, _) = event {
+            self.compute_column_layout(*width);
+            return;
+        }
+
         if self.error_message.is_some() {
             if matches!(
                 event,
@@ -102,44 +949,307 @@ impl App {
             return;
         }
 
+        if self.handle_overlay(event) {
+            return;
+        }
+
+        if let Event::Mouse(mouse) = event {
+            self.handle_mouse(*mouse);
+            return;
+        }
+
         if self.shortcuts_visible {
             if let Event::Key(KeyEvent {
-                code: KeyCode::Char('?') | KeyCode::Esc,
+                code,
                 kind: KeyEventKind::Press,
                 ..
             }) = event
             {
-                self.shortcuts_visible = false;
+                match code {
+                    KeyCode::Char('?') | KeyCode::Esc => self.shortcuts_visible = false,
+                    KeyCode::Char('j') | KeyCode::Down => self.help_scroll += 1,
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        self.help_scroll = self.help_scroll.saturating_sub(1);
+                    }
+                    _ => {}
+                }
             }
             return;
         }
 
-        let consumed = if self.focused_panel == PanelId::Tasks {
-            let consumed = self.tasks_panel.handle(event);
+        // Keys a panel binds without modifiers match with Ctrl held too, so the palette is
+        // opened here, before the tasks panel's `p` can take it, unless it is mid-way through
+        // something
+        let tasks_busy = self.focused_panel == PanelId::Tasks && self.tasks_panel.overlay_open();
+        if !tasks_busy
+            && matches!(
+                event,
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('p'),
+                    modifiers,
+                    kind: KeyEventKind::Press,
+                    ..
+                }) if modifiers.contains(KeyModifiers::CONTROL)
+            )
+        {
+            self.open_command_palette();
+            return;
+        }
+
+        let consumed = match self.focused_panel {
+            PanelId::Tasks => {
+                let consumed = self.tasks_panel.handle(event);
+
+                if let Some(error) = self.tasks_panel.take_error() {
+                    self.error_message = Some(error);
+                }
+                if let Some(message) = self.tasks_panel.take_message() {
+                    self.toast = Some(Toast::new(message));
+                }
+                for text in self.tasks_panel.take_added_tasks() {
+                    self.emit_script_event(&ScriptEvent::TaskAdded(text));
+                }
 
-            if let Some(error) = self.tasks_panel.take_error() {
-                self.error_message = Some(error);
+                consumed
             }
+            PanelId::History => self.history_panel.handle(event),
+            PanelId::Timer => false,
+        };
 
-            consumed
-        } else {
-            false
+        if !consumed && !KeyMap::handle(self, event) {
+            self.handle_script_key(event);
+        }
+    }
+
+    /// Pass the event to the session complete prompt, recording how the task went and
+    /// starting the chosen session once answered
+    fn handle_session_complete(&mut self, event: &Event) {
+        let Some(ref mut overlay) = self.session_complete else {
+            return;
+        };
+        KeyMap::handle(overlay, event);
+        let outcome = overlay.take_task_outcome();
+        if matches!(outcome, Some(TaskOutcome::Finished | TaskOutcome::Partial)) {
+            if let Err(e) = self.tasks_panel.record_pomodoro() {
+                self.error_message = Some(e);
+            }
+        }
+        if outcome == Some(TaskOutcome::Finished) {
+            self.complete_current_task();
+        }
+        if let Some(overlay) = self.session_complete.take_if(|o| o.is_done()) {
+            match overlay.result() {
+                Some(choice) => self.apply_session_choice(choice),
+                // Closing without starting also stops the countdown to the next session
+                None => self.timer.cancel_grace(),
+            }
+        }
+    }
+
+    /// Quit once answered, first writing unsynced task changes to the file if asked; a
+    /// failed write keeps the app open so the changes aren't lost
+    fn handle_quit_prompt(&mut self, event: &Event) {
+        let Some(ref mut overlay) = self.quit_prompt else {
+            return;
+        };
+        KeyMap::handle(overlay, event);
+        let Some(overlay) = self.quit_prompt.take_if(|o| o.is_done()) else {
+            return;
+        };
+        match overlay.result() {
+            Some(QuitChoice::Sync) => match self.tasks_panel.save() {
+                Ok(()) => self.should_quit = true,
+                Err(e) => self.error_message = Some(e),
+            },
+            Some(QuitChoice::Discard) => self.should_quit = true,
+            None => {}
+        }
+    }
+
+    /// Pass the event to the open prompt, if any, applying its answer once done
+    fn handle_overlay(&mut self, event: &Event) -> bool {
+        if self.session_complete.is_some() {
+            self.handle_session_complete(event);
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.pause_reason {
+            overlay.handle(event);
+            if let Some(overlay) = self.pause_reason.take_if(|o| o.is_done()) {
+                if let Some(reason) = overlay.result() {
+                    // Logged at the pause itself, so the history finds it with its session
+                    let at = self.timer.paused_at().unwrap_or_else(Local::now);
+                    self.log_interruption(reason, at);
+                }
+            }
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.recovery {
+            KeyMap::handle(overlay, event);
+            if let Some(overlay) = self.recovery.take_if(|o| o.is_done()) {
+                if let Some(choice) = overlay.result() {
+                    self.apply_recovery_choice(choice, overlay.snapshot());
+                }
+            }
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.sleep_prompt {
+            KeyMap::handle(overlay, event);
+            if let Some(overlay) = self.sleep_prompt.take_if(|o| o.is_done()) {
+                match overlay.result() {
+                    Some(SleepChoice::Resume) => self.timer.start(),
+                    Some(SleepChoice::Reset) => self.timer.reset(),
+                    Some(SleepChoice::Count) => {
+                        self.timer.fast_forward(overlay.slept());
+                        self.timer.start();
+                    }
+                    None => {}
+                }
+            }
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.preset_picker {
+            KeyMap::handle(overlay, event);
+            if let Some(overlay) = self.preset_picker.take_if(|o| o.is_done()) {
+                if let Some(preset) = overlay.result() {
+                    if self.timer.apply_preset(preset) {
+                        self.toast = Some(Toast::new(format!("Preset: {}", preset.name)));
+                    }
+                }
+            }
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.task_switcher {
+            KeyMap::handle(overlay, event);
+            if let Some(overlay) = self.task_switcher.take_if(|o| o.is_done()) {
+                if let Some(index) = overlay.result() {
+                    self.tasks_panel.activate_task(index);
+                }
+            }
+            return true;
+        }
+
+        if self.quit_prompt.is_some() {
+            self.handle_quit_prompt(event);
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.schedule_input {
+            overlay.handle(event);
+            if let Some(overlay) = self.schedule_input.take_if(|o| o.is_done()) {
+                if let Some(at) = overlay.result() {
+                    self.scheduler.add_once(ScheduleEntry {
+                        at,
+                        session: SessionType::Work,
+                        auto_start: true,
+                    });
+                    self.toast = Some(Toast::new(format!(
+                        "Work session scheduled for {}",
+                        at.format("%H:%M")
+                    )));
+                }
+            }
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.until_input {
+            overlay.handle(event);
+            if let Some(overlay) = self.until_input.take_if(|o| o.is_done()) {
+                if let Some(time) = overlay.result() {
+                    self.count_down_until(time);
+                }
+            }
+            return true;
+        }
+
+        if let Some(ref mut overlay) = self.command_palette {
+            overlay.handle(event);
+            if let Some(overlay) = self.command_palette.take_if(|o| o.is_done()) {
+                if let Some(&command) = overlay.result().and_then(|i| Command::ALL.get(i)) {
+                    self.run_command(command);
+                }
+            }
+            return true;
+        }
+
+        false
+    }
+
+    /// Scroll the help or sync overlay, or the list under the pointer, as `j`/`k` would
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let code = match mouse.kind {
+            MouseEventKind::ScrollDown => KeyCode::Char('j'),
+            MouseEventKind::ScrollUp => KeyCode::Char('k'),
+            _ => return,
         };
+        if self.shortcuts_visible {
+            self.help_scroll = if code == KeyCode::Char('j') {
+                self.help_scroll + 1
+            } else {
+                self.help_scroll.saturating_sub(1)
+            };
+            return;
+        }
+        // Letters would be typed into the text field
+        if self.tasks_panel.task_input_overlay().is_some()
+            || self.tasks_panel.note_overlay().is_some()
+            || self.tasks_panel.is_searching()
+        {
+            return;
+        }
 
-        if !consumed {
-            KeyMap::handle(self, event);
+        let key = Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        if self.tasks_panel.sync_overlay().is_some()
+            || self.tasks_panel.tag_filter_overlay().is_some()
+            || self.tasks_panel.project_overlay().is_some()
+            || self.tasks_panel.duplicates_overlay().is_some()
+            || self.tasks_panel.url_picker().is_some()
+            || self.tasks_panel.backup_overlay().is_some()
+        {
+            self.tasks_panel.handle(&key);
+            return;
+        }
+        let over_tasks = self
+            .tasks_area
+            .is_some_and(|area| area.contains(Position::new(mouse.column, mouse.row)));
+        if !over_tasks {
+            return;
+        }
+        match self.focused_panel {
+            PanelId::History => {
+                self.history_panel.handle(&key);
+            }
+            PanelId::Timer | PanelId::Tasks => {
+                self.focused_panel = PanelId::Tasks;
+                self.tasks_panel.handle(&key);
+            }
         }
     }
 }
 
 #[keymap(backend = "crossterm")]
 impl App {
+    /// Open the command palette
+    // Ahead of `p`, which also matches with Ctrl held
+    #[keybind(pressed(key=KeyCode::Char('p'), modifiers=KeyModifiers::CONTROL))]
+    fn open_command_palette(&mut self) {
+        let labels = Command::ALL.iter().map(|c| c.label()).collect();
+        self.command_palette = Some(CommandPaletteOverlay::new(labels));
+    }
+
     /// Quit
     #[keybind(pressed(key=KeyCode::Char('q')))]
     #[keybind(pressed(key=KeyCode::Char('Q')))]
     #[keybind(pressed(key=KeyCode::Esc))]
     fn quit(&mut self) {
-        self.should_quit = true;
+        match self.tasks_panel.unsynced_changes() {
+            0 => self.should_quit = true,
+            changes => self.quit_prompt = Some(QuitOverlay::new(changes)),
+        }
     }
 
     /// Toggle tasks panel visibility
@@ -154,22 +1264,110 @@ impl App {
         if self.tasks_visible {
             self.focused_panel = match self.focused_panel {
                 PanelId::Timer => PanelId::Tasks,
-                PanelId::Tasks => PanelId::Timer,
+                PanelId::Tasks => PanelId::History,
+                PanelId::History => PanelId::Timer,
             };
         }
     }
 
+    /// Focus timer panel
+    #[keybind(pressed(key=KeyCode::Char('1')))]
+    fn focus_timer(&mut self) {
+        self.focus_number('1');
+    }
+
+    /// Focus tasks panel
+    #[keybind(pressed(key=KeyCode::Char('2')))]
+    fn focus_tasks(&mut self) {
+        self.focus_number('2');
+    }
+
+    /// Focus history panel
+    #[keybind(pressed(key=KeyCode::Char('3')))]
+    fn focus_history(&mut self) {
+        self.focus_number('3');
+    }
+
+    /// Cycle layout preset
+    #[keybind(pressed(key=KeyCode::Char('L')))]
+    fn cycle_layout(&mut self) {
+        self.layout_preset = self.layout_preset.next();
+        self.compute_column_layout(self.width);
+        if !self.two_columns && self.focused_panel != PanelId::Timer {
+            self.focused_panel = PanelId::Timer;
+        }
+        self.toast = Some(Toast::new(format!(
+            "Layout: {}",
+            self.layout_preset.label()
+        )));
+    }
+
     /// Toggle help overlay
     #[keybind(pressed(key=KeyCode::Char('?')))]
     fn toggle_help(&mut self) {
         self.shortcuts_visible = !self.shortcuts_visible;
+        self.help_scroll = 0;
+    }
+
+    /// Toggle do not disturb
+    #[keybind(pressed(key=KeyCode::Char('D')))]
+    fn toggle_dnd(&mut self) {
+        self.dnd = !self.dnd;
+        let state = if self.dnd { "on" } else { "off" };
+        self.toast = Some(Toast::new(format!("Do not disturb {state}")));
+    }
+
+    /// Snooze the coming break
+    #[keybind(pressed(key=KeyCode::Char('z')))]
+    fn snooze_break(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            self.snooze();
+        }
+    }
+
+    /// Toggle ticking sound
+    #[keybind(pressed(key=KeyCode::Char('m')))]
+    fn toggle_ticking(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            self.ticking = !self.ticking;
+            let state = if self.ticking { "on" } else { "off" };
+            self.toast = Some(Toast::new(format!("Ticking {state}")));
+        }
+    }
+
+    /// Toggle digits/pie view
+    #[keybind(pressed(key=KeyCode::Char('v')))]
+    fn toggle_timer_view(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            let view = self.timer_panel.toggle_view();
+            self.toast = Some(Toast::new(format!("Timer view: {}", view.label())));
+        }
     }
 
     /// Start or pause timer
     #[keybind(pressed(key=KeyCode::Char(' ')))]
     fn toggle_timer(&mut self) {
-        if self.focused_panel == PanelId::Timer {
+        if self.focused_panel != PanelId::Timer {
+            return;
+        }
+        if self.timer.is_overtime() {
+            // Overtime was already announced at zero, so finish quietly
+            let session = self.timer.session_type();
+            self.timer.finish_overtime();
+            self.record_completed_session(session);
+        } else {
+            let pausing_work =
+                self.timer.is_running() && self.timer.session_type() == SessionType::Work;
+            if pausing_work && self.strict {
+                self.toast = Some(Toast::new(
+                    "Strict mode: work sessions can't be paused".to_string(),
+                ));
+                return;
+            }
             self.timer.toggle();
+            if pausing_work && self.config.timer.ask_pause_reason {
+                self.pause_reason = Some(PauseReasonOverlay::default());
+            }
         }
     }
 
@@ -177,8 +1375,32 @@ impl App {
     #[keybind(pressed(key=KeyCode::Char('r')))]
     #[keybind(pressed(key=KeyCode::Char('R')))]
     fn reset_timer(&mut self) {
-        if self.focused_panel == PanelId::Timer {
-            self.timer.reset();
+        if self.focused_panel != PanelId::Timer {
+            return;
+        }
+        if self.strict && !self.timer.is_idle() {
+            let confirmed = self
+                .reset_pressed_at
+                .take()
+                .is_some_and(|at| at.elapsed() < RESET_CONFIRM_WINDOW);
+            if !confirmed {
+                self.reset_pressed_at = Some(Instant::now());
+                self.toast = Some(Toast::new(
+                    "Strict mode: press r again to reset".to_string(),
+                ));
+                return;
+            }
+        }
+        self.timer.reset();
+    }
+
+    /// Toggle strict mode
+    #[keybind(pressed(key=KeyCode::Char('!')))]
+    fn toggle_strict(&mut self) {
+        if self.focused_panel == PanelId::Timer && self.timer.is_idle() {
+            self.strict = !self.strict;
+            let state = if self.strict { "on" } else { "off" };
+            self.toast = Some(Toast::new(format!("Strict mode {state}")));
         }
     }
 
@@ -205,7 +1427,52 @@ impl App {
     #[keybind(pressed(key=KeyCode::Char('X')))]
     fn handle_complete(&mut self) {
         if self.focused_panel == PanelId::Timer {
-            self.tasks_panel.complete_current_task();
+            self.complete_current_task();
+        }
+    }
+
+    /// Count down to a clock time
+    #[keybind(pressed(key=KeyCode::Char('u')))]
+    fn open_until_input(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            self.until_input = Some(UntilOverlay::default());
+        }
+    }
+
+    /// Choose a preset
+    #[keybind(pressed(key=KeyCode::Char('p')))]
+    fn open_preset_picker(&mut self) {
+        if self.focused_panel == PanelId::Timer && self.timer.is_idle() {
+            self.preset_picker = Some(PresetOverlay::new(self.config.timer.presets.clone()));
+        }
+    }
+
+    /// Change the active task
+    #[keybind(pressed(key=KeyCode::Char('c')))]
+    fn open_task_switcher(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            self.task_switcher = Some(TaskSwitcherOverlay::new(self.tasks_panel.current_tasks()));
+        }
+    }
+
+    /// Schedule a work session
+    #[keybind(pressed(key=KeyCode::Char('@')))]
+    fn open_schedule_input(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            self.schedule_input = Some(UntilOverlay::new(" Schedule work at ", "Schedule"));
+        }
+    }
+
+    /// Switch between pomodoro and flowtime
+    #[keybind(pressed(key=KeyCode::Char('f')))]
+    fn toggle_timer_mode(&mut self) {
+        if self.focused_panel == PanelId::Timer && self.timer.is_idle() {
+            let mode = match self.timer.mode() {
+                TimerMode::Pomodoro => TimerMode::Flowtime,
+                TimerMode::Flowtime => TimerMode::Pomodoro,
+            };
+            self.timer.set_mode(mode);
+            self.toast = Some(Toast::new(format!("Mode: {}", mode.label())));
         }
     }
 
@@ -222,8 +1489,8 @@ impl App {
     #[keybind(pressed(key=KeyCode::Char('+')))]
     #[keybind(pressed(key=KeyCode::Char('=')))]
     fn add_minute(&mut self) {
-        if self.focused_panel == PanelId::Timer && self.timer.is_idle() {
-            self.timer.add_minute();
+        if self.focused_panel == PanelId::Timer {
+            self.timer.add_minutes(1);
         }
     }
 
@@ -231,19 +1498,41 @@ impl App {
     #[keybind(pressed(key=KeyCode::Char('-')))]
     #[keybind(pressed(key=KeyCode::Char('_')))]
     fn subtract_minute(&mut self) {
-        if self.focused_panel == PanelId::Timer && self.timer.is_idle() {
-            self.timer.subtract_minute();
+        if self.focused_panel == PanelId::Timer {
+            self.timer.subtract_minutes(1);
+        }
+    }
+
+    /// Add five minutes to timer
+    #[keybind(pressed(key=KeyCode::Char(']')))]
+    fn add_five_minutes(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            self.timer.add_minutes(5);
+        }
+    }
+
+    /// Subtract five minutes from timer
+    #[keybind(pressed(key=KeyCode::Char('[')))]
+    fn subtract_five_minutes(&mut self) {
+        if self.focused_panel == PanelId::Timer {
+            self.timer.subtract_minutes(5);
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::error::Error;
+
+    use crossterm::event::KeyModifiers;
+    use ratatui::{backend::TestBackend, Terminal};
+
     use super::*;
+    use crate::task::TaskSection;
 
     #[test]
     fn test_toggle_tasks_visibility() {
-        let mut app = App::new(None);
+        let mut app = App::new(None, Config::default());
         app.tasks_visible = false;
         app.two_columns = false;
         app.focused_panel = PanelId::Timer;
@@ -271,12 +1560,551 @@ mod tests {
         assert_eq!(app.focused_panel, PanelId::Timer);
     }
 
+    #[test]
+    fn test_cycle_layout_presets() {
+        let mut app = App::new(None, Config::default());
+        app.compute_column_layout(TIMER_MIN_WIDTH * 4);
+        app.focused_panel = PanelId::Tasks;
+
+        app.handle(&key(KeyCode::Char('L')));
+        assert_eq!(app.layout_preset, LayoutPreset::TasksDominant);
+        assert!(app.two_columns);
+        assert_eq!(app.focused_panel, PanelId::Tasks);
+
+        app.handle(&key(KeyCode::Char('L')));
+        assert_eq!(app.layout_preset, LayoutPreset::Stacked);
+        assert!(app.two_columns);
+
+        // Only the timer is left on screen, so it takes focus
+        app.handle(&key(KeyCode::Char('L')));
+        assert_eq!(app.layout_preset, LayoutPreset::TimerOnly);
+        assert!(!app.two_columns);
+        assert_eq!(app.focused_panel, PanelId::Timer);
+
+        app.handle(&key(KeyCode::Char('L')));
+        assert_eq!(app.layout_preset, LayoutPreset::Split);
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let mut app = App::new(None, Config::default());
+        app.handle(&key(KeyCode::Char('!')));
+        assert!(app.strict);
+
+        app.handle(&key(KeyCode::Char(' ')));
+        app.handle(&key(KeyCode::Char(' ')));
+        assert!(app.timer.is_running());
+
+        // Can't be switched off mid-session
+        app.handle(&key(KeyCode::Char('!')));
+        assert!(app.strict);
+
+        app.handle(&key(KeyCode::Char('r')));
+        assert!(app.timer.is_running());
+        app.handle(&key(KeyCode::Char('r')));
+        assert!(app.timer.is_idle());
+    }
+
+    #[test]
+    fn test_pause_while_away() {
+        let mut config = Config::default();
+        config.timer.idle_pause = 5;
+        config.timer.idle_resume = true;
+        let mut app = App::new(None, config);
+        let present = Presence {
+            idle: Duration::from_secs(2),
+            locked: false,
+        };
+        app.handle(&key(KeyCode::Char(' ')));
+        app.handle_presence(present);
+        assert!(app.timer.is_running());
+
+        app.handle_presence(Presence {
+            idle: Duration::from_secs(2),
+            locked: true,
+        });
+        assert!(app.timer.is_paused());
+
+        app.handle_presence(present);
+        assert!(app.timer.is_running());
+    }
+
+    #[test]
+    fn test_apply_preset() {
+        let mut app = App::new(None, Config::default());
+        app.handle(&key(KeyCode::Char('p')));
+        app.handle(&key(KeyCode::Char('j')));
+        app.handle(&key(KeyCode::Enter));
+        assert!(app.preset_picker.is_none());
+        assert_eq!(app.timer.minutes(), 50);
+
+        // Only while idle
+        app.handle(&key(KeyCode::Char(' ')));
+        app.handle(&key(KeyCode::Char('p')));
+        assert!(app.preset_picker.is_none());
+    }
+
+    #[test]
+    fn test_resume_interrupted_session() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("session.toml");
+        let snapshot = SessionSnapshot {
+            session: "work".to_string(),
+            length_secs: 25 * 60,
+            remaining_secs: 10 * 60,
+            ends_at: None,
+            task: None,
+        };
+        SessionFile::load(path.clone()).0.update(Some(snapshot))?;
+
+        let mut app = App::new(None, Config::default());
+        app.session_file = SessionFile::load(path).0;
+        app.offer_recovery();
+        assert!(app.recovery.is_some());
+        app.handle(&key(KeyCode::Enter));
+        assert!(app.recovery.is_none());
+        assert!(app.timer.is_paused());
+        assert_eq!(app.timer.minutes(), 10);
+        assert_eq!(app.timer.elapsed(), Duration::from_mins(15));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pause_reason_is_logged() {
+        let mut config = Config::default();
+        config.timer.ask_pause_reason = true;
+        let mut app = App::new(None, config);
+        let start = Local::now();
+
+        app.handle(&key(KeyCode::Char(' ')));
+        assert!(app.pause_reason.is_none());
+        app.handle(&key(KeyCode::Char(' ')));
+        assert!(app.pause_reason.is_some());
+        for c in "call".chars() {
+            app.handle(&key(KeyCode::Char(c)));
+        }
+        app.handle(&key(KeyCode::Enter));
+
+        assert!(app.pause_reason.is_none());
+        assert!(!app.timer.is_running());
+        let reasons: Vec<&str> = app
+            .interruptions
+            .between(start, Local::now())
+            .map(|i| i.reason.as_str())
+            .collect();
+        assert_eq!(reasons, ["call"]);
+        let paused_at: Vec<_> = app.timer.paused_at().into_iter().collect();
+        assert_eq!(app.interruptions.at_times(&paused_at).count(), 1);
+    }
+
+    #[test]
+    fn test_toggle_timer_view_needs_timer_focus() {
+        let mut app = App::new(None, Config::default());
+        app.focused_panel = PanelId::History;
+        app.handle(&key(KeyCode::Char('v')));
+        assert!(app.toast.is_none());
+
+        app.focused_panel = PanelId::Timer;
+        app.handle(&key(KeyCode::Char('v')));
+        assert!(app.toast.is_some());
+    }
+
+    #[test]
+    fn test_daily_goal_toast() {
+        let mut config = Config::default();
+        config.timer.daily_goal = 2;
+        let mut app = App::new(None, config);
+
+        app.record_completed_session(SessionType::Work);
+        assert!(app.toast.is_none());
+        app.record_completed_session(SessionType::Work);
+        assert_eq!(app.pomodoros_today(), 2);
+        assert!(app.toast.is_some());
+    }
+
+    #[test]
+    fn test_apply_config_updates_idle_timer() {
+        let mut app = App::new(None, Config::default());
+        assert_eq!(app.timer.minutes(), 25);
+
+        let mut config = Config::default();
+        config.timer.work = 50;
+        config.sound.enabled = false;
+        app.apply_config(&config);
+
+        assert_eq!(app.timer.minutes(), 50);
+        assert!(!app.config.sound.enabled);
+        assert!(app.toast.is_some());
+    }
+
+    #[test]
+    fn test_apply_config_keeps_adjusted_timer() {
+        let mut app = App::new(None, Config::default());
+        app.handle(&key(KeyCode::Char('+')));
+        let minutes = app.timer.minutes();
+        assert_ne!(minutes, 25);
+
+        let mut config = Config::default();
+        config.sound.enabled = false;
+        config.timer.max_session = u64::MAX;
+        app.apply_config(&config);
+
+        assert_eq!(app.timer.minutes(), minutes);
+    }
+
+    #[test]
+    fn test_apply_config_strict_waits_for_idle() {
+        let mut app = App::new(None, Config::default());
+        app.handle(&key(KeyCode::Char(' ')));
+        assert!(app.timer.is_running());
+
+        let mut config = Config::default();
+        config.timer.strict = true;
+        app.apply_config(&config);
+        app.tick();
+        assert!(!app.strict);
+
+        app.handle(&key(KeyCode::Char('r')));
+        assert!(app.timer.is_idle());
+        app.tick();
+        assert!(app.strict);
+    }
+
+    #[test]
+    fn test_resize_recomputes_layout() {
+        let mut app = App::new(None, Config::default());
+        app.error_message = Some("Error".to_string());
+
+        app.handle(&Event::Resize(TIMER_MIN_WIDTH * 2, 40));
+        assert!(app.two_columns);
+
+        app.handle(&Event::Resize(TIMER_MIN_WIDTH, 40));
+        assert!(!app.two_columns);
+
+        // Resizing does not dismiss the error overlay
+        assert!(app.error_message.is_some());
+    }
+
+    #[test]
+    fn test_draw_recomputes_layout() -> Result<(), Box<dyn Error>> {
+        let mut app = App::new(None, Config::default());
+        let mut terminal = Terminal::new(TestBackend::new(TIMER_MIN_WIDTH * 2, 40))?;
+        app.tasks_visible = false;
+        terminal.draw(|frame| crate::ui::render(frame, &mut app))?;
+        assert!(!app.two_columns);
+
+        app.tasks_visible = true;
+        terminal.draw(|frame| crate::ui::render(frame, &mut app))?;
+        assert!(app.two_columns);
+        Ok(())
+    }
+
+    #[test]
+    fn test_toggle_dnd_from_any_panel() {
+        let mut app = App::new(None, Config::default());
+        app.handle(&key(KeyCode::Char('D')));
+        assert!(app.dnd);
+
+        app.focused_panel = PanelId::Tasks;
+        app.handle(&key(KeyCode::Char('D')));
+        assert!(!app.dnd);
+    }
+
+    #[test]
+    fn test_focus_cycles_through_history() {
+        let mut app = App::new(None, Config::default());
+        for expected in [PanelId::Tasks, PanelId::History, PanelId::Timer] {
+            app.handle(&key(KeyCode::Char('t')));
+            assert_eq!(app.focused_panel, expected);
+        }
+
+        // Hiding the tasks column also hides history
+        app.focused_panel = PanelId::History;
+        app.handle(&key(KeyCode::Char('T')));
+        assert_eq!(app.focused_panel, PanelId::Timer);
+    }
+
+    #[test]
+    fn test_number_keys_focus_and_unhide() {
+        let mut app = App::new(None, Config::default());
+        app.tasks_visible = false;
+
+        app.handle(&key(KeyCode::Char('3')));
+        assert!(app.tasks_visible);
+        assert_eq!(app.focused_panel, PanelId::History);
+
+        app.handle(&key(KeyCode::Char('2')));
+        assert_eq!(app.focused_panel, PanelId::Tasks);
+
+        app.handle(&key(KeyCode::Char('1')));
+        assert_eq!(app.focused_panel, PanelId::Timer);
+        assert!(app.tasks_visible);
+    }
+
+    #[test]
+    fn test_number_keys_focus_with_line_numbers() {
+        let mut config = Config::default();
+        config.tasks.line_numbers = true;
+        let mut app = App::new(None, config);
+        app.handle(&key(KeyCode::Char('2')));
+        assert_eq!(app.focused_panel, PanelId::Tasks);
+
+        app.handle(&key(KeyCode::Char('1')));
+        assert_eq!(app.focused_panel, PanelId::Timer);
+    }
+
+    #[test]
+    fn test_number_keys_update_columns() {
+        let mut app = App::new(None, Config::default());
+        app.tasks_visible = false;
+        app.compute_column_layout(TIMER_MIN_WIDTH * 2);
+        assert!(!app.two_columns);
+
+        app.handle(&key(KeyCode::Char('2')));
+        assert!(app.two_columns);
+    }
+
+    #[test]
+    fn test_notification_actions_drive_timer() {
+        let mut app = App::new(None, Config::default());
+        app.last_completed = Some(SessionType::Work);
+        app.timer.set_session_type(SessionType::ShortBreak);
+
+        app.notification_tx
+            .send(Ok(NotificationAction::Extend))
+            .ok();
+        app.tick();
+        assert!(app.timer.is_running());
+        assert_eq!(app.timer.session_type(), SessionType::Work);
+        assert_eq!(app.timer.minutes(), 5);
+
+        // Ignored once the timer is running
+        app.notification_tx.send(Ok(NotificationAction::Skip)).ok();
+        app.tick();
+        assert_eq!(app.timer.session_type(), SessionType::Work);
+
+        app.notification_tx.send(Err("failed".to_string())).ok();
+        app.tick();
+        assert_eq!(app.error_message.as_deref(), Some("failed"));
+    }
+
+    #[test]
+    fn test_session_complete_overlay_choices() {
+        let mut app = App::new(None, Config::default());
+        app.tasks_panel
+            .add_task("Write report".to_string(), TaskSection::Current);
+        app.last_completed = Some(SessionType::Work);
+        app.timer.set_session_type(SessionType::ShortBreak);
+        app.session_complete = Some(SessionCompleteOverlay::new(
+            SessionType::Work,
+            SessionType::ShortBreak,
+            5,
+            1,
+            app.tasks_panel.active_task().map(|t| t.text.clone()),
+        ));
+
+        // The task question comes first
+        app.handle(&key(KeyCode::Char('s')));
+        assert!(app.session_complete.is_some());
+        app.handle(&key(KeyCode::Char('y')));
+        assert!(app.tasks_panel.active_task().is_none());
+        assert!(app.session_complete.is_some());
+
+        app.handle(&key(KeyCode::Char('s')));
+        assert!(app.session_complete.is_none());
+        assert_eq!(app.timer.session_type(), SessionType::Work);
+        assert!(app.timer.is_idle());
+    }
+
+    #[test]
+    fn test_switch_active_task() {
+        let mut app = App::new(None, Config::default());
+        app.tasks_panel
+            .add_task("Write report", TaskSection::Current);
+        app.tasks_panel.add_task("Review PR", TaskSection::Current);
+        app.focused_panel = PanelId::Timer;
+
+        app.handle(&key(KeyCode::Char('c')));
+        assert!(app.task_switcher.is_some());
+        app.handle(&key(KeyCode::Char('j')));
+        app.handle(&key(KeyCode::Enter));
+        assert!(app.task_switcher.is_none());
+        let task = app.tasks_panel.active_task().map(|t| t.text.as_str());
+        assert_eq!(task, Some("Review PR"));
+    }
+
+    #[test]
+    fn test_quit_with_unsynced_changes() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        std::fs::write(&path, "- [ ] Plan\n")?;
+
+        let mut app = App::new(Some(path.clone()), Config::default());
+        app.tasks_panel.add_task("Write", TaskSection::Backlog);
+        app.handle(&key(KeyCode::Char('q')));
+        assert!(app.quit_prompt.is_some());
+        app.handle(&key(KeyCode::Esc));
+        assert!(app.quit_prompt.is_none());
+        assert!(!app.should_quit);
+
+        app.handle(&key(KeyCode::Char('q')));
+        app.handle(&key(KeyCode::Char('s')));
+        assert!(app.should_quit);
+        assert!(std::fs::read_to_string(&path)?.contains("- [ ] Write"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_task_records_pomodoro() {
+        let mut app = App::new(None, Config::default());
+        app.tasks_panel
+            .add_task("Write report".to_string(), TaskSection::Current);
+        app.session_complete = Some(SessionCompleteOverlay::new(
+            SessionType::Work,
+            SessionType::ShortBreak,
+            5,
+            1,
+            Some("Write report".to_string()),
+        ));
+
+        app.handle(&key(KeyCode::Char('p')));
+        let task = app.tasks_panel.active_task();
+        assert_eq!(task.map(|t| t.pomodoros), Some(1));
+    }
+
+    #[test]
+    fn test_snooze_break_from_overlay() {
+        let mut app = App::new(None, Config::default());
+        app.timer.set_session_type(SessionType::ShortBreak);
+        app.session_complete = Some(SessionCompleteOverlay::new(
+            SessionType::Work,
+            SessionType::ShortBreak,
+            5,
+            1,
+            None,
+        ));
+
+        app.handle(&key(KeyCode::Char('z')));
+        assert!(app.session_complete.is_none());
+        assert!(app.timer.is_running());
+        assert_eq!(app.timer.session_type(), SessionType::Work);
+        let logged: Vec<&str> = app
+            .interruptions
+            .between(Local::now() - chrono::TimeDelta::minutes(1), Local::now())
+            .map(|i| i.reason.as_str())
+            .collect();
+        assert_eq!(logged, ["Snoozed short break (1/2)"]);
+
+        // Already working through the snooze
+        app.handle(&key(KeyCode::Char('z')));
+        assert_eq!(app.timer.snoozes(), 1);
+        assert_eq!(app.timer.remaining(), SNOOZE_LENGTH);
+    }
+
+    #[test]
+    fn test_snoozed_work_is_not_another_pomodoro() {
+        let mut app = App::new(None, Config::default());
+        app.timer.set_session_type(SessionType::ShortBreak);
+        app.timer.set_speed(u32::MAX);
+        app.snooze();
+        assert_eq!(app.timer.snoozes(), 1);
+
+        std::thread::sleep(Duration::from_millis(10));
+        app.tick();
+        assert!(app.session_complete.is_some());
+        assert_eq!(app.pomodoros_today(), 0);
+    }
+
+    #[test]
+    fn test_help_scroll_clamped_to_frame() -> Result<(), Box<dyn Error>> {
+        let mut app = App::new(None, Config::default());
+        app.handle(&key(KeyCode::Char('?')));
+        for _ in 0..100 {
+            app.handle(&key(KeyCode::Char('j')));
+        }
+
+        // A 16-row frame leaves a 12-row overlay showing 10 keybinds
+        let mut terminal = Terminal::new(TestBackend::new(60, 16))?;
+        terminal.draw(|frame| crate::ui::render(frame, &mut app))?;
+        assert_eq!(app.help_scroll, App::KEYBINDS.len() - 10);
+
+        app.handle(&key(KeyCode::Char('k')));
+        assert_eq!(app.help_scroll, App::KEYBINDS.len() - 11);
+
+        // Reopening starts from the top
+        app.handle(&key(KeyCode::Esc));
+        app.handle(&key(KeyCode::Char('?')));
+        assert_eq!(app.help_scroll, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_palette() {
+        let mut app = App::new(None, Config::default());
+        let ctrl_p = Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL));
+        app.handle(&ctrl_p);
+        assert!(app.command_palette.is_some());
+        assert!(app.preset_picker.is_none());
+        for c in "add".chars() {
+            app.handle(&key(KeyCode::Char(c)));
+        }
+        app.handle(&key(KeyCode::Enter));
+        assert!(app.command_palette.is_none());
+        assert_eq!(app.focused_panel, PanelId::Tasks);
+        assert!(app.tasks_panel.task_input_overlay().is_some());
+
+        // Timer commands run from any panel, including the tasks panel, whose `p` pastes
+        app.handle(&key(KeyCode::Esc));
+        assert_eq!(app.focused_panel, PanelId::Tasks);
+        app.handle(&ctrl_p);
+        for c in "start".chars() {
+            app.handle(&key(KeyCode::Char(c)));
+        }
+        app.handle(&key(KeyCode::Enter));
+        assert_eq!(app.focused_panel, PanelId::Timer);
+        assert!(app.timer.is_running());
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn wheel(kind: MouseEventKind, column: u16, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    #[test]
+    fn test_mouse_wheel_scrolls_under_pointer() {
+        let mut app = App::new(None, Config::default());
+        app.tasks_area = Some(Rect::new(40, 0, 40, 20));
+
+        // Outside the tasks column nothing moves
+        app.handle(&wheel(MouseEventKind::ScrollDown, 10, 5));
+        assert_eq!(app.focused_panel, PanelId::Timer);
+
+        // Scrolling over the tasks column focuses it
+        app.handle(&wheel(MouseEventKind::ScrollDown, 50, 5));
+        assert_eq!(app.focused_panel, PanelId::Tasks);
+
+        // The help overlay scrolls wherever the pointer is
+        app.handle(&key(KeyCode::Char('?')));
+        app.handle(&wheel(MouseEventKind::ScrollDown, 10, 5));
+        app.handle(&wheel(MouseEventKind::ScrollDown, 10, 5));
+        app.handle(&wheel(MouseEventKind::ScrollUp, 10, 5));
+        assert_eq!(app.help_scroll, 1);
+    }
+
     #[test]
     fn test_update_layout_two_column_threshold() {
         let mut app = App {
             tasks_visible: true,
             two_columns: false,
-            ..App::new(None)
+            ..App::new(None, Config::default())
         };
 
         // Width below threshold: single column
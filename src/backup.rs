@@ -0,0 +1,116 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::fileio::flat_name;
+
+/// Timestamped copies of the task file, kept in a folder per task file under the cache
+/// directory's `backups`, so a bad write or edit can be rolled back
+#[derive(Debug, Clone, Default)]
+pub struct Backups {
+    dir: Option<PathBuf>,
+    /// Most copies kept per task file, the oldest going first
+    keep: usize,
+}
+
+impl Backups {
+    /// Backups that keep nothing, until a directory is given
+    pub const fn new() -> Self {
+        Self { dir: None, keep: 0 }
+    }
+
+    pub const fn in_dir(dir: PathBuf, keep: usize) -> Self {
+        Self {
+            dir: Some(dir),
+            keep,
+        }
+    }
+
+    /// Copy the file at `path` into its backup folder, dropping the oldest copies past
+    /// `keep`; nothing is kept without a backups directory, or a file to copy
+    pub fn save(&self, path: &Path) -> Result<(), io::Error> {
+        let Some(dir) = self.folder(path).filter(|_| self.keep > 0) else {
+            return Ok(());
+        };
+        if !path.exists() {
+            return Ok(());
+        }
+        fs::create_dir_all(&dir)?;
+        // Down to the microsecond, so restoring just after a write still backs up what it replaces
+        let name = Local::now().format("%Y-%m-%d_%H-%M-%S-%6f.md").to_string();
+        fs::copy(path, dir.join(name))?;
+        for old in self.list(path).iter().skip(self.keep) {
+            fs::remove_file(old)?;
+        }
+        Ok(())
+    }
+
+    /// Copies of the file at `path`, newest first
+    pub fn list(&self, path: &Path) -> Vec<PathBuf> {
+        let Some(entries) = self.folder(path).and_then(|dir| fs::read_dir(dir).ok()) else {
+            return Vec::new();
+        };
+        let mut backups: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        // Named by time, so by name is by age
+        backups.sort_unstable_by(|a, b| b.cmp(a));
+        backups
+    }
+
+    /// Folder holding the copies of the file at `path`, named after its whole path so task
+    /// files of the same name in different projects are kept apart
+    fn folder(&self, path: &Path) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(flat_name(path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_keeps_newest_backups() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Task")?;
+        let backups = Backups::in_dir(temp_dir.path().join("backups"), 2);
+
+        let folder = backups.folder(&path).unwrap_or_default();
+        fs::create_dir_all(&folder)?;
+        for day in ["2026-01-01", "2026-01-02"] {
+            fs::write(
+                folder.join(format!("{day}_09-00-00-000000.md")),
+                "- [ ] Old",
+            )?;
+        }
+        backups.save(&path)?;
+
+        let list = backups.list(&path);
+        assert_eq!(list.len(), 2);
+        assert_eq!(fs::read_to_string(&list[0])?, "- [ ] Task");
+        assert!(list[1].ends_with("2026-01-02_09-00-00-000000.md"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_backups_in_quick_succession() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        let backups = Backups::in_dir(temp_dir.path().join("backups"), 5);
+
+        fs::write(&path, "- [ ] First")?;
+        backups.save(&path)?;
+        fs::write(&path, "- [ ] Second")?;
+        backups.save(&path)?;
+
+        let list = backups.list(&path);
+        assert_eq!(list.len(), 2);
+        assert_eq!(fs::read_to_string(&list[1])?, "- [ ] First");
+        Ok(())
+    }
+}
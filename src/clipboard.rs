@@ -0,0 +1,79 @@
+//! System clipboard for yanking and pasting tasks, which needs the `clipboard` feature.
+
+/// Reason given for every clipboard use when built without the feature
+#[cfg(not(feature = "clipboard"))]
+const UNSUPPORTED: &str = "Clipboard support needs the `clipboard` feature";
+
+/// Connection to the system clipboard, opened on first use and kept so that on X11 what was
+/// copied stays available until something else is
+#[derive(Default)]
+pub struct Clipboard {
+    #[cfg(feature = "clipboard")]
+    inner: Option<arboard::Clipboard>,
+}
+
+#[cfg(feature = "clipboard")]
+impl Clipboard {
+    fn inner(&mut self) -> Result<&mut arboard::Clipboard, String> {
+        if self.inner.is_none() {
+            self.inner = Some(arboard::Clipboard::new().map_err(|e| e.to_string())?);
+        }
+        self.inner
+            .as_mut()
+            .ok_or_else(|| "Clipboard unavailable".to_string())
+    }
+
+    pub fn copy(&mut self, text: &str) -> Result<(), String> {
+        self.inner()?.set_text(text).map_err(|e| e.to_string())
+    }
+
+    pub fn paste(&mut self) -> Result<String, String> {
+        self.inner()?.get_text().map_err(|e| e.to_string())
+    }
+}
+
+// Signatures mirror the real clipboard, including its fallible returns
+#[cfg(not(feature = "clipboard"))]
+#[allow(clippy::unnecessary_wraps, clippy::needless_pass_by_ref_mut)]
+impl Clipboard {
+    pub fn copy(&mut self, _text: &str) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn paste(&mut self) -> Result<String, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+/// Task texts in pasted text, one per non-blank line, without any list or checkbox prefix
+pub fn pasted_tasks(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .unwrap_or(line);
+            let line = ["[ ]", "[x]", "[X]"]
+                .iter()
+                .find_map(|checkbox| line.strip_prefix(checkbox))
+                .unwrap_or(line);
+            line.trim().to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pasted_tasks() {
+        let text = "- [ ] Fix parser\n\n  - [x] Write docs #work\n* Plan week\nReview PR\n- [ ] \n";
+        assert_eq!(
+            pasted_tasks(text),
+            ["Fix parser", "Write docs #work", "Plan week", "Review PR"]
+        );
+    }
+}
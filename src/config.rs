@@ -0,0 +1,797 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::NaiveTime;
+use serde::{de::Error, Deserialize, Deserializer};
+
+use crate::notification_policy::NotificationPolicy;
+use crate::theme::Theme;
+use crate::timer::{SessionType, SleepPolicy};
+
+/// How often the config file's modification time is checked for live reload
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest any configured length can be, so huge values can't overflow a `Duration`
+const MAX_MINUTES: u64 = 24 * 60;
+
+/// Project config file looked for in the working directory and its ancestors
+const PROJECT_CONFIG_NAME: &str = ".pomo-tui.toml";
+/// Project task file looked for alongside `PROJECT_CONFIG_NAME`
+const PROJECT_TASKS_NAME: &str = "TODO.md";
+
+/// User configuration loaded from `~/.config/pomo-tui/config.toml` or a project `.pomo-tui.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Task file used when none is given on the command line, relative to the config file
+    pub task_file: Option<PathBuf>,
+    pub plugins: Vec<PluginConfig>,
+    pub theme: Theme,
+    pub timer: TimerConfig,
+    pub sound: SoundConfig,
+    pub notifications: NotificationPolicy,
+    pub tasks: TasksConfig,
+    pub display: DisplayConfig,
+    /// Sessions to start at set times each day, as `[[schedule]]`
+    pub schedule: Vec<ScheduleEntry>,
+}
+
+/// Session lengths, in minutes
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimerConfig {
+    pub work: u64,
+    pub short_break: u64,
+    pub long_break: u64,
+    /// Longest a session can be stretched to with `+`, even while running
+    pub max_session: u64,
+    /// Keep a work session counting up past zero until it is stopped with space
+    pub overtime: bool,
+    /// Flowtime breaks last 1/N of the time worked
+    pub flow_break_divisor: u32,
+    /// Custom cycle of session lengths alternating work and break, e.g. `[52, 17]`.
+    /// Breaks longer than the shortest one are long breaks.
+    #[serde(deserialize_with = "deserialize_sequence")]
+    pub sequence: Vec<u64>,
+    /// Work sessions to aim for each day, shown on the timer border; 0 shows just the count
+    pub daily_goal: u32,
+    /// Ask why when a work session is paused, logging the answer
+    pub ask_pause_reason: bool,
+    /// Start in strict mode, where work sessions can't be paused and reset needs a double press
+    pub strict: bool,
+    /// Minutes without input, or with the screen locked, before work pauses itself; 0 for never.
+    /// Needs the `idle` feature.
+    pub idle_pause: u64,
+    /// Resume a session paused this way as soon as activity returns, instead of asking
+    pub idle_resume: bool,
+    /// `ask` to pause a session the machine slept through, or `count` to count the time asleep
+    pub on_sleep: SleepPolicy,
+    /// Named session lengths to pick from with `p`, as `[[timer.presets]]`
+    pub presets: Vec<Preset>,
+    /// Seconds to count down after each session before starting the next; 0 waits for a key
+    pub grace_period: u64,
+    /// Every 20 minutes of unbroken work, remind to look 20 feet away for 20 seconds
+    pub eye_breaks: bool,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            work: 25,
+            short_break: 5,
+            long_break: 15,
+            max_session: 120,
+            overtime: false,
+            flow_break_divisor: 5,
+            sequence: Vec::new(),
+            daily_goal: 0,
+            ask_pause_reason: false,
+            strict: false,
+            idle_pause: 0,
+            idle_resume: false,
+            on_sleep: SleepPolicy::Ask,
+            presets: vec![
+                Preset::new("Classic 25/5", 25, 5, 15),
+                Preset::new("Long 50/10", 50, 10, 30),
+                Preset::new("Sprint 15/3", 15, 3, 10),
+            ],
+            grace_period: 0,
+            eye_breaks: false,
+        }
+    }
+}
+
+impl TimerConfig {
+    /// Work, short break and long break durations
+    pub const fn durations(&self) -> (Duration, Duration, Duration) {
+        (
+            clamped_mins(self.work),
+            clamped_mins(self.short_break),
+            clamped_mins(self.long_break),
+        )
+    }
+
+    pub const fn idle_pause(&self) -> Duration {
+        clamped_mins(self.idle_pause)
+    }
+
+    pub const fn max_session(&self) -> Duration {
+        clamped_mins(self.max_session)
+    }
+
+    pub const fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period)
+    }
+
+    /// The custom cycle as sessions, empty if none is set
+    pub fn sequence(&self) -> Vec<(SessionType, Duration)> {
+        let shortest_break = self.sequence.iter().skip(1).step_by(2).min().copied();
+        self.sequence
+            .iter()
+            .enumerate()
+            .map(|(i, &minutes)| {
+                let session = if i % 2 == 0 {
+                    SessionType::Work
+                } else if Some(minutes) > shortest_break {
+                    SessionType::LongBreak
+                } else {
+                    SessionType::ShortBreak
+                };
+                (session, clamped_mins(minutes))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoundConfig {
+    /// Play melodies when sessions complete
+    pub enabled: bool,
+    /// Play a soft chime halfway through each session
+    pub halfway_chime: bool,
+    /// Minutes before the end of a session to play a soft chime, 0 for never
+    pub warn_before_end: u64,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            halfway_chime: false,
+            warn_before_end: 0,
+        }
+    }
+}
+
+impl SoundConfig {
+    pub const fn warn_before_end(&self) -> Duration {
+        clamped_mins(self.warn_before_end)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TasksConfig {
+    /// Show each task's pomodoro tally after its text
+    pub show_pomodoros: bool,
+    /// Ask before deleting a task
+    pub confirm_delete: bool,
+    /// Days a task stays in Completed before `A` archives it; 0 archives them all
+    pub archive_after_days: u32,
+    /// Number the tasks in each section, and take digits typed before `g` as the number to
+    /// jump to. Counts start from 4 to 9, leaving 1 to 3 to focus panels
+    pub line_numbers: bool,
+    /// Most tasks Current should hold at once, shown against its count in the title
+    pub wip_limit: Option<usize>,
+    /// Days a task can sit in Backlog before it is dimmed as stale; 0 never dims them
+    pub stale_after_days: u32,
+    /// Move the top Backlog task to Current when completing the active task empties it
+    pub auto_promote: bool,
+    /// Start with archiving old completed tasks ticked when resetting the day
+    pub archive_on_reset: bool,
+    /// Lay the sections out side by side as columns when the panel is wide enough, rather
+    /// than stacked
+    pub columns: bool,
+    /// Write every change made in the app straight to the task file, leaving the sync dialog
+    /// for bringing in changes made to the file
+    pub auto_save: bool,
+    /// Seconds between syncing with the task file in the background, applying changes made on
+    /// only one side; conflicts open the sync prompt when Tasks is focused and idle, and show a
+    /// reminder otherwise. 0 syncs only when asked
+    pub auto_sync_secs: u64,
+    /// Copies of the task file kept as backups before it is written, restored with `B`;
+    /// 0 keeps none
+    pub backups: usize,
+    /// Mark tasks completed in the app with the day, e.g. `✅ 2024-06-05`, so `A` can tell
+    /// how long ago they were done; tasks without a day are only archived with
+    /// `archive_after_days = 0`
+    pub completion_dates: bool,
+    /// Mark tasks added in the app with the day, e.g. `➕ 2024-06-01`, so oldest-first
+    /// order holds across restarts; otherwise it goes by the day they were added this run
+    pub creation_dates: bool,
+}
+
+impl Default for TasksConfig {
+    fn default() -> Self {
+        Self {
+            show_pomodoros: true,
+            confirm_delete: true,
+            archive_after_days: 7,
+            line_numbers: false,
+            wip_limit: None,
+            stale_after_days: 30,
+            auto_promote: false,
+            archive_on_reset: false,
+            columns: false,
+            auto_save: false,
+            auto_sync_secs: 0,
+            backups: 10,
+            completion_dates: false,
+            creation_dates: false,
+        }
+    }
+}
+
+/// How the timer panel draws the countdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// In the final minute, show only the seconds in the session color with a faster wave
+    pub final_minute_emphasis: bool,
+    /// Pace 4-7-8 breathing with a growing and shrinking bar under the timer while a break
+    /// runs, when the panel is tall enough
+    pub breathing: bool,
+    /// Draw a bar with the percent elapsed under the digits, filling up as the session runs;
+    /// hidden while counting overtime and when the panel is too short
+    pub progress_gauge: bool,
+    /// Show tenths of a second for sessions under two minutes
+    pub tenths: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            final_minute_emphasis: true,
+            breathing: true,
+            progress_gauge: true,
+            tenths: false,
+        }
+    }
+}
+
+/// Settings that differ between two configs, split by whether they can be applied live
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    pub applied: Vec<&'static str>,
+    pub needs_restart: Vec<&'static str>,
+}
+
+impl ConfigChanges {
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.needs_restart.is_empty()
+    }
+
+    /// One-line summary suitable for a toast
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.applied.is_empty() {
+            parts.push(format!("Reloaded {}", self.applied.join(", ")));
+        }
+        if !self.needs_restart.is_empty() {
+            parts.push(format!(
+                "restart to apply {}",
+                self.needs_restart.join(", ")
+            ));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Named set of session lengths, in minutes
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_length")]
+    pub work: u64,
+    #[serde(deserialize_with = "deserialize_length")]
+    pub short_break: u64,
+    #[serde(
+        default = "default_preset_long_break",
+        deserialize_with = "deserialize_length"
+    )]
+    pub long_break: u64,
+}
+
+const fn default_preset_long_break() -> u64 {
+    15
+}
+
+impl Preset {
+    fn new(name: &str, work: u64, short_break: u64, long_break: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            work,
+            short_break,
+            long_break,
+        }
+    }
+
+    /// Work, short break and long break durations
+    pub const fn durations(&self) -> (Duration, Duration, Duration) {
+        (
+            clamped_mins(self.work),
+            clamped_mins(self.short_break),
+            clamped_mins(self.long_break),
+        )
+    }
+}
+
+/// A session to start at a time of day
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleEntry {
+    /// `HH:MM`
+    #[serde(deserialize_with = "deserialize_time")]
+    pub at: NaiveTime,
+    /// `work`, `short_break` or `long_break`
+    #[serde(
+        default = "default_scheduled_session",
+        deserialize_with = "deserialize_session"
+    )]
+    pub session: SessionType,
+    /// Start without asking, if the timer is idle
+    #[serde(default)]
+    pub auto_start: bool,
+}
+
+const fn default_scheduled_session() -> SessionType {
+    SessionType::Work
+}
+
+fn deserialize_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&text, "%H:%M")
+        .map_err(|_| D::Error::custom(format!("'{text}' is not a time as HH:MM")))
+}
+
+fn deserialize_session<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SessionType, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    SessionType::from_name(&name)
+        .ok_or_else(|| D::Error::custom(format!("unknown session '{name}'")))
+}
+
+/// An external command whose output is shown in its own panel
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginConfig {
+    pub title: String,
+    /// Shell command run via `sh -c`; each line of stdout becomes a panel row
+    pub command: String,
+    #[serde(default = "default_plugin_interval")]
+    pub interval_secs: u64,
+}
+
+const fn default_plugin_interval() -> u64 {
+    60
+}
+
+/// A length in minutes from the config, clamped to `MAX_MINUTES`
+const fn clamped_mins(minutes: u64) -> Duration {
+    Duration::from_mins(if minutes > MAX_MINUTES {
+        MAX_MINUTES
+    } else {
+        minutes
+    })
+}
+
+fn deserialize_sequence<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u64>, D::Error> {
+    let sequence = Vec::<u64>::deserialize(deserializer)?;
+    if sequence.contains(&0) {
+        return Err(D::Error::custom(
+            "session lengths must be at least 1 minute",
+        ));
+    }
+    Ok(sequence)
+}
+
+fn deserialize_length<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let minutes = u64::deserialize(deserializer)?;
+    if minutes == 0 {
+        return Err(D::Error::custom(
+            "session lengths must be at least 1 minute",
+        ));
+    }
+    Ok(minutes)
+}
+
+impl Config {
+    /// Load the config file, falling back to defaults if it is missing or invalid
+    pub fn load(path: &Path) -> (Self, Option<String>) {
+        match Self::from_file(path) {
+            Ok(config) => (config, None),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Self::default(), None),
+            Err(e) => (Self::default(), Some(format!("Failed to load config: {e}"))),
+        }
+    }
+
+    fn from_file(path: &Path) -> Result<Self, io::Error> {
+        let content = fs::read_to_string(path)?;
+        let mut config = Self::parse(&content)?;
+        if let (Some(task_file), Some(dir)) = (&config.task_file, path.parent()) {
+            config.task_file = Some(dir.join(task_file));
+        }
+        Ok(config)
+    }
+
+    fn parse(content: &str) -> Result<Self, io::Error> {
+        toml::from_str(content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Compare against a newly loaded config
+    pub fn changes(&self, new: &Self) -> ConfigChanges {
+        let mut changes = ConfigChanges::default();
+        if self.theme != new.theme {
+            changes.applied.push("theme");
+        }
+        if self.timer != new.timer {
+            changes.applied.push("timer durations");
+        }
+        if self.sound != new.sound {
+            changes.applied.push("sound");
+        }
+        if self.notifications != new.notifications {
+            changes.applied.push("notifications");
+        }
+        if self.tasks != new.tasks {
+            changes.applied.push("tasks");
+        }
+        if self.display != new.display {
+            changes.applied.push("display");
+        }
+        if self.schedule != new.schedule {
+            changes.applied.push("schedule");
+        }
+        if self.plugins != new.plugins {
+            changes.needs_restart.push("plugins");
+        }
+        if self.task_file != new.task_file {
+            changes.needs_restart.push("task file");
+        }
+        changes
+    }
+}
+
+/// Resolve `$XDG_CONFIG_HOME/pomo-tui`, defaulting to `~/.config/pomo-tui`
+pub fn config_dir() -> Option<PathBuf> {
+    let base = env::var("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|_| {
+        env::var("HOME")
+            .or_else(|_| env::var("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(".config"))
+    });
+    base.ok().map(|dir| dir.join("pomo-tui"))
+}
+
+/// `~/.cache/pomo-tui`, holding the default task file, session history and UI state
+pub fn cache_dir() -> Option<PathBuf> {
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .ok()
+        .map(|home| PathBuf::from(home).join(".cache").join("pomo-tui"))
+}
+
+/// The global config file, `config.toml` in [`config_dir`]
+pub fn global_config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Project files found by walking up from the working directory
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProjectFile {
+    /// A `.pomo-tui.toml`, used in place of the global config
+    Config(PathBuf),
+    /// A `TODO.md`, used as the task file
+    Tasks(PathBuf),
+}
+
+/// Find the nearest project config or task file in `start` or any of its ancestors
+pub fn discover_project(start: &Path) -> Option<ProjectFile> {
+    start.ancestors().find_map(|dir| {
+        let config = dir.join(PROJECT_CONFIG_NAME);
+        if config.is_file() {
+            return Some(ProjectFile::Config(config));
+        }
+        let tasks = dir.join(PROJECT_TASKS_NAME);
+        tasks.is_file().then_some(ProjectFile::Tasks(tasks))
+    })
+}
+
+/// Detects edits to the config file by polling its modification time
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            modified: modified_time(&path),
+            path,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Re-read the config if the file changed, checking at most once per `WATCH_INTERVAL`
+    pub fn poll(&mut self) -> Option<Result<Config, String>> {
+        if self.last_poll.elapsed() < WATCH_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+        self.check()
+    }
+
+    fn check(&mut self) -> Option<Result<Config, String>> {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        Some(match Config::from_file(&self.path) {
+            Ok(config) => Ok(config),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("Failed to reload config: {e}")),
+        })
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::NotificationBackend;
+    use chrono::NaiveTime;
+    use ratatui::style::Color;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_empty_config() -> Result<(), io::Error> {
+        let config = Config::parse("")?;
+        assert!(config.plugins.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_plugins() -> Result<(), io::Error> {
+        let config = Config::parse(
+            r#"
+            [[plugins]]
+            title = "Weather"
+            command = "curl -s wttr.in?format=3"
+            interval_secs = 600
+
+            [[plugins]]
+            title = "Calendar"
+            command = "gcalcli agenda"
+            "#,
+        )?;
+        assert_eq!(config.plugins.len(), 2);
+        assert_eq!(config.plugins[0].title, "Weather");
+        assert_eq!(config.plugins[0].interval_secs, 600);
+        assert_eq!(config.plugins[1].command, "gcalcli agenda");
+        assert_eq!(config.plugins[1].interval_secs, 60);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_theme() -> Result<(), io::Error> {
+        let config = Config::parse(
+            r##"
+            [theme]
+            accent = "magenta"
+            work = "#ff8800"
+            "##,
+        )?;
+        assert_eq!(config.theme.accent, Color::Magenta);
+        assert_eq!(config.theme.work, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(config.theme.short_break, Theme::default().short_break);
+
+        assert!(Config::parse("[theme]\naccent = \"not-a-color\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_sequence() -> Result<(), io::Error> {
+        let config = Config::parse("[timer]\nsequence = [25, 5, 25, 5, 25, 30]")?;
+        let sessions: Vec<SessionType> = config.timer.sequence().into_iter().map(|s| s.0).collect();
+        assert_eq!(
+            sessions,
+            [
+                SessionType::Work,
+                SessionType::ShortBreak,
+                SessionType::Work,
+                SessionType::ShortBreak,
+                SessionType::Work,
+                SessionType::LongBreak,
+            ]
+        );
+        assert_eq!(config.timer.sequence()[5].1, Duration::from_mins(30));
+
+        assert!(Config::parse("[timer]\nsequence = [25, 0]").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_presets() -> Result<(), io::Error> {
+        assert_eq!(Config::default().timer.presets.len(), 3);
+
+        let config =
+            Config::parse("[[timer.presets]]\nname = \"Deep\"\nwork = 90\nshort_break = 20\n")?;
+        assert_eq!(config.timer.presets, [Preset::new("Deep", 90, 20, 15)]);
+
+        for lengths in [
+            "work = 0\nshort_break = 5",
+            "work = 25\nshort_break = 0",
+            "work = 25\nshort_break = 5\nlong_break = 0",
+        ] {
+            assert!(
+                Config::parse(&format!("[[timer.presets]]\nname = \"Off\"\n{lengths}\n")).is_err()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_schedule() -> Result<(), io::Error> {
+        let config = Config::parse(
+            "[[schedule]]\nat = \"09:00\"\n\n[[schedule]]\nat = \"13:30\"\nsession = \"long_break\"\nauto_start = true\n",
+        )?;
+        assert_eq!(config.schedule.len(), 2);
+        assert_eq!(config.schedule[0].session, SessionType::Work);
+        assert_eq!(
+            config.schedule[1].at,
+            NaiveTime::from_hms_opt(13, 30, 0).unwrap_or_default()
+        );
+        assert!(config.schedule[1].auto_start);
+
+        assert!(Config::parse("[[schedule]]\nat = \"9am\"").is_err());
+        assert!(Config::parse("[[schedule]]\nat = \"09:00\"\nsession = \"nap\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_notifications() -> Result<(), io::Error> {
+        let config = Config::parse(
+            r#"
+            [notifications]
+            quiet_hours = { start = "18:00", end = "08:30" }
+            work = { sound = false }
+            backend = "osc777"
+            "#,
+        )?;
+        assert_eq!(config.notifications.backend, NotificationBackend::Osc777);
+        let quiet = config.notifications.quiet_hours.map(|q| (q.start, q.end));
+        assert_eq!(
+            quiet,
+            NaiveTime::from_hms_opt(18, 0, 0).zip(NaiveTime::from_hms_opt(8, 30, 0))
+        );
+        assert!(!config.notifications.work.sound);
+        assert!(config.notifications.work.desktop);
+
+        assert!(Config::parse(
+            "[notifications]\nquiet_hours = { start = \"6pm\", end = \"08:00\" }"
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_watcher_reloads_on_change() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "[theme]\naccent = \"red\"")?;
+
+        let mut watcher = ConfigWatcher::new(path.clone());
+        assert!(watcher.check().is_none());
+
+        fs::write(&path, "[theme]\naccent = \"green\"")?;
+        // Make the change visible even on filesystems with coarse timestamps
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(later)?;
+
+        let reloaded = watcher.check().and_then(Result::ok);
+        assert_eq!(reloaded.map(|c| c.theme.accent), Some(Color::Green));
+        assert!(watcher.check().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_changes() -> Result<(), io::Error> {
+        let old = Config::default();
+        assert!(old.changes(&old.clone()).is_empty());
+
+        let new = Config::parse(
+            r#"
+            [timer]
+            work = 50
+
+            [[plugins]]
+            title = "Date"
+            command = "date"
+            "#,
+        )?;
+        let changes = old.changes(&new);
+        assert_eq!(changes.applied, vec!["timer durations"]);
+        assert_eq!(changes.needs_restart, vec!["plugins"]);
+        assert_eq!(
+            changes.summary(),
+            "Reloaded timer durations; restart to apply plugins"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_file_relative_to_config() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(PROJECT_CONFIG_NAME);
+        fs::write(&path, "task_file = \"notes/tasks.md\"")?;
+
+        let (config, error) = Config::load(&path);
+        assert!(error.is_none());
+        assert_eq!(
+            config.task_file,
+            Some(temp_dir.path().join("notes").join("tasks.md"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_discover_project() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        let nested = root.join("src").join("deep");
+        fs::create_dir_all(&nested)?;
+
+        assert_eq!(discover_project(&nested), None);
+
+        // TODO.md in an ancestor is found
+        fs::write(root.join(PROJECT_TASKS_NAME), "- [ ] Task")?;
+        assert_eq!(
+            discover_project(&nested),
+            Some(ProjectFile::Tasks(root.join(PROJECT_TASKS_NAME)))
+        );
+
+        // A project config wins over a TODO.md in the same directory
+        fs::write(root.join(PROJECT_CONFIG_NAME), "")?;
+        assert_eq!(
+            discover_project(&nested),
+            Some(ProjectFile::Config(root.join(PROJECT_CONFIG_NAME)))
+        );
+
+        // The nearest match wins
+        fs::write(nested.join(PROJECT_TASKS_NAME), "")?;
+        assert_eq!(
+            discover_project(&nested),
+            Some(ProjectFile::Tasks(nested.join(PROJECT_TASKS_NAME)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_fields() {
+        assert!(Config::parse("colour = \"red\"").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Days, Local, NaiveTime, TimeDelta};
+
+use crate::app::App;
+use crate::history::SessionRecord;
+use crate::task::TaskSection;
+use crate::timer::SessionType;
+
+/// Countdown speed-up, so a work session takes 25 s
+const DEMO_SPEED: u32 = 60;
+
+const BACKLOG: [&str; 5] = [
+    "Draft the release announcement",
+    "Review open pull requests",
+    "Update onboarding docs",
+    "Triage new bug reports",
+    "Plan next sprint",
+];
+const CURRENT: [&str; 2] = [
+    "Write integration tests for sync",
+    "Reply to design feedback",
+];
+const COMPLETED: [&str; 3] = [
+    "Fix flaky CI job",
+    "Benchmark the parser",
+    "Clean up old branches",
+];
+
+/// Sessions in a typical morning: type, minutes, pauses, and idle minutes before the next one
+const DAY: [(SessionType, u64, u32, u64); 8] = [
+    (SessionType::Work, 25, 0, 0),
+    (SessionType::ShortBreak, 5, 0, 0),
+    (SessionType::Work, 25, 1, 0),
+    (SessionType::ShortBreak, 5, 0, 12),
+    (SessionType::Work, 25, 0, 0),
+    (SessionType::ShortBreak, 5, 0, 0),
+    (SessionType::Work, 25, 2, 0),
+    (SessionType::LongBreak, 15, 0, 0),
+];
+
+/// Fill the app with made-up tasks and history and speed up the timer, for screenshots
+/// that never show a real task list. Syncing is turned off so the made-up tasks can't
+/// reach the default task file.
+pub fn populate(app: &mut App) {
+    for (texts, section) in [
+        (&BACKLOG[..], TaskSection::Backlog),
+        (&CURRENT[..], TaskSection::Current),
+        (&COMPLETED[..], TaskSection::Completed),
+    ] {
+        for text in texts {
+            app.tasks_panel.add_task(*text, section);
+        }
+    }
+    for _ in 0..3 {
+        app.tasks_panel.record_pomodoro().ok();
+    }
+
+    let now = Local::now();
+    for days_back in (1..=2).rev() {
+        let start = now
+            .checked_sub_days(Days::new(days_back))
+            .and_then(|day| day.with_time(NaiveTime::MIN).single())
+            .map(|midnight| midnight + TimeDelta::hours(9));
+        if let Some(start) = start {
+            record_day(app, start, now);
+        }
+    }
+    // Today's morning ends just before now
+    record_day(app, now - TimeDelta::minutes(190), now);
+
+    app.timer.set_speed(DEMO_SPEED);
+    app.tasks_panel.disable_file();
+}
+
+fn record_day(app: &mut App, start: DateTime<Local>, now: DateTime<Local>) {
+    let tasks = CURRENT.iter().chain(&COMPLETED).cycle();
+    let mut end = start;
+    for ((session, mins, interruptions, idle), task) in DAY.into_iter().zip(tasks) {
+        let duration = Duration::from_mins(mins);
+        end += duration;
+        if end > now {
+            return;
+        }
+        let record = SessionRecord {
+            end,
+            session,
+            duration,
+            interruptions,
+            task: (session == SessionType::Work).then(|| (*task).to_string()),
+            paused_at: Vec::new(),
+        };
+        app.history.record(record).ok();
+        end += Duration::from_mins(idle);
+    }
+}
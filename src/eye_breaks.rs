@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+/// Unbroken work after which to rest the eyes
+const INTERVAL: Duration = Duration::from_mins(20);
+
+/// Reminds to look away every 20 minutes of running work, the 20-20-20 rule, apart from the
+/// pomodoro breaks
+#[derive(Default)]
+pub struct EyeBreaks {
+    /// When the current stretch of running work, or the last reminder in it, began
+    since: Option<Instant>,
+}
+
+impl EyeBreaks {
+    /// Whether a reminder is due at `now`; pausing or stopping work starts the count again
+    pub fn tick(&mut self, working: bool, now: Instant) -> bool {
+        if !working {
+            self.since = None;
+            return false;
+        }
+        let since = *self.since.get_or_insert(now);
+        if now.saturating_duration_since(since) < INTERVAL {
+            return false;
+        }
+        self.since = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reminds_after_unbroken_work() {
+        let start = Instant::now();
+        let at = |mins| start + Duration::from_mins(mins);
+        let mut eye_breaks = EyeBreaks::default();
+
+        assert!(!eye_breaks.tick(true, start));
+        assert!(!eye_breaks.tick(true, at(19)));
+        assert!(eye_breaks.tick(true, at(20)));
+        assert!(!eye_breaks.tick(true, at(21)));
+        assert!(eye_breaks.tick(true, at(40)));
+
+        // A pause breaks the stretch
+        assert!(!eye_breaks.tick(false, at(45)));
+        assert!(!eye_breaks.tick(true, at(50)));
+        assert!(!eye_breaks.tick(true, at(65)));
+        assert!(eye_breaks.tick(true, at(70)));
+    }
+}
@@ -1,19 +1,75 @@
-use std::fs;
-use std::io;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, File, TryLockError};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use chrono::NaiveDate;
+
+use crate::backup::Backups;
 use crate::overlays::{SyncItem, SyncResolution};
+use crate::task::{resolve_due_dates, sanitize, Task};
+
+/// Appended to a task line once per completed pomodoro, e.g. `- [ ] Fix parser 🍅🍅`
+const POMODORO_MARKER: char = '🍅';
+
+/// Ends a completed task line with the day it was completed, after any pomodoros, e.g.
+/// `- [x] Fix parser 🍅 ✅ 2024-06-05` as the Obsidian Tasks plugin writes it
+const DONE_MARKER: char = '✅';
+
+/// Gives the day a task was added, after any pomodoros and before any done marker, e.g.
+/// `- [ ] Fix parser 🍅 ➕ 2024-06-01`
+const CREATED_MARKER: char = '➕';
 
 /// Parsed task file: incomplete and complete task text vectors.
+#[derive(Default)]
 pub struct ParsedTasks {
     pub incomplete: Vec<String>,
     pub complete: Vec<String>,
+    /// Pomodoro tallies by task text, for tasks with at least one
+    pub pomodoros: HashMap<String, u32>,
+    /// Notes by task text, from the lines indented under each task
+    pub notes: HashMap<String, String>,
+    /// Completion days by task text, for complete tasks with a done marker
+    pub completed_on: HashMap<String, NaiveDate>,
+    /// Days tasks were added by task text, for tasks with a created marker
+    pub created_on: HashMap<String, NaiveDate>,
+    /// Projects by task text, for tasks under a `## ` header
+    pub projects: HashMap<String, String>,
+}
+
+impl ParsedTasks {
+    /// The task with `text`, carrying what the file records about it
+    pub fn task(&self, text: String) -> Task {
+        Task {
+            pomodoros: self.pomodoros.get(&text).copied().unwrap_or(0),
+            note: self.notes.get(&text).cloned().unwrap_or_default(),
+            completed_on: self.completed_on.get(&text).copied(),
+            created_on: self.created_on.get(&text).copied(),
+            project: self.projects.get(&text).cloned(),
+            ..Task::new(text)
+        }
+    }
 }
 
+/// Least time between backups of the task file, so the several writes of one sync make one
+const BACKUP_INTERVAL: Duration = Duration::from_mins(5);
+
 /// Handles reading/writing the markdown task file.
 pub struct TaskFile {
     path: PathBuf,
     original_lines: Vec<String>,
+    backups: Backups,
+    last_backup: Option<Instant>,
+    /// Why the last backup failed, kept to report as the write goes ahead without one
+    backup_error: Option<io::Error>,
+    /// Folder of lock files, until which the file is not locked
+    locks: Option<PathBuf>,
+    /// Held while the file is open, so a second instance can tell it is in use
+    lock: Option<File>,
+    /// Another instance held the lock, so the file is not written
+    read_only: bool,
 }
 
 impl TaskFile {
@@ -21,119 +77,773 @@ impl TaskFile {
     ///
     /// Returns the `TaskFile` handle and parsed tasks.
     pub fn load(path: PathBuf) -> Result<(Self, ParsedTasks), io::Error> {
-        let content = fs::read_to_string(&path)?;
-        let original_lines: Vec<String> = content.lines().map(String::from).collect();
+        let (original_lines, _) = read_lines(&path)?;
         let parsed = parse_task_lines(&original_lines);
         Ok((
             Self {
                 path,
                 original_lines,
+                backups: Backups::new(),
+                last_backup: None,
+                backup_error: None,
+                locks: None,
+                lock: None,
+                read_only: false,
             },
             parsed,
         ))
     }
 
+    /// Lock the file through a lock file in `dir`, making it read-only here if another
+    /// instance already has it
+    pub fn lock_in(&mut self, dir: PathBuf) {
+        self.release_lock();
+        (self.lock, self.read_only) = match take_lock(&self.path, &dir) {
+            Ok(Some(lock)) => (Some(lock), false),
+            Ok(None) => (None, true),
+            // Where no lock can be taken, other instances just go unnoticed
+            Err(_) => (None, false),
+        };
+        self.locks = Some(dir);
+    }
+
+    /// Remove the lock file while it is still held, so no other instance takes it first
+    fn release_lock(&mut self) {
+        if let (Some(lock), Some(dir)) = (self.lock.take(), &self.locks) {
+            let _ = fs::remove_file(lock_path(&self.path, dir));
+            drop(lock);
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn set_backups(&mut self, backups: Backups) {
+        self.backups = backups;
+    }
+
+    pub const fn backups(&self) -> &Backups {
+        &self.backups
+    }
+
+    /// Why the last backup before a write failed, if it did since this was last asked
+    pub const fn take_backup_error(&mut self) -> Option<io::Error> {
+        self.backup_error.take()
+    }
+
+    /// Whether another instance had the file open when it was loaded, so it is not written
+    pub const fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Write the file despite another instance having it open, taking the lock if it has
+    /// since been let go
+    pub fn ignore_lock(&mut self) {
+        self.read_only = false;
+        if let (None, Some(dir)) = (&self.lock, &self.locks) {
+            self.lock = take_lock(&self.path, dir).ok().flatten();
+        }
+    }
+
+    fn check_writable(&self) -> Result<(), io::Error> {
+        if self.read_only {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "the task file is open in another pomo-tui, so it is read-only here",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Replace the whole file with `content`, backing up what it held first
+    pub fn replace(&mut self, content: &str) -> Result<(), io::Error> {
+        self.last_backup = None;
+        let (lines, format) = split_lines(content);
+        self.write(lines, format)
+    }
+
+    /// Write `lines` out as the new file, after a backup if none was made lately
+    fn write(&mut self, lines: Vec<String>, format: TextFormat) -> Result<(), io::Error> {
+        self.check_writable()?;
+        if self
+            .last_backup
+            .is_none_or(|at| at.elapsed() >= BACKUP_INTERVAL)
+        {
+            // A failed backup is reported, but doesn't stop the write
+            if let Err(e) = self.backups.save(&self.path) {
+                self.backup_error = Some(e);
+            }
+            self.last_backup = Some(Instant::now());
+        }
+        write_atomic(&self.path, &format.join(&lines))?;
+        self.original_lines = lines;
+        Ok(())
+    }
+
     /// Re-read the task file from disk and return parsed tasks
     pub fn read_tasks(&self) -> Result<ParsedTasks, io::Error> {
-        let content = fs::read_to_string(&self.path)?;
-        let lines: Vec<String> = content.lines().map(String::from).collect();
-        Ok(parse_task_lines(&lines))
+        read_task_file(&self.path)
+    }
+
+    /// Rewrite relative `due:` words on task lines to the dates they mean from `today`, as
+    /// the app does for tasks added there, returning whether any line changed
+    pub fn resolve_due_dates(&mut self, today: NaiveDate) -> Result<bool, io::Error> {
+        let (mut file_lines, format) = read_lines(&self.path)?;
+        let mut changed = false;
+        for line in &mut file_lines {
+            if task_line_text(line).is_none() {
+                continue;
+            }
+            let resolved = resolve_due_dates(line, today);
+            if resolved != *line {
+                *line = resolved;
+                changed = true;
+            }
+        }
+        if changed {
+            self.write(file_lines, format)?;
+        }
+        Ok(changed)
     }
 
     /// Apply sync item resolutions to the file, preserving indentation and line order
+    ///
+    /// Lines already in the resolved state are left as they are, and the file is not written
+    /// at all if no line changes.
     pub fn write_sync(&mut self, items: &[SyncItem]) -> Result<(), io::Error> {
-        let content = fs::read_to_string(&self.path)?;
-        let mut file_lines: Vec<String> = content.lines().map(String::from).collect();
-        let mut used: Vec<usize> = Vec::new();
-        let mut lines_to_remove: Vec<usize> = Vec::new();
+        let (mut file_lines, format) = read_lines(&self.path)?;
+        let mut index = LineIndex::new(&file_lines);
+        let mut lines_to_remove: HashSet<usize> = HashSet::new();
+        let mut new_lines = Vec::new();
+        let mut changed = false;
 
         for item in items {
-            if let Some(line_idx) = find_line_index(&item.text, &file_lines, &used) {
+            if let Some(line_idx) = index.take(&item.text) {
                 let trimmed = file_lines[line_idx].trim();
-                let indent = &file_lines[line_idx][..file_lines[line_idx].len() - trimmed.len()];
-                match item.resolution {
-                    SyncResolution::Incomplete => {
-                        file_lines[line_idx] = format!("{}- [ ] {}", indent, item.text);
-                    }
-                    SyncResolution::Complete => {
-                        file_lines[line_idx] = format!("{}- [x] {}", indent, item.text);
-                    }
+                let is_complete = !trimmed.starts_with("- [ ] ");
+                let checkbox = match item.resolution {
+                    SyncResolution::Incomplete if is_complete => "[ ]",
+                    SyncResolution::Complete if !is_complete => "[x]",
+                    SyncResolution::Incomplete | SyncResolution::Complete => continue,
                     SyncResolution::Remove => {
-                        lines_to_remove.push(line_idx);
+                        // The note goes with its task
+                        lines_to_remove.insert(line_idx);
+                        lines_to_remove.extend(note_lines(&file_lines, line_idx));
+                        changed = true;
+                        continue;
                     }
-                }
-                used.push(line_idx);
+                };
+                let indent = &file_lines[line_idx][..file_lines[line_idx].len() - trimmed.len()];
+                let (rest, done) = split_done(trimmed);
+                let (rest, created) = split_created(rest);
+                let (_, pomodoros) = split_pomodoros(rest);
+                // A task no longer complete loses its completion day
+                let done = done.filter(|_| checkbox == "[x]");
+                let text = with_done(
+                    &with_created(&with_pomodoros(&item.text, pomodoros), created),
+                    done,
+                );
+                file_lines[line_idx] = format!("{indent}- {checkbox} {text}");
+                changed = true;
             } else if item.resolution != SyncResolution::Remove {
                 let new_line = match item.resolution {
                     SyncResolution::Incomplete => format!("- [ ] {}", item.text),
                     SyncResolution::Complete => format!("- [x] {}", item.text),
                     SyncResolution::Remove => unreachable!(),
                 };
-                file_lines.push(new_line);
+                new_lines.push(new_line);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return Ok(());
+        }
+
+        if !lines_to_remove.is_empty() {
+            file_lines = file_lines
+                .into_iter()
+                .enumerate()
+                .filter(|(idx, _)| !lines_to_remove.contains(idx))
+                .map(|(_, line)| line)
+                .collect();
+        }
+        // New tasks have no project yet, so they go above the first project header
+        let at = loose_end(&file_lines);
+        file_lines.splice(at..at, new_lines);
+
+        self.write(file_lines, format)?;
+
+        Ok(())
+    }
+
+    /// Rewrite the pomodoro markers of tasks already in the file, leaving other lines untouched
+    pub fn write_pomodoros(&mut self, tallies: &[(&str, u32)]) -> Result<(), io::Error> {
+        let (mut file_lines, format) = read_lines(&self.path)?;
+        let mut index = LineIndex::new(&file_lines);
+        let mut changed = false;
+
+        for &(text, pomodoros) in tallies {
+            let Some(line_idx) = index.take(text) else {
+                continue;
+            };
+            let line = &file_lines[line_idx];
+            let (rest, done) = split_done(line.trim_end());
+            let (rest, created) = split_created(rest);
+            let (task, current) = split_pomodoros(rest);
+            if current != pomodoros {
+                file_lines[line_idx] = with_done(
+                    &with_created(&with_pomodoros(task, pomodoros), created),
+                    done,
+                );
+                changed = true;
             }
         }
 
-        lines_to_remove.sort_unstable();
-        for idx in lines_to_remove.into_iter().rev() {
-            file_lines.remove(idx);
+        if changed {
+            self.write(file_lines, format)?;
         }
+        Ok(())
+    }
 
-        let output = file_lines.join("\n");
-        fs::write(&self.path, output)?;
-        self.original_lines = file_lines;
+    /// Mark the completion day of complete tasks already in the file, leaving other lines
+    /// untouched
+    pub fn write_completion_days(&mut self, days: &[(&str, NaiveDate)]) -> Result<(), io::Error> {
+        let (mut file_lines, format) = read_lines(&self.path)?;
+        let mut index = LineIndex::new(&file_lines);
+        let mut changed = false;
+
+        for &(text, day) in days {
+            let Some(line_idx) = index.take(text) else {
+                continue;
+            };
+            let line = &file_lines[line_idx];
+            let (rest, done) = split_done(line.trim_end());
+            if done.is_none() && !line.trim_start().starts_with("- [ ] ") {
+                file_lines[line_idx] = with_done(rest, Some(day));
+                changed = true;
+            }
+        }
 
+        if changed {
+            self.write(file_lines, format)?;
+        }
         Ok(())
     }
+
+    /// Mark the day tasks already in the file were added, leaving other lines untouched
+    pub fn write_creation_days(&mut self, days: &[(&str, NaiveDate)]) -> Result<(), io::Error> {
+        let (mut file_lines, format) = read_lines(&self.path)?;
+        let mut index = LineIndex::new(&file_lines);
+        let mut changed = false;
+
+        for &(text, day) in days {
+            let Some(line_idx) = index.take(text) else {
+                continue;
+            };
+            let (rest, done) = split_done(file_lines[line_idx].trim_end());
+            let (rest, created) = split_created(rest);
+            if created.is_none() {
+                file_lines[line_idx] = with_done(&with_created(rest, Some(day)), done);
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.write(file_lines, format)?;
+        }
+        Ok(())
+    }
+
+    /// Move tasks already in the file, with their notes, under the `## ` header of their
+    /// project, adding the header at the end of the file if there is none yet
+    pub fn write_projects(&mut self, projects: &[(&str, &str)]) -> Result<(), io::Error> {
+        let (mut file_lines, format) = read_lines(&self.path)?;
+        let owners = line_projects(&file_lines);
+        let mut index = LineIndex::new(&file_lines);
+        let mut moves = Vec::new();
+
+        for &(text, project) in projects {
+            let Some(line_idx) = index.take(text) else {
+                continue;
+            };
+            if owners[line_idx] != Some(project) {
+                let end = note_lines(&file_lines, line_idx).end;
+                moves.push((line_idx..end, project));
+            }
+        }
+
+        if moves.is_empty() {
+            return Ok(());
+        }
+        // Taken out from the bottom up, so the ranges still to take stay in place
+        moves.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        let mut blocks: Vec<(&str, Vec<String>)> = moves
+            .into_iter()
+            .map(|(range, project)| (project, file_lines.drain(range).collect()))
+            .collect();
+        blocks.reverse();
+        for (project, block) in blocks {
+            if let Some(at) = project_end(&file_lines, project) {
+                file_lines.splice(at..at, block);
+            } else {
+                if file_lines
+                    .last()
+                    .is_some_and(|line| !line.trim().is_empty())
+                {
+                    file_lines.push(String::new());
+                }
+                file_lines.push(format!("## {project}"));
+                file_lines.extend(block);
+            }
+        }
+        self.write(file_lines, format)?;
+        Ok(())
+    }
+
+    /// Replace the notes under tasks already in the file, leaving other lines untouched
+    pub fn write_notes(&mut self, notes: &[(&str, &str)]) -> Result<(), io::Error> {
+        let (mut file_lines, format) = read_lines(&self.path)?;
+        let mut index = LineIndex::new(&file_lines);
+        let mut edits = Vec::new();
+
+        for &(text, note) in notes {
+            let Some(line_idx) = index.take(text) else {
+                continue;
+            };
+            let task_line = &file_lines[line_idx];
+            let indent = &task_line[..indent_len(task_line)];
+            let lines: Vec<String> = note
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| format!("{indent}  {}", line.trim()))
+                .collect();
+            let range = note_lines(&file_lines, line_idx);
+            if file_lines[range.clone()] != lines[..] {
+                edits.push((range, lines));
+            }
+        }
+
+        if edits.is_empty() {
+            return Ok(());
+        }
+        // From the bottom up, so the ranges still to splice stay in place
+        edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, lines) in edits {
+            file_lines.splice(range, lines);
+        }
+        self.write(file_lines, format)?;
+        Ok(())
+    }
+
+    /// The file completed tasks are archived to, beside this one, e.g. `tasks-archive.md`
+    pub fn archive_path(&self) -> PathBuf {
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        self.path.with_file_name(format!("{stem}-archive.md"))
+    }
+
+    /// Move completed tasks out to the archive file, appending them as they stand in the app
+    /// and removing their lines and notes from this file
+    pub fn archive(&mut self, tasks: &[Task]) -> Result<(), io::Error> {
+        self.check_writable()?;
+        let archive_path = self.archive_path();
+        let (mut archive_lines, archive_format) = match read_lines(&archive_path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Vec::new(), TextFormat::default()),
+            read => read?,
+        };
+        for task in tasks {
+            let text = with_done(
+                &with_created(&with_pomodoros(&task.text, task.pomodoros), task.created_on),
+                task.completed_on,
+            );
+            archive_lines.push(format!("- [x] {text}"));
+            archive_lines.extend(task.note.lines().map(|line| format!("  {line}")));
+        }
+        // Written first, so a failure leaves the tasks in this file rather than nowhere
+        write_atomic(&archive_path, &archive_format.join(&archive_lines))?;
+
+        let (file_lines, format) = read_lines(&self.path)?;
+        let mut index = LineIndex::new(&file_lines);
+        let mut lines_to_remove: HashSet<usize> = HashSet::new();
+        for task in tasks {
+            if let Some(line_idx) = index.take(&task.text) {
+                lines_to_remove.insert(line_idx);
+                lines_to_remove.extend(note_lines(&file_lines, line_idx));
+            }
+        }
+        if lines_to_remove.is_empty() {
+            return Ok(());
+        }
+        let file_lines: Vec<String> = file_lines
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| !lines_to_remove.contains(idx))
+            .map(|(_, line)| line)
+            .collect();
+        self.write(file_lines, format)?;
+        Ok(())
+    }
+}
+
+impl Drop for TaskFile {
+    fn drop(&mut self) {
+        self.release_lock();
+    }
+}
+
+const BOM: char = '\u{feff}';
+
+/// Encoding details of the file on disk, reproduced when writing it back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TextFormat {
+    bom: bool,
+    crlf: bool,
+    trailing_newline: bool,
+}
+
+impl Default for TextFormat {
+    /// How a new file is written
+    fn default() -> Self {
+        Self {
+            bom: false,
+            crlf: false,
+            trailing_newline: true,
+        }
+    }
+}
+
+impl TextFormat {
+    fn detect(content: &str) -> Self {
+        Self {
+            bom: content.starts_with(BOM),
+            crlf: content.contains("\r\n"),
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    fn join(self, lines: &[String]) -> String {
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        let mut output = String::new();
+        if self.bom {
+            output.push(BOM);
+        }
+        output.push_str(&lines.join(newline));
+        if self.trailing_newline && !lines.is_empty() {
+            output.push_str(newline);
+        }
+        output
+    }
+}
+
+/// Read and parse the task file at `path`, e.g. from a thread that doesn't own the file
+pub fn read_task_file(path: &Path) -> Result<ParsedTasks, io::Error> {
+    let (lines, _) = read_lines(path)?;
+    Ok(parse_task_lines(&lines))
+}
+
+/// Read the file as lines without their endings or any byte order mark
+fn read_lines(path: &Path) -> Result<(Vec<String>, TextFormat), io::Error> {
+    Ok(split_lines(&fs::read_to_string(path)?))
+}
+
+fn split_lines(content: &str) -> (Vec<String>, TextFormat) {
+    let format = TextFormat::detect(content);
+    let lines = content
+        .strip_prefix(BOM)
+        .unwrap_or(content)
+        .lines()
+        .map(String::from)
+        .collect();
+    (lines, format)
+}
+
+/// Lock the task file at `path` through a lock file in `dir`, or None if another instance
+/// holds it. The lock goes when the returned file is closed.
+fn take_lock(path: &Path, dir: &Path) -> Result<Option<File>, io::Error> {
+    fs::create_dir_all(dir)?;
+    let lock = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(path, dir))?;
+    match lock.try_lock() {
+        Ok(()) => Ok(Some(lock)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+fn lock_path(path: &Path, dir: &Path) -> PathBuf {
+    dir.join(format!("{}.lock", flat_name(path)))
+}
+
+/// A name for the file at `path`: its file name, readable, then a hash of its whole path, so
+/// files in different folders are kept apart, e.g. `tasks_md_5d2a9c3e0b41f786`
+pub fn flat_name(path: &Path) -> String {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let name: String = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    format!(
+        "{}_{:016x}",
+        name.trim_matches('_'),
+        fnv1a(path.as_os_str().as_encoded_bytes())
+    )
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` stays the same across Rust releases, so names
+/// made from it still find their files after an upgrade
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Write `contents` to a temporary file beside `path` and rename it over, so a crash part
+/// way through leaves the old file whole rather than a truncated one
+fn write_atomic(path: &Path, contents: &str) -> Result<(), io::Error> {
+    // Write through a link to the file it points at, rather than replacing the link
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut temp = path.clone().into_os_string();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    let mut file = File::create(&temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    if let Ok(metadata) = fs::metadata(&path) {
+        fs::set_permissions(&temp, metadata.permissions())?;
+    }
+    fs::rename(&temp, &path)
+}
+
+/// Split a trailing pomodoro marker from task text, returning the text and the tally
+fn split_pomodoros(text: &str) -> (&str, u32) {
+    let stripped = text.trim_end_matches(POMODORO_MARKER);
+    let count = text[stripped.len()..].chars().count() as u32;
+    let stripped = stripped.trim_end();
+    if count == 0 || stripped.is_empty() {
+        (text, 0)
+    } else {
+        (stripped, count)
+    }
+}
+
+fn with_pomodoros(text: &str, pomodoros: u32) -> String {
+    if pomodoros == 0 {
+        return text.to_string();
+    }
+    let marker = POMODORO_MARKER.to_string().repeat(pomodoros as usize);
+    format!("{text} {marker}")
+}
+
+/// Split a trailing done marker from task text, returning the text and the completion day
+pub fn split_done(text: &str) -> (&str, Option<NaiveDate>) {
+    split_day(text, DONE_MARKER)
+}
+
+pub fn with_done(text: &str, day: Option<NaiveDate>) -> String {
+    with_day(text, DONE_MARKER, day)
+}
+
+/// Split a trailing created marker from task text, once any done marker is split off,
+/// returning the text and the day the task was added
+pub fn split_created(text: &str) -> (&str, Option<NaiveDate>) {
+    split_day(text, CREATED_MARKER)
+}
+
+pub fn with_created(text: &str, day: Option<NaiveDate>) -> String {
+    with_day(text, CREATED_MARKER, day)
+}
+
+/// Split a trailing `marker` and the day after it from task text
+fn split_day(text: &str, marker: char) -> (&str, Option<NaiveDate>) {
+    let Some((rest, day)) = text.rsplit_once(marker) else {
+        return (text, None);
+    };
+    let rest = rest.trim_end();
+    match NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d") {
+        Ok(day) if !rest.is_empty() => (rest, Some(day)),
+        _ => (text, None),
+    }
+}
+
+fn with_day(text: &str, marker: char, day: Option<NaiveDate>) -> String {
+    day.map_or_else(|| text.to_string(), |day| format!("{text} {marker} {day}"))
+}
+
+/// Level and text of a markdown header line, e.g. `(2, "Garden")` for `## Garden`
+fn header(line: &str) -> Option<(usize, &str)> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    let text = text.strip_prefix(' ')?;
+    (level > 0).then(|| (level, text.trim()))
+}
+
+/// Project each line falls under: the last `## ` header above it, until a `# ` header
+fn line_projects(lines: &[String]) -> Vec<Option<&str>> {
+    let mut project = None;
+    lines
+        .iter()
+        .map(|line| {
+            match header(line) {
+                Some((1, _)) => project = None,
+                Some((2, name)) => project = Some(name),
+                _ => {}
+            }
+            project
+        })
+        .collect()
+}
+
+/// Where a task joins `project`: after the last line under its header, before any blank
+/// lines and the next header at its level or above; None if it has no header
+fn project_end(lines: &[String], project: &str) -> Option<usize> {
+    let start = lines
+        .iter()
+        .position(|line| header(line) == Some((2, project)))?
+        + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|line| header(line).is_some_and(|(level, _)| level <= 2))
+        .map_or(lines.len(), |i| start + i);
+    let last = lines[start..end]
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(start, |i| start + i + 1);
+    Some(last)
+}
+
+/// Where a task with no project joins the file: after the last line before the first
+/// project header, or at the end if there is none
+fn loose_end(lines: &[String]) -> usize {
+    let Some(first) = lines
+        .iter()
+        .position(|line| header(line).is_some_and(|(level, _)| level == 2))
+    else {
+        return lines.len();
+    };
+    lines[..first]
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |i| i + 1)
+}
+
+fn indent_len(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Lines after the task at `line_idx` holding its note: those indented deeper than the task
+/// that aren't tasks themselves, up to the first that isn't or is blank
+fn note_lines(lines: &[String], line_idx: usize) -> Range<usize> {
+    let indent = indent_len(&lines[line_idx]);
+    let start = line_idx + 1;
+    let len = lines[start..]
+        .iter()
+        .take_while(|line| {
+            !line.trim().is_empty() && indent_len(line) > indent && task_line_text(line).is_none()
+        })
+        .count();
+    start..start + len
 }
 
 /// Parse markdown task lines into incomplete and complete text vectors
 fn parse_task_lines(lines: &[String]) -> ParsedTasks {
     let mut incomplete = Vec::new();
     let mut complete = Vec::new();
-
-    for line in lines {
+    let mut pomodoros = HashMap::new();
+    let mut notes = HashMap::new();
+    let mut completed_on = HashMap::new();
+    let mut created_on = HashMap::new();
+    let mut projects = HashMap::new();
+    let owners = line_projects(lines);
+
+    for (line_idx, line) in lines.iter().enumerate() {
         let trimmed = line.trim();
-        if let Some(text) = trimmed.strip_prefix("- [ ] ") {
-            if !text.is_empty() {
-                incomplete.push(text.to_string());
-            }
+        let (tasks, text) = if let Some(text) = trimmed.strip_prefix("- [ ] ") {
+            (&mut incomplete, text)
         } else if let Some(text) = trimmed
             .strip_prefix("- [x] ")
             .or_else(|| trimmed.strip_prefix("- [X] "))
         {
-            if !text.is_empty() {
-                complete.push(text.to_string());
+            (&mut complete, text)
+        } else {
+            continue;
+        };
+        let (text, done) = split_done(text);
+        let (text, created) = split_created(text);
+        let (text, count) = split_pomodoros(text);
+        let text = sanitize(text);
+        if !text.is_empty() {
+            if count > 0 {
+                pomodoros.insert(text.clone(), count);
+            }
+            if let Some(day) = done {
+                completed_on.insert(text.clone(), day);
+            }
+            if let Some(day) = created {
+                created_on.insert(text.clone(), day);
             }
+            if let Some(project) = owners[line_idx] {
+                projects.insert(text.clone(), project.to_string());
+            }
+            let note: Vec<&str> = lines[note_lines(lines, line_idx)]
+                .iter()
+                .map(|line| line.trim())
+                .collect();
+            if !note.is_empty() {
+                notes.insert(text.clone(), note.join("\n"));
+            }
+            tasks.push(text);
         }
     }
 
     ParsedTasks {
         incomplete,
         complete,
+        pomodoros,
+        notes,
+        completed_on,
+        created_on,
+        projects,
     }
 }
 
-/// Find the line index of a task, skipping already-used lines to handle duplicates
-fn find_line_index(task_text: &str, file_lines: &[String], used_lines: &[usize]) -> Option<usize> {
-    for (idx, line) in file_lines.iter().enumerate() {
-        if used_lines.contains(&idx) {
-            continue;
-        }
-        let trimmed = line.trim();
-        if let Some(text) = trimmed.strip_prefix("- [ ] ") {
-            if text == task_text {
-                return Some(idx);
-            }
-        } else if let Some(text) = trimmed
-            .strip_prefix("- [x] ")
-            .or_else(|| trimmed.strip_prefix("- [X] "))
-        {
-            if text == task_text {
-                return Some(idx);
+/// Task text of a checklist line as the app sees it, without its pomodoro marker
+fn task_line_text(line: &str) -> Option<String> {
+    let trimmed = line.trim();
+    let text = trimmed
+        .strip_prefix("- [ ] ")
+        .or_else(|| trimmed.strip_prefix("- [x] "))
+        .or_else(|| trimmed.strip_prefix("- [X] "))?;
+    let text = split_created(split_done(text).0).0;
+    Some(sanitize(split_pomodoros(text).0))
+}
+
+/// Line numbers of task lines by their text, so finding a task does not scan the file
+struct LineIndex {
+    lines: HashMap<String, VecDeque<usize>>,
+}
+
+impl LineIndex {
+    fn new(file_lines: &[String]) -> Self {
+        let mut lines: HashMap<String, VecDeque<usize>> = HashMap::new();
+        for (idx, line) in file_lines.iter().enumerate() {
+            if let Some(text) = task_line_text(line) {
+                lines.entry(text).or_default().push_back(idx);
             }
         }
+        Self { lines }
+    }
+
+    /// Take the first line holding `task_text` not already taken, so duplicates map in file order
+    fn take(&mut self, task_text: &str) -> Option<usize> {
+        self.lines.get_mut(task_text)?.pop_front()
     }
-    None
 }
 
 #[cfg(test)]
@@ -187,25 +897,374 @@ mod tests {
     }
 
     #[test]
-    fn test_find_line_index() {
+    fn test_parse_pomodoro_markers() {
+        let lines = vec![
+            "- [ ] Fix parser 🍅🍅".to_string(),
+            "- [x] Write docs🍅".to_string(),
+            "- [ ] No pomodoros".to_string(),
+            "- [ ] 🍅".to_string(), // Marker alone is the task text
+        ];
+        let parsed = parse_task_lines(&lines);
+        assert_eq!(parsed.incomplete, vec!["Fix parser", "No pomodoros", "🍅"]);
+        assert_eq!(parsed.complete, vec!["Write docs"]);
+        assert_eq!(parsed.pomodoros.get("Fix parser"), Some(&2));
+        assert_eq!(parsed.pomodoros.get("Write docs"), Some(&1));
+        assert_eq!(parsed.pomodoros.len(), 2);
+
+        assert_eq!(LineIndex::new(&lines).take("Fix parser"), Some(0));
+
+        // An estimate is part of the text, ahead of the tally
+        let parsed = parse_task_lines(&["- [ ] Fix login ~3 🍅🍅".to_string()]);
+        assert_eq!(parsed.incomplete, vec!["Fix login ~3"]);
+        assert_eq!(parsed.pomodoros.get("Fix login ~3"), Some(&2));
+    }
+
+    #[test]
+    fn test_parse_done_markers() {
+        let lines = vec![
+            "- [x] Fix parser 🍅🍅 ✅ 2024-06-05".to_string(),
+            "- [x] Write docs ✅ soon".to_string(),
+        ];
+        let parsed = parse_task_lines(&lines);
+        assert_eq!(parsed.complete, vec!["Fix parser", "Write docs ✅ soon"]);
+        assert_eq!(parsed.pomodoros.get("Fix parser"), Some(&2));
+        assert_eq!(
+            parsed.completed_on.get("Fix parser"),
+            NaiveDate::from_ymd_opt(2024, 6, 5).as_ref()
+        );
+        assert_eq!(parsed.completed_on.len(), 1);
+    }
+
+    #[test]
+    fn test_write_completion_days() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(
+            &file_path,
+            "- [x] Task 1 🍅\n- [ ] Task 2\n- [x] Task 3 ✅ 2024-06-01\n",
+        )?;
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+        let day = NaiveDate::from_ymd_opt(2024, 6, 5).unwrap_or_default();
+
+        task_file.write_completion_days(&[("Task 1", day), ("Task 2", day), ("Task 3", day)])?;
+        task_file.write_pomodoros(&[("Task 1", 2)])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "- [x] Task 1 🍅🍅 ✅ 2024-06-05\n- [ ] Task 2\n- [x] Task 3 ✅ 2024-06-01\n"
+        );
+
+        // Reopening a task drops its day
+        task_file.write_sync(&[SyncItem::new(
+            "Task 3".to_string(),
+            SyncResolution::Incomplete,
+        )])?;
+        assert!(fs::read_to_string(&file_path)?.ends_with("- [ ] Task 3\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_projects() {
+        let lines: Vec<String> = [
+            "# Tasks",
+            "- [ ] Loose end",
+            "## Garden",
+            "- [ ] Plant beans",
+            "### Tools",
+            "- [x] Oil shears",
+            "## Website",
+            "- [ ] Fix header",
+            "# Someday",
+            "- [ ] Learn piano",
+        ]
+        .map(String::from)
+        .to_vec();
+        let parsed = parse_task_lines(&lines);
+        let project = |text: &str| parsed.projects.get(text).map(String::as_str);
+        assert_eq!(project("Loose end"), None);
+        assert_eq!(project("Plant beans"), Some("Garden"));
+        // Deeper headers stay within the project
+        assert_eq!(project("Oil shears"), Some("Garden"));
+        assert_eq!(project("Fix header"), Some("Website"));
+        assert_eq!(project("Learn piano"), None);
+    }
+
+    #[test]
+    fn test_write_without_backup() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(&file_path, "- [ ] Task 1\n")?;
+        // A file where the backups folder should be, so no backup can be made
+        let blocked = temp_dir.path().join("backups");
+        fs::write(&blocked, "")?;
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+        task_file.set_backups(Backups::in_dir(blocked, 5));
+
+        task_file.write_pomodoros(&[("Task 1", 1)])?;
+        assert_eq!(fs::read_to_string(&file_path)?, "- [ ] Task 1 🍅\n");
+        assert!(task_file.take_backup_error().is_some());
+        assert!(task_file.take_backup_error().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_projects() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(
+            &file_path,
+            "## Garden\n- [ ] Plant beans\n\n## Website\n- [ ] Fix header\n- [ ] Water roses\n  Before noon\n- [ ] New page\n",
+        )?;
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+
+        task_file.write_projects(&[
+            ("Water roses", "Garden"),
+            ("Fix header", "Website"),
+            ("New page", "Blog"),
+        ])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "## Garden\n- [ ] Plant beans\n- [ ] Water roses\n  Before noon\n\n\
+             ## Website\n- [ ] Fix header\n\n\
+             ## Blog\n- [ ] New page\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_sync_adds_above_projects() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(
+            &file_path,
+            "# Tasks\n- [ ] Loose end\n\n## Garden\n- [ ] Plant beans\n",
+        )?;
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+
+        task_file.write_sync(&[SyncItem::new(
+            "New in app".to_string(),
+            SyncResolution::Incomplete,
+        )])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "# Tasks\n- [ ] Loose end\n- [ ] New in app\n\n## Garden\n- [ ] Plant beans\n"
+        );
+        let (_, parsed) = TaskFile::load(file_path)?;
+        assert_eq!(parsed.projects.get("New in app"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_creation_days() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(
+            &file_path,
+            "- [ ] Task 1 🍅\n- [x] Task 2 ✅ 2024-06-05\n- [ ] Task 3 ➕ 2024-05-01\n",
+        )?;
+        let (mut task_file, parsed) = TaskFile::load(file_path.clone())?;
+        assert_eq!(parsed.incomplete, vec!["Task 1", "Task 3"]);
+        assert_eq!(
+            parsed.created_on.get("Task 3"),
+            NaiveDate::from_ymd_opt(2024, 5, 1).as_ref()
+        );
+        let day = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap_or_default();
+
+        task_file.write_creation_days(&[("Task 1", day), ("Task 2", day), ("Task 3", day)])?;
+        task_file.write_pomodoros(&[("Task 1", 2), ("Task 2", 1)])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "- [ ] Task 1 🍅🍅 ➕ 2024-06-01\n\
+             - [x] Task 2 🍅 ➕ 2024-06-01 ✅ 2024-06-05\n\
+             - [ ] Task 3 ➕ 2024-05-01\n"
+        );
+        let parsed = task_file.read_tasks()?;
+        assert_eq!(parsed.complete, vec!["Task 2"]);
+        assert_eq!(parsed.pomodoros.get("Task 2"), Some(&1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(
+            &file_path,
+            "# Tasks\n- [ ] Task 1\n- [x] Task 2\n  Its note\n- [x] Task 3\n",
+        )?;
+        let archive_path = temp_dir.path().join("tasks-archive.md");
+        fs::write(&archive_path, "- [x] Task 0")?;
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+        assert_eq!(task_file.archive_path(), archive_path);
+
+        let task = Task {
+            pomodoros: 1,
+            note: "Its note".to_string(),
+            completed_on: NaiveDate::from_ymd_opt(2024, 6, 5),
+            ..Task::new("Task 2".to_string())
+        };
+        task_file.archive(&[task])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "# Tasks\n- [ ] Task 1\n- [x] Task 3\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&archive_path)?,
+            "- [x] Task 0\n- [x] Task 2 🍅 ✅ 2024-06-05\n  Its note"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_notes() {
+        let lines: Vec<String> = [
+            "- [ ] Fix parser 🍅",
+            "  Fails on tabs",
+            "    see #12",
+            "  - [ ] Add a test",
+            "    For the tab case",
+            "",
+            "  Not a note after the gap",
+            "- [x] Write docs",
+        ]
+        .map(String::from)
+        .to_vec();
+        let parsed = parse_task_lines(&lines);
+        assert_eq!(parsed.incomplete, vec!["Fix parser", "Add a test"]);
+        assert_eq!(
+            parsed.notes.get("Fix parser").map(String::as_str),
+            Some("Fails on tabs\nsee #12")
+        );
+        assert_eq!(
+            parsed.notes.get("Add a test").map(String::as_str),
+            Some("For the tab case")
+        );
+        assert_eq!(parsed.notes.len(), 2);
+    }
+
+    #[test]
+    fn test_write_notes() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(
+            &file_path,
+            "- [ ] Task 1\n  Old note\n  - [ ] Task 2\n- [ ] Task 3\n",
+        )?;
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+
+        task_file.write_notes(&[
+            ("Task 1", ""),
+            ("Task 2", "First\n\nSecond"),
+            ("Task 3", "Third"),
+        ])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "- [ ] Task 1\n  - [ ] Task 2\n    First\n    Second\n- [ ] Task 3\n  Third\n"
+        );
+
+        // Removing a task takes its note with it
+        task_file.write_sync(&[SyncItem::new("Task 2".to_string(), SyncResolution::Remove)])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "- [ ] Task 1\n- [ ] Task 3\n  Third\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_pomodoros() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("test_tasks.md");
+        fs::write(&file_path, "# Tasks\n  - [ ] Task 1 🍅\n- [ ] Task 2")?;
+
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+        task_file.write_pomodoros(&[("Task 1", 3), ("Task 2", 0), ("Missing", 1)])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "# Tasks\n  - [ ] Task 1 🍅🍅🍅\n- [ ] Task 2"
+        );
+
+        // Changing completion keeps the tally
+        task_file.write_sync(&[SyncItem::new(
+            "Task 1".to_string(),
+            SyncResolution::Complete,
+        )])?;
+        assert!(fs::read_to_string(&file_path)?.contains("  - [x] Task 1 🍅🍅🍅"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_sync_skips_unchanged_file() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("test_tasks.md");
+        fs::write(&file_path, "- [ ] Task 1\n- [X] Task 2\n")?;
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        fs::File::options()
+            .write(true)
+            .open(&file_path)?
+            .set_modified(old)?;
+
+        let (mut task_file, _) = TaskFile::load(file_path.clone())?;
+        task_file.write_sync(&[
+            SyncItem::new("Task 1".to_string(), SyncResolution::Incomplete),
+            SyncItem::new("Task 2".to_string(), SyncResolution::Complete),
+        ])?;
+
+        assert_eq!(fs::metadata(&file_path)?.modified()?, old);
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "- [ ] Task 1\n- [X] Task 2\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_preserves_bom_and_line_endings() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("test_tasks.md");
+        fs::write(&file_path, "\u{feff}- [ ] Task 1\r\n- [ ] Task 2\r\n")?;
+
+        let (mut task_file, parsed) = TaskFile::load(file_path.clone())?;
+        assert_eq!(parsed.incomplete, vec!["Task 1", "Task 2"]);
+        task_file.write_sync(&[SyncItem::new(
+            "Task 1".to_string(),
+            SyncResolution::Complete,
+        )])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "\u{feff}- [x] Task 1\r\n- [ ] Task 2\r\n"
+        );
+
+        task_file.write_pomodoros(&[("Task 2", 1)])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "\u{feff}- [x] Task 1\r\n- [ ] Task 2 🍅\r\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_index() {
         let lines = vec![
             "# Header".to_string(),
             "- [ ] Task 1".to_string(),
             "- [x] Task 2".to_string(),
             "- [ ] Task 3".to_string(),
+            "  - [X] Task 1".to_string(),
         ];
+        let mut index = LineIndex::new(&lines);
 
         // Find incomplete task
-        assert_eq!(find_line_index("Task 1", &lines, &[]), Some(1));
+        assert_eq!(index.take("Task 1"), Some(1));
 
         // Find complete task
-        assert_eq!(find_line_index("Task 2", &lines, &[]), Some(2));
+        assert_eq!(index.take("Task 2"), Some(2));
 
         // Task not found
-        assert_eq!(find_line_index("Nonexistent", &lines, &[]), None);
+        assert_eq!(index.take("Nonexistent"), None);
 
-        // Task already used
-        assert_eq!(find_line_index("Task 1", &lines, &[1]), None);
+        // Duplicates are taken in file order, then run out
+        assert_eq!(index.take("Task 1"), Some(4));
+        assert_eq!(index.take("Task 1"), None);
     }
 
     #[test]
@@ -226,6 +1285,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_second_load_is_read_only() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("tasks.md");
+        fs::write(&file_path, "- [ ] Task 1\n")?;
+
+        let locks = temp_dir.path().join("locks");
+        let (mut first, _) = TaskFile::load(file_path.clone())?;
+        first.lock_in(locks.clone());
+        let (mut second, _) = TaskFile::load(file_path.clone())?;
+        second.lock_in(locks.clone());
+        assert!(!first.is_read_only());
+        assert!(second.is_read_only());
+        assert!(second.write_pomodoros(&[("Task 1", 1)]).is_err());
+        assert_eq!(fs::read_to_string(&file_path)?, "- [ ] Task 1\n");
+
+        // Once the first lets go, the lock is free to take
+        drop(first);
+        second.ignore_lock();
+        assert!(second.lock.is_some());
+        second.write_pomodoros(&[("Task 1", 1)])?;
+        let (mut third, _) = TaskFile::load(file_path)?;
+        third.lock_in(locks.clone());
+        assert!(third.is_read_only());
+
+        // The lock file goes with the instance holding it
+        drop(third);
+        drop(second);
+        assert_eq!(fs::read_dir(&locks)?.count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_flat_name() {
+        let name = flat_name(Path::new("/a/b_c/tasks.md"));
+        assert!(name.starts_with("tasks_md_"));
+        assert_eq!(name, flat_name(Path::new("/a/b_c/tasks.md")));
+        // Folders that would read the same with `_` for `/` are still kept apart
+        assert_ne!(name, flat_name(Path::new("/a_b/c/tasks.md")));
+    }
+
     #[test]
     fn test_task_file_read_tasks() -> Result<(), io::Error> {
         let temp_dir = TempDir::new()?;
@@ -258,10 +1359,10 @@ mod tests {
 
         let (mut task_file, _) = TaskFile::load(file_path.clone())?;
 
-        let sync_items = vec![SyncItem {
-            text: "Task 1".to_string(),
-            resolution: SyncResolution::Complete,
-        }];
+        let sync_items = vec![SyncItem::new(
+            "Task 1".to_string(),
+            SyncResolution::Complete,
+        )];
 
         task_file.write_sync(&sync_items)?;
 
@@ -272,6 +1373,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_sync_keeps_tags() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("test_tasks.md");
+        fs::write(&file_path, "- [ ] Plan #work  #q3 🍅\n")?;
+
+        let (mut task_file, parsed) = TaskFile::load(file_path.clone())?;
+        assert_eq!(parsed.incomplete, ["Plan #work  #q3"]);
+
+        task_file.write_sync(&[SyncItem::new(
+            "Plan #work  #q3".to_string(),
+            SyncResolution::Complete,
+        )])?;
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "- [x] Plan #work  #q3 🍅\n"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_write_sync_mark_incomplete() -> Result<(), io::Error> {
         let temp_dir = TempDir::new()?;
@@ -282,10 +1404,10 @@ mod tests {
 
         let (mut task_file, _) = TaskFile::load(file_path.clone())?;
 
-        let sync_items = vec![SyncItem {
-            text: "Task 1".to_string(),
-            resolution: SyncResolution::Incomplete,
-        }];
+        let sync_items = vec![SyncItem::new(
+            "Task 1".to_string(),
+            SyncResolution::Incomplete,
+        )];
 
         task_file.write_sync(&sync_items)?;
 
@@ -306,10 +1428,10 @@ mod tests {
 
         let (mut task_file, _) = TaskFile::load(file_path.clone())?;
 
-        let sync_items = vec![SyncItem {
-            text: "New Task".to_string(),
-            resolution: SyncResolution::Incomplete,
-        }];
+        let sync_items = vec![SyncItem::new(
+            "New Task".to_string(),
+            SyncResolution::Incomplete,
+        )];
 
         task_file.write_sync(&sync_items)?;
 
@@ -330,10 +1452,7 @@ mod tests {
 
         let (mut task_file, _) = TaskFile::load(file_path.clone())?;
 
-        let sync_items = vec![SyncItem {
-            text: "Task 2".to_string(),
-            resolution: SyncResolution::Remove,
-        }];
+        let sync_items = vec![SyncItem::new("Task 2".to_string(), SyncResolution::Remove)];
 
         task_file.write_sync(&sync_items)?;
 
@@ -355,10 +1474,10 @@ mod tests {
 
         let (mut task_file, _) = TaskFile::load(file_path.clone())?;
 
-        let sync_items = vec![SyncItem {
-            text: "Indented task".to_string(),
-            resolution: SyncResolution::Complete,
-        }];
+        let sync_items = vec![SyncItem::new(
+            "Indented task".to_string(),
+            SyncResolution::Complete,
+        )];
 
         task_file.write_sync(&sync_items)?;
 
@@ -379,22 +1498,10 @@ mod tests {
         let (mut task_file, _) = TaskFile::load(file_path.clone())?;
 
         let sync_items = vec![
-            SyncItem {
-                text: "Task 1".to_string(),
-                resolution: SyncResolution::Complete,
-            },
-            SyncItem {
-                text: "Task 2".to_string(),
-                resolution: SyncResolution::Incomplete,
-            },
-            SyncItem {
-                text: "Task 3".to_string(),
-                resolution: SyncResolution::Remove,
-            },
-            SyncItem {
-                text: "New Task 4".to_string(),
-                resolution: SyncResolution::Incomplete,
-            },
+            SyncItem::new("Task 1".to_string(), SyncResolution::Complete),
+            SyncItem::new("Task 2".to_string(), SyncResolution::Incomplete),
+            SyncItem::new("Task 3".to_string(), SyncResolution::Remove),
+            SyncItem::new("New Task 4".to_string(), SyncResolution::Incomplete),
         ];
 
         task_file.write_sync(&sync_items)?;
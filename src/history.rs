@@ -0,0 +1,325 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::timer::SessionType;
+
+/// A finished session, stored one per line as
+/// `<end RFC 3339>\t<session>\t<seconds>\t<interruptions>\t<task>\t<pause times>`,
+/// the pause times each RFC 3339 and separated by commas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRecord {
+    pub end: DateTime<Local>,
+    pub session: SessionType,
+    /// Time the timer actually ran
+    pub duration: Duration,
+    /// Times the session was paused
+    pub interruptions: u32,
+    /// Task worked on, for work sessions
+    pub task: Option<String>,
+    /// When each pause happened, matching the times logged with their reasons
+    pub paused_at: Vec<DateTime<Local>>,
+}
+
+impl SessionRecord {
+    fn to_line(&self) -> String {
+        let paused_at: Vec<String> = self.paused_at.iter().map(DateTime::to_rfc3339).collect();
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.end.to_rfc3339(),
+            self.session.name(),
+            self.duration.as_secs(),
+            self.interruptions,
+            self.task.as_deref().unwrap_or_default().replace('\t', " "),
+            paused_at.join(",")
+        )
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let end = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+        let session = SessionType::from_name(fields.next()?)?;
+        let secs = fields.next()?.parse().ok()?;
+        // Older lines stop after the duration
+        let interruptions = fields.next().and_then(|f| f.parse().ok()).unwrap_or(0);
+        let task = fields.next().filter(|t| !t.is_empty()).map(String::from);
+        let paused_at = fields
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.with_timezone(&Local))
+            .collect();
+        Some(Self {
+            end: end.with_timezone(&Local),
+            session,
+            duration: Duration::from_secs(secs),
+            interruptions,
+            task,
+            paused_at,
+        })
+    }
+}
+
+/// Log of completed sessions, appended to `history.tsv` in the cache directory
+#[derive(Default)]
+pub struct History {
+    path: Option<PathBuf>,
+    records: Vec<SessionRecord>,
+}
+
+impl History {
+    /// Load the history file, starting empty if it is missing; unreadable lines are skipped
+    pub fn load(path: PathBuf) -> (Self, Option<String>) {
+        let (records, error) = match fs::read_to_string(&path) {
+            Ok(content) => (
+                content.lines().filter_map(SessionRecord::parse).collect(),
+                None,
+            ),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Vec::new(), None),
+            Err(e) => (Vec::new(), Some(format!("Failed to load history: {e}"))),
+        };
+        (
+            Self {
+                path: Some(path),
+                records,
+            },
+            error,
+        )
+    }
+
+    /// Add a finished session, appending it to the history file if there is one
+    pub fn record(&mut self, record: SessionRecord) -> Result<(), io::Error> {
+        let line = record.to_line();
+        self.records.push(record);
+
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{line}")
+    }
+
+    /// Sessions that ended on `date`, oldest first
+    pub fn sessions_on(&self, date: NaiveDate) -> &[SessionRecord] {
+        // Records are appended as sessions end, so each day is a contiguous run
+        let start = self.records.partition_point(|r| r.end.date_naive() < date);
+        let end = self.records.partition_point(|r| r.end.date_naive() <= date);
+        &self.records[start..end]
+    }
+
+    /// Work sessions that ended on `date`
+    pub fn pomodoros_on(&self, date: NaiveDate) -> u32 {
+        let count = self
+            .sessions_on(date)
+            .iter()
+            .filter(|r| r.session == SessionType::Work)
+            .count();
+        u32::try_from(count).unwrap_or(u32::MAX)
+    }
+
+    /// Work sessions recorded against the task with `text`, oldest first
+    pub fn sessions_for(&self, text: &str) -> Vec<&SessionRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.session == SessionType::Work && r.task.as_deref() == Some(text))
+            .collect()
+    }
+
+    /// Total work time of sessions that ended on `date`
+    pub fn focused_on(&self, date: NaiveDate) -> Duration {
+        self.records
+            .iter()
+            .filter(|r| r.session == SessionType::Work && r.end.date_naive() == date)
+            .map(|r| r.duration)
+            .sum()
+    }
+}
+
+/// Squash `sessions` into `columns` equal slices of time from the first start to the last end,
+/// each holding the session running at its midpoint or `None` for a gap
+pub fn timeline(sessions: &[SessionRecord], columns: u16) -> Vec<Option<SessionType>> {
+    let spans: Vec<_> = sessions
+        .iter()
+        .map(|r| (r.end - r.duration, r.end, r.session))
+        .collect();
+    let (Some(first), Some(last)) = (spans.iter().map(|s| s.0).min(), spans.last()) else {
+        return Vec::new();
+    };
+    let total = (last.1 - first).as_seconds_f64();
+    (0..columns)
+        .map(|i| {
+            let offset = total * (f64::from(i) + 0.5) / f64::from(columns);
+            let at = first + chrono::Duration::milliseconds((offset * 1000.0) as i64);
+            spans
+                .iter()
+                .find(|(start, end, _)| *start <= at && at < *end)
+                .map(|s| s.2)
+        })
+        .collect()
+}
+
+/// Format a duration as e.g. `2h 05m` or `45m`
+pub fn format_focused(duration: Duration) -> String {
+    let mins = duration.as_secs() / 60;
+    if mins >= 60 {
+        format!("{}h {:02}m", mins / 60, mins % 60)
+    } else {
+        format!("{mins}m")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone};
+    use tempfile::TempDir;
+
+    fn record(day: u32, session: SessionType, mins: u64) -> SessionRecord {
+        SessionRecord {
+            end: Local
+                .with_ymd_and_hms(2026, 3, day, 12, 0, 0)
+                .single()
+                .unwrap_or_default(),
+            session,
+            duration: Duration::from_mins(mins),
+            interruptions: 0,
+            task: None,
+            paused_at: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_record_and_reload() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("cache").join("history.tsv");
+
+        let (mut history, error) = History::load(path.clone());
+        assert!(error.is_none());
+        let paused_at = record(1, SessionType::Work, 0).end;
+        history.record(SessionRecord {
+            interruptions: 2,
+            task: Some("Write\treport".to_string()),
+            paused_at: vec![paused_at, paused_at + Duration::from_millis(1500)],
+            ..record(1, SessionType::Work, 25)
+        })?;
+        history.record(record(1, SessionType::ShortBreak, 5))?;
+        let old_line = "2026-03-01T09:00:00+00:00\twork\t1500\n";
+        fs::write(&path, fs::read_to_string(&path)? + old_line + "garbage\n")?;
+
+        let (reloaded, _) = History::load(path);
+        assert_eq!(reloaded.records.len(), 3);
+        assert_eq!(reloaded.records[0].task.as_deref(), Some("Write report"));
+        assert_eq!(reloaded.records[0].interruptions, 2);
+        assert_eq!(reloaded.records[0].paused_at, history.records[0].paused_at);
+        assert_eq!(reloaded.records[1..2], history.records[1..2]);
+        assert_eq!(reloaded.records[2].interruptions, 0);
+        assert_eq!(reloaded.records[2].task, None);
+        assert!(reloaded.records[2].paused_at.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_focused_on() {
+        let mut history = History::default();
+        for r in [
+            record(1, SessionType::Work, 25),
+            record(1, SessionType::ShortBreak, 5),
+            record(1, SessionType::Work, 50),
+            record(2, SessionType::Work, 25),
+        ] {
+            history.record(r).ok();
+        }
+
+        let day = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap_or_default();
+        assert_eq!(history.focused_on(day), Duration::from_mins(75));
+    }
+
+    #[test]
+    fn test_sessions_for() {
+        let mut history = History::default();
+        let on = |day, task: &str| SessionRecord {
+            task: Some(task.to_string()),
+            ..record(day, SessionType::Work, 25)
+        };
+        for r in [on(1, "Write"), on(1, "Review"), on(2, "Write")] {
+            history.record(r).ok();
+        }
+
+        let days: Vec<u32> = history
+            .sessions_for("Write")
+            .iter()
+            .map(|r| r.end.day())
+            .collect();
+        assert_eq!(days, [1, 2]);
+        assert!(history.sessions_for("Plan").is_empty());
+    }
+
+    #[test]
+    fn test_sessions_on_and_timeline() {
+        let mut history = History::default();
+        let at = |h, m| {
+            Local
+                .with_ymd_and_hms(2026, 3, 1, h, m, 0)
+                .single()
+                .unwrap_or_default()
+        };
+        for (end, session, mins) in [
+            (at(9, 25), SessionType::Work, 25),
+            (at(9, 30), SessionType::ShortBreak, 5),
+            (at(10, 0), SessionType::Work, 20),
+        ] {
+            history
+                .record(SessionRecord {
+                    end,
+                    session,
+                    duration: Duration::from_mins(mins),
+                    interruptions: 0,
+                    task: None,
+                    paused_at: Vec::new(),
+                })
+                .ok();
+        }
+        history.record(record(2, SessionType::Work, 25)).ok();
+
+        let day = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap_or_default();
+        let sessions = history.sessions_on(day);
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(history.pomodoros_on(day), 2);
+
+        // One column per 5 minutes from 9:00 to 10:00
+        let work = Some(SessionType::Work);
+        let gap = None;
+        assert_eq!(
+            timeline(sessions, 12),
+            [
+                work,
+                work,
+                work,
+                work,
+                work,
+                Some(SessionType::ShortBreak),
+                gap,
+                gap,
+                work,
+                work,
+                work,
+                work
+            ]
+        );
+        assert!(timeline(&[], 12).is_empty());
+    }
+
+    #[test]
+    fn test_format_focused() {
+        assert_eq!(format_focused(Duration::from_secs(59)), "0m");
+        assert_eq!(format_focused(Duration::from_mins(45)), "45m");
+        assert_eq!(format_focused(Duration::from_mins(125)), "2h 05m");
+    }
+}
@@ -0,0 +1,172 @@
+//! Detecting when the user has stepped away, so a running work session can pause itself.
+//!
+//! With the `idle` feature the desktop is asked how long since the last input and whether the
+//! screen is locked, through the same command-line tools a shell script would use:
+//! `loginctl` and `xprintidle` or GNOME's `IdleMonitor` on Linux, `ioreg` on macOS.
+
+// Without the `idle` feature the stub monitor never reports anything
+#![cfg_attr(not(feature = "idle"), allow(dead_code))]
+
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the desktop is asked about activity
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// What the desktop reported about the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Presence {
+    /// Time since the last keyboard or mouse input
+    pub idle: Duration,
+    pub locked: bool,
+}
+
+/// Polls for user activity in the background, like a plugin command
+pub struct IdleMonitor {
+    last_check: Option<Instant>,
+    pending: Option<Receiver<Option<Presence>>>,
+}
+
+impl IdleMonitor {
+    /// Returns None when built without the `idle` feature
+    pub fn new() -> Option<Self> {
+        cfg!(feature = "idle").then_some(Self {
+            last_check: None,
+            pending: None,
+        })
+    }
+
+    /// The latest report, if a check finished since the last call; starts a new one when due
+    pub fn poll(&mut self) -> Option<Presence> {
+        let mut presence = None;
+        if let Some(ref rx) = self.pending {
+            match rx.try_recv() {
+                Ok(result) => {
+                    presence = result;
+                    self.pending = None;
+                }
+                Err(TryRecvError::Disconnected) => self.pending = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        let due = self
+            .last_check
+            .is_none_or(|last| last.elapsed() >= POLL_INTERVAL);
+        if due && self.pending.is_none() {
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                // The receiver is dropped if the app exits first, which is fine to ignore
+                let _ = tx.send(query());
+            });
+            self.pending = Some(rx);
+            self.last_check = Some(Instant::now());
+        }
+        presence
+    }
+}
+
+#[cfg(all(feature = "idle", target_os = "linux"))]
+fn query() -> Option<Presence> {
+    let idle = run("xprintidle", &[])
+        .and_then(|out| parse_xprintidle(&out))
+        .or_else(|| {
+            run(
+                "gdbus",
+                &[
+                    "call",
+                    "--session",
+                    "--dest",
+                    "org.gnome.Mutter.IdleMonitor",
+                    "--object-path",
+                    "/org/gnome/Mutter/IdleMonitor/Core",
+                    "--method",
+                    "org.gnome.Mutter.IdleMonitor.GetIdletime",
+                ],
+            )
+            .and_then(|out| parse_gdbus_idletime(&out))
+        });
+    let locked = std::env::var("XDG_SESSION_ID").ok().and_then(|id| {
+        run(
+            "loginctl",
+            &["show-session", &id, "--property=LockedHint", "--value"],
+        )
+        .map(|out| out.trim() == "yes")
+    });
+    if idle.is_none() && locked.is_none() {
+        return None;
+    }
+    Some(Presence {
+        idle: idle.unwrap_or_default(),
+        locked: locked.unwrap_or_default(),
+    })
+}
+
+#[cfg(all(feature = "idle", target_os = "macos"))]
+fn query() -> Option<Presence> {
+    let idle = run("ioreg", &["-c", "IOHIDSystem", "-d", "4"]).and_then(|out| parse_ioreg(&out))?;
+    let locked = run("ioreg", &["-n", "Root", "-d", "1", "-a"])
+        .is_some_and(|out| out.contains("<key>CGSSessionScreenIsLocked</key>"));
+    Some(Presence { idle, locked })
+}
+
+#[cfg(not(all(feature = "idle", any(target_os = "linux", target_os = "macos"))))]
+const fn query() -> Option<Presence> {
+    None
+}
+
+/// Standard output of a command that succeeded
+#[cfg(feature = "idle")]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `xprintidle` prints the idle time in milliseconds
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_xprintidle(output: &str) -> Option<Duration> {
+    output.trim().parse().ok().map(Duration::from_millis)
+}
+
+/// `gdbus` prints the idle time in milliseconds as `(uint64 12345,)`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_gdbus_idletime(output: &str) -> Option<Duration> {
+    let value = output.trim().strip_prefix("(uint64 ")?.strip_suffix(",)")?;
+    value.parse().ok().map(Duration::from_millis)
+}
+
+/// `ioreg` lists the idle time in nanoseconds as `"HIDIdleTime" = 1234567890`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ioreg(output: &str) -> Option<Duration> {
+    output.lines().find_map(|line| {
+        let (_, value) = line.split_once("\"HIDIdleTime\" = ")?;
+        value.trim().parse().ok().map(Duration::from_nanos)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_idle_times() {
+        assert_eq!(
+            parse_xprintidle("4200\n"),
+            Some(Duration::from_millis(4200))
+        );
+        assert_eq!(
+            parse_gdbus_idletime("(uint64 61000,)\n"),
+            Some(Duration::from_secs(61))
+        );
+        assert_eq!(parse_gdbus_idletime("Error: no such service"), None);
+        let ioreg = "    |   \"HIDIdleTime\" = 3000000000\n    |   \"HIDMouseAcceleration\" = 1";
+        assert_eq!(parse_ioreg(ioreg), Some(Duration::from_secs(3)));
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+
+use crate::task::sanitize;
+
+/// Why a work session was paused, stored one per line as `<time RFC 3339>\t<reason>\t<task>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interruption {
+    pub at: DateTime<Local>,
+    pub reason: String,
+    /// Task being worked on when paused
+    pub task: Option<String>,
+}
+
+impl Interruption {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}",
+            self.at.to_rfc3339(),
+            sanitize(&self.reason),
+            sanitize(self.task.as_deref().unwrap_or_default())
+        )
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let at = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+        let reason = fields.next()?.to_string();
+        let task = fields.next().filter(|t| !t.is_empty()).map(String::from);
+        Some(Self {
+            at: at.with_timezone(&Local),
+            reason,
+            task,
+        })
+    }
+}
+
+/// Pause reasons given when asked, appended to `interruptions.tsv` in the cache directory
+#[derive(Default)]
+pub struct InterruptionLog {
+    path: Option<PathBuf>,
+    entries: Vec<Interruption>,
+}
+
+impl InterruptionLog {
+    /// Load the log file, starting empty if it is missing; unreadable lines are skipped
+    pub fn load(path: PathBuf) -> (Self, Option<String>) {
+        let (entries, error) = match fs::read_to_string(&path) {
+            Ok(content) => (
+                content.lines().filter_map(Interruption::parse).collect(),
+                None,
+            ),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Vec::new(), None),
+            Err(e) => (
+                Vec::new(),
+                Some(format!("Failed to load interruptions: {e}")),
+            ),
+        };
+        (
+            Self {
+                path: Some(path),
+                entries,
+            },
+            error,
+        )
+    }
+
+    /// Add an interruption, appending it to the log file if there is one
+    pub fn record(&mut self, interruption: Interruption) -> Result<(), io::Error> {
+        let line = interruption.to_line();
+        self.entries.push(interruption);
+
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{line}")
+    }
+
+    /// Interruptions from `start` up to `end`, oldest first
+    pub fn between(
+        &self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> impl Iterator<Item = &Interruption> {
+        self.entries
+            .iter()
+            .filter(move |i| start <= i.at && i.at <= end)
+    }
+
+    /// Interruptions logged for the pauses at `times`, oldest first
+    pub fn at_times<'a>(
+        &'a self,
+        times: &'a [DateTime<Local>],
+    ) -> impl Iterator<Item = &'a Interruption> {
+        self.entries.iter().filter(|i| times.contains(&i.at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, 1, h, m, 0)
+            .single()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_record_and_reload() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("cache").join("interruptions.tsv");
+
+        let (mut log, error) = InterruptionLog::load(path.clone());
+        assert!(error.is_none());
+        log.record(Interruption {
+            at: at(9, 10),
+            reason: "Phone\tcall".to_string(),
+            task: Some("Write report".to_string()),
+        })?;
+        log.record(Interruption {
+            at: at(11, 0),
+            reason: "Meeting".to_string(),
+            task: None,
+        })?;
+
+        let (reloaded, _) = InterruptionLog::load(path);
+        let reasons: Vec<&str> = reloaded
+            .between(at(9, 0), at(9, 25))
+            .map(|i| i.reason.as_str())
+            .collect();
+        assert_eq!(reasons, ["Phone call"]);
+        assert_eq!(reloaded.entries[1].task, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_at_times() {
+        let mut log = InterruptionLog::default();
+        for (at, reason) in [(at(9, 10), "Phone"), (at(9, 20), "Door")] {
+            log.record(Interruption {
+                at,
+                reason: reason.to_string(),
+                task: None,
+            })
+            .ok();
+        }
+
+        let paused_at = [at(9, 5), at(9, 20)];
+        let reasons: Vec<&str> = log
+            .at_times(&paused_at)
+            .map(|i| i.reason.as_str())
+            .collect();
+        assert_eq!(reasons, ["Door"]);
+    }
+}
@@ -1,12 +1,27 @@
 mod app;
+mod backup;
+mod clipboard;
+mod config;
+mod demo;
+mod eye_breaks;
 mod fileio;
+mod history;
+mod idle;
+mod interruptions;
 mod melodies;
+mod notification_policy;
 mod notifications;
 mod overlays;
 mod panels;
+mod recovery;
+mod scheduler;
+mod scripting;
+mod state;
 mod task;
 mod task_manager;
+mod theme;
 mod timer;
+mod trash;
 mod ui;
 
 use std::io;
@@ -14,34 +29,77 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use crossterm::{
-    event, execute,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
     terminal::{
-        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen, SetTitle,
     },
 };
 use ratatui::prelude::*;
 
 use app::App;
+use backup::Backups;
+use config::{cache_dir, discover_project, global_config_path, Config, ConfigWatcher, ProjectFile};
+use history::History;
+use interruptions::InterruptionLog;
+use recovery::SessionFile;
+use scripting::ScriptEngine;
+use state::UiState;
+use trash::Trash;
 
 fn main() -> io::Result<()> {
     // Parse CLI arguments
-    let args: Vec<String> = std::env::args().collect();
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    let task_file = args.get(1).map(PathBuf::from);
+    // `--demo` shows made-up data and leaves the real config, tasks and history alone
+    let demo = args.iter().any(|arg| arg == "--demo");
+    let task_file = args
+        .iter()
+        .find(|arg| !arg.starts_with("--"))
+        .map(PathBuf::from);
 
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, SetTitle("pomo-tui"))?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        SetTitle("pomo-tui")
+    )?;
+    // The kitty keyboard protocol tells apart keys legacy encoding merges, such as Tab and
+    // Ctrl+I or Enter and Shift+Enter, and reports Esc without a timeout
+    let enhanced_keys = supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced_keys {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Run the app
-    let result = run(&mut terminal, task_file);
+    let result = if demo {
+        run_demo(&mut terminal)
+    } else {
+        run(&mut terminal, task_file)
+    };
 
     // Restore terminal
+    if enhanced_keys {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        LeaveAlternateScreen
+    )?;
 
     result
 }
@@ -50,24 +108,107 @@ fn run(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     task_file: Option<PathBuf>,
 ) -> io::Result<()> {
-    let mut app = App::new(task_file);
+    // A project `.pomo-tui.toml` replaces the global config; a project `TODO.md` is the task file
+    let project = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| discover_project(&cwd));
+    let (config_path, project_tasks) = match project {
+        Some(ProjectFile::Config(path)) => {
+            let todo = path.with_file_name("TODO.md");
+            let tasks = todo.is_file().then_some(todo);
+            (Some(path), tasks)
+        }
+        Some(ProjectFile::Tasks(path)) => (global_config_path(), Some(path)),
+        None => (global_config_path(), None),
+    };
+
+    let (config, config_error) = config_path
+        .as_deref()
+        .map_or_else(|| (Config::default(), None), Config::load);
+    let task_file = task_file
+        .or_else(|| config.task_file.clone())
+        .or(project_tasks);
+
+    let mut app = App::new(None, config);
+    app.load_tasks(task_file, cache_dir().map(|dir| dir.join("locks")));
+    app.config_watcher = config_path.map(ConfigWatcher::new);
+    app.error_message = app.error_message.take().or(config_error);
+    if let Some(dir) = cache_dir() {
+        let (history, history_error) = History::load(dir.join("history.tsv"));
+        app.history = history;
+        app.error_message = app.error_message.take().or(history_error);
+        let (interruptions, interruptions_error) =
+            InterruptionLog::load(dir.join("interruptions.tsv"));
+        app.interruptions = interruptions;
+        app.error_message = app.error_message.take().or(interruptions_error);
+        let (trash, trash_error) = Trash::load(dir.join("trash.tsv"));
+        app.tasks_panel.set_trash(trash);
+        app.tasks_panel.set_backups(Backups::in_dir(
+            dir.join("backups"),
+            app.config.tasks.backups,
+        ));
+        app.error_message = app.error_message.take().or(trash_error);
+        let (session_file, session_error) = SessionFile::load(dir.join("session.toml"));
+        app.session_file = session_file;
+        app.offer_recovery();
+        app.error_message = app.error_message.take().or(session_error);
+    }
+    let state_path = cache_dir().map(|dir| dir.join("state.toml"));
+    if let Some(ref path) = state_path {
+        let (state, state_error) = UiState::load(path);
+        app.tasks_visible = state.tasks_visible;
+        app.layout_preset = state.layout;
+        app.error_message = app.error_message.take().or(state_error);
+    }
+    match ScriptEngine::load() {
+        Ok(scripts) => app.scripts = scripts,
+        Err(e) => app.error_message = app.error_message.take().or(Some(e)),
+    }
+    event_loop(terminal, &mut app)?;
+
+    // Quitting mid-session is deliberate, so there is nothing to recover next time
+    app.session_file.update(None)?;
+    if let Some(path) = state_path {
+        let state = UiState {
+            tasks_visible: app.tasks_visible,
+            layout: app.layout_preset,
+        };
+        state.save(&path)?;
+    }
+
+    Ok(())
+}
+
+/// Run on generated tasks and history with a sped-up timer, saving nothing
+fn run_demo(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
+    let mut app = App::new(None, Config::default());
+    demo::populate(&mut app);
+    event_loop(terminal, &mut app)
+}
+
+fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> io::Result<()> {
     let tick_rate = Duration::from_millis(100);
 
     loop {
-        let size = terminal.size()?;
-        app.compute_column_layout(size.width);
-        terminal.draw(|frame| ui::render(frame, &mut app))?;
+        terminal.draw(|frame| ui::render(frame, app))?;
 
         if event::poll(tick_rate)? {
-            app.handle(&event::read()?);
+            let event = event::read()?;
+            if let Event::Resize(..) = event {
+                // Drop the previous frame so the next draw repaints the whole screen
+                terminal.autoresize()?;
+                terminal.clear()?;
+            }
+            app.handle(&event);
         }
 
         app.tick();
 
         if app.should_quit {
-            break;
+            return Ok(());
         }
     }
-
-    Ok(())
 }
@@ -12,6 +12,21 @@ const CS6: f32 = 1108.0;
 /// A5 and C#6 two-tone chime
 pub const TWO_TONE: Melody = &[(A5, 150), (SILENCE, 50), (CS6, 200)];
 
+/// Single A5 ping for cues during a session
+pub const SOFT_CHIME: Melody = &[(A5, 120)];
+
+/// Two low pips and a high one, at the end of the countdown between sessions
+pub const GET_READY: Melody = &[
+    (C5, 100),
+    (SILENCE, 200),
+    (C5, 100),
+    (SILENCE, 200),
+    (A5, 250),
+];
+
+/// Short click looped while the timer runs; with the silence appended on load it ticks once a second
+pub const TICK: Melody = &[(A5, 10), (SILENCE, 790)];
+
 /// Final Fantasy VII victory fanfare
 pub const VICTORY_FANFARE: Melody = {
     const U: u64 = 150; // one beat unit (0.25 beats at 100 BPM) in ms
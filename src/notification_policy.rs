@@ -0,0 +1,162 @@
+use chrono::NaiveTime;
+use serde::{de::Error, Deserialize, Deserializer};
+
+use crate::config::Config;
+use crate::notifications::NotificationBackend;
+use crate::timer::SessionType;
+
+/// Rules for when session alerts fire, configured in the `[notifications]` config section.
+///
+/// Per-session rules apply to the session that just finished, e.g. `short_break.sound = false`
+/// keeps the end of a short break silent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationPolicy {
+    pub work: SessionAlerts,
+    pub short_break: SessionAlerts,
+    pub long_break: SessionAlerts,
+    /// Local time range, e.g. `{ start = "18:00", end = "08:00" }`, when no alerts fire
+    pub quiet_hours: Option<QuietHours>,
+    /// `notify-send`, or `osc9`/`osc777` to let the terminal show desktop alerts, e.g. over SSH
+    pub backend: NotificationBackend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionAlerts {
+    pub sound: bool,
+    pub desktop: bool,
+}
+
+impl Default for SessionAlerts {
+    fn default() -> Self {
+        Self {
+            sound: true,
+            desktop: true,
+        }
+    }
+}
+
+/// Daily time range, wrapping past midnight when `end` is before `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuietHours {
+    #[serde(deserialize_with = "deserialize_time")]
+    pub start: NaiveTime,
+    #[serde(deserialize_with = "deserialize_time")]
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Alerts to fire for a single event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alerts {
+    pub sound: bool,
+    pub desktop: bool,
+}
+
+impl NotificationPolicy {
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        self.quiet_hours.is_some_and(|q| q.contains(time))
+    }
+
+    const fn session_alerts(&self, session: SessionType) -> SessionAlerts {
+        match session {
+            SessionType::Work => self.work,
+            SessionType::ShortBreak => self.short_break,
+            SessionType::LongBreak => self.long_break,
+        }
+    }
+}
+
+/// Decide which alerts fire when `session` completes at local `time`
+pub fn resolve(config: &Config, session: SessionType, time: NaiveTime, dnd: bool) -> Alerts {
+    let policy = &config.notifications;
+    if dnd || policy.is_quiet(time) {
+        return Alerts {
+            sound: false,
+            desktop: false,
+        };
+    }
+    let rules = policy.session_alerts(session);
+    Alerts {
+        sound: config.sound.enabled && rules.sound,
+        desktop: rules.desktop,
+    }
+}
+
+fn deserialize_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&value, "%H:%M")
+        .map_err(|_| D::Error::custom(format!("invalid time '{value}', expected HH:MM")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        let quiet = QuietHours {
+            start: time(18, 0),
+            end: time(8, 0),
+        };
+        assert!(quiet.contains(time(18, 0)));
+        assert!(quiet.contains(time(23, 59)));
+        assert!(quiet.contains(time(7, 59)));
+        assert!(!quiet.contains(time(8, 0)));
+        assert!(!quiet.contains(time(12, 0)));
+
+        let lunch = QuietHours {
+            start: time(12, 0),
+            end: time(13, 0),
+        };
+        assert!(lunch.contains(time(12, 30)));
+        assert!(!lunch.contains(time(13, 0)));
+    }
+
+    #[test]
+    fn test_resolve_alerts() {
+        let mut config = Config::default();
+        config.notifications.work.sound = false;
+        config.notifications.quiet_hours = Some(QuietHours {
+            start: time(18, 0),
+            end: time(8, 0),
+        });
+        let noon = time(12, 0);
+
+        // Desktop only at the end of work, everything at the end of breaks
+        let work = resolve(&config, SessionType::Work, noon, false);
+        assert_eq!((work.sound, work.desktop), (false, true));
+        let short_break = resolve(&config, SessionType::ShortBreak, noon, false);
+        assert_eq!((short_break.sound, short_break.desktop), (true, true));
+
+        // The global sound switch still applies
+        config.sound.enabled = false;
+        assert!(!resolve(&config, SessionType::ShortBreak, noon, false).sound);
+
+        // Quiet hours and do not disturb silence everything
+        let silent = Alerts {
+            sound: false,
+            desktop: false,
+        };
+        assert_eq!(
+            resolve(&config, SessionType::Work, time(19, 0), false),
+            silent
+        );
+        assert_eq!(resolve(&config, SessionType::Work, noon, true), silent);
+    }
+}
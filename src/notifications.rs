@@ -1,9 +1,60 @@
+use std::io::{self, Write};
 use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread;
 
-use crate::melodies::Melody;
+use crate::melodies::{Melody, TICK};
 
 use rodio::buffer::SamplesBuffer;
-use rodio::{OutputStream, OutputStreamHandle, Sink};
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use serde::Deserialize;
+
+/// How desktop notifications reach the user, set with `backend` in `[notifications]`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationBackend {
+    /// Desktop notification daemon, with action buttons where supported
+    #[default]
+    NotifySend,
+    /// `OSC 9` escape sequence, understood by kitty, `WezTerm` and iTerm2
+    Osc9,
+    /// `OSC 777` escape sequence, understood by foot, `WezTerm` and VTE terminals
+    Osc777,
+}
+
+/// Send a plain notification through `backend`, returning any error message
+pub fn send_notification_via(
+    backend: NotificationBackend,
+    title: &str,
+    message: &str,
+) -> Option<String> {
+    match backend {
+        NotificationBackend::NotifySend => send_notification(title, message),
+        NotificationBackend::Osc9 | NotificationBackend::Osc777 => {
+            let sequence = osc_sequence(backend, title, message);
+            let mut stdout = io::stdout();
+            stdout
+                .write_all(sequence.as_bytes())
+                .and_then(|()| stdout.flush())
+                .err()
+                .map(|e| format!("Failed to write notification: {e}"))
+        }
+    }
+}
+
+/// Escape sequence asking the terminal to show a notification, which also works over SSH
+fn osc_sequence(backend: NotificationBackend, title: &str, message: &str) -> String {
+    // Control characters would end the sequence early and `;` separates OSC 777 fields
+    let clean = |text: &str| text.replace(|c: char| c.is_control() || c == ';', " ");
+    match backend {
+        NotificationBackend::Osc777 => {
+            format!("\x1b]777;notify;{};{}\x1b\\", clean(title), clean(message))
+        }
+        _ => format!("\x1b]9;{}: {}\x1b\\", clean(title), clean(message)),
+    }
+}
 
 /// Send text notification via notify-send, returning any error message
 pub fn send_notification(title: &str, message: &str) -> Option<String> {
@@ -23,13 +74,104 @@ pub fn send_notification(title: &str, message: &str) -> Option<String> {
     }
 }
 
+/// A button clicked on an actionable notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    StartNext,
+    Skip,
+    Extend,
+}
+
+impl NotificationAction {
+    const ALL: [Self; 3] = [Self::StartNext, Self::Skip, Self::Extend];
+
+    /// Identifier passed to and printed back by `notify-send --action`
+    const fn key(self) -> &'static str {
+        match self {
+            Self::StartNext => "start",
+            Self::Skip => "skip",
+            Self::Extend => "extend",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.key() == key)
+    }
+}
+
+/// Numbers the actionable notifications sent, so a click on one that a newer one has
+/// replaced is dropped instead of acting on a later session
+#[derive(Clone, Default)]
+pub struct NotificationSerial(Arc<AtomicU64>);
+
+impl NotificationSerial {
+    fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn is_latest(&self, serial: u64) -> bool {
+        self.0.load(Ordering::SeqCst) == serial
+    }
+}
+
+/// Send a notification with action buttons on a background thread, reporting the clicked
+/// action (or any error) on `tx` unless another has been sent through `serial` since.
+///
+/// `notify-send` versions without `--action` support fall back to a plain notification.
+pub fn send_actionable_notification(
+    title: &str,
+    message: &str,
+    start_label: &str,
+    serial: &NotificationSerial,
+    tx: Sender<Result<NotificationAction, String>>,
+) {
+    let labels = [start_label, "Skip", "+5 min"];
+    let mut command = Command::new("notify-send");
+    command.arg("--wait");
+    for (action, label) in NotificationAction::ALL.into_iter().zip(labels) {
+        command.arg(format!("--action={}={label}", action.key()));
+    }
+    command.arg(title).arg(message);
+
+    let (title, message) = (title.to_string(), message.to_string());
+    let (serial, id) = (serial.clone(), serial.next());
+    thread::spawn(move || {
+        let result = match command
+            .stderr(std::process::Stdio::null())
+            .stdin(std::process::Stdio::null())
+            .output()
+        {
+            Ok(output) if output.status.success() => {
+                let key = String::from_utf8_lossy(&output.stdout);
+                match NotificationAction::from_key(key.trim()) {
+                    Some(action) => Ok(action),
+                    // Dismissed without choosing an action
+                    None => return,
+                }
+            }
+            _ => match send_notification(&title, &message) {
+                Some(err) => Err(err),
+                None => return,
+            },
+        };
+        if !serial.is_latest(id) {
+            return;
+        }
+        // The receiver is dropped if the app exits first, which is fine to ignore
+        let _ = tx.send(result);
+    });
+}
+
 /// Renders a [`Melody`] into a [`SamplesBuffer`].
 ///
 /// Each audible note gets a short linear fade-out to prevent inter-note clicks.
 /// A tail of silence is appended so hardware output buffers flush cleanly.
 fn load_melody(melody: Melody) -> SamplesBuffer<f32> {
+    load_melody_at(melody, 0.3)
+}
+
+fn load_melody_at(melody: Melody, amp: f32) -> SamplesBuffer<f32> {
     const SAMPLE_RATE: u32 = 44100;
-    const AMP: f32 = 0.3;
     const FADE: usize = 400; // ~9ms linear fade-out per note
     #[allow(clippy::cast_precision_loss)]
     const FADE_STEP: f32 = 1.0 / FADE as f32;
@@ -45,7 +187,7 @@ fn load_melody(melody: Melody) -> SamplesBuffer<f32> {
             if freq > 0.0 && n - i <= FADE {
                 gain = (gain - FADE_STEP).max(0.0);
             }
-            samples.push((std::f32::consts::TAU * phase).sin() * AMP * gain);
+            samples.push((std::f32::consts::TAU * phase).sin() * amp * gain);
             phase = (phase + phase_inc).fract();
         }
     }
@@ -60,6 +202,8 @@ fn load_melody(melody: Melody) -> SamplesBuffer<f32> {
 pub struct AudioPlayer {
     _stream: OutputStream,
     stream_handle: OutputStreamHandle,
+    /// Looping tick, while enabled
+    ticking: Option<Sink>,
 }
 
 impl AudioPlayer {
@@ -68,6 +212,7 @@ impl AudioPlayer {
         Some(Self {
             _stream: stream,
             stream_handle,
+            ticking: None,
         })
     }
 
@@ -78,4 +223,54 @@ impl AudioPlayer {
             sink.detach();
         }
     }
+
+    /// Start or stop the quiet ticking loop
+    pub fn set_ticking(&mut self, on: bool) {
+        if !on {
+            self.ticking = None;
+        } else if self.ticking.is_none() {
+            if let Ok(sink) = Sink::try_new(&self.stream_handle) {
+                sink.append(load_melody_at(TICK, 0.05).repeat_infinite());
+                self.ticking = Some(sink);
+            }
+        }
+    }
+
+    /// Play a melody quietly without blocking, for cues that shouldn't startle
+    pub fn play_soft(&self, melody: Melody) {
+        if let Ok(sink) = Sink::try_new(&self.stream_handle) {
+            sink.append(load_melody_at(melody, 0.1));
+            sink.detach();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc_sequence() {
+        assert_eq!(
+            osc_sequence(NotificationBackend::Osc9, "Pomo-TUI", "Session completed!"),
+            "\x1b]9;Pomo-TUI: Session completed!\x1b\\"
+        );
+        assert_eq!(
+            osc_sequence(NotificationBackend::Osc777, "Pomo-TUI", "Done; next\x07"),
+            "\x1b]777;notify;Pomo-TUI;Done  next \x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_notification_serial() {
+        let serial = NotificationSerial::default();
+        let first = serial.next();
+        assert!(serial.is_latest(first));
+
+        // Clones share the count, as each notification's thread holds one
+        let shared = serial.clone();
+        let second = shared.next();
+        assert!(!serial.is_latest(first));
+        assert!(serial.is_latest(second));
+    }
 }
@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Overlay listing the backups of the task file, newest first, to pick one to restore
+pub struct BackupOverlay {
+    backups: Vec<PathBuf>,
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl BackupOverlay {
+    pub const fn new(backups: Vec<PathBuf>) -> Self {
+        Self {
+            backups,
+            selected: 0,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the backup to restore, or None if dismissed
+    pub fn result(&self) -> Option<&Path> {
+        self.backups
+            .get(self.selected)
+            .filter(|_| self.chosen)
+            .map(PathBuf::as_path)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let key = Style::default().fg(Color::Yellow);
+
+        let mut lines = vec![Line::from("")];
+        for (i, backup) in self.backups.iter().enumerate() {
+            let (marker, style) = if i == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            let name = backup
+                .file_stem()
+                .map(|stem| stem.to_string_lossy())
+                .unwrap_or_default();
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                Span::styled(name, style),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Restore "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 50, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Restore Backup ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl BackupOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Restore backup
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn choose(&mut self) {
+        if self.selected < self.backups.len() {
+            self.chosen = true;
+        }
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.backups.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
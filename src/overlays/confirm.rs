@@ -0,0 +1,86 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::{keymap, KeyMap};
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Width of the dialog, so callers can fit their message
+pub const CONFIRM_WIDTH: u16 = 46;
+
+/// Overlay asking to confirm a destructive action before it happens
+pub struct ConfirmOverlay {
+    title: &'static str,
+    message: String,
+    confirmed: bool,
+    dismissed: bool,
+}
+
+impl ConfirmOverlay {
+    pub const fn new(title: &'static str, message: String) -> Self {
+        Self {
+            title,
+            message,
+            confirmed: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.confirmed
+    }
+
+    /// Whether the action was confirmed
+    pub const fn result(&self) -> bool {
+        self.confirmed
+    }
+
+    /// Takes every key while open, so nothing behind the dialog reacts to it
+    pub fn handle(&mut self, event: &Event) -> bool {
+        KeyMap::handle(self, event);
+        true
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let key = Style::default().fg(Color::Yellow);
+        let lines = vec![
+            Line::from(""),
+            Line::from(format!("  {}", self.message)),
+            Line::from(""),
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[y]", key),
+                Span::raw(" Yes "),
+                Span::styled("[n]", key),
+                Span::raw(" No"),
+            ]),
+            Line::from(""),
+        ];
+
+        let overlay_area = overlay_rect(frame.area(), CONFIRM_WIDTH, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, self.title, theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl ConfirmOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    #[keybind(pressed(key=KeyCode::Char('n')))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Confirm
+    #[keybind(pressed(key=KeyCode::Enter))]
+    #[keybind(pressed(key=KeyCode::Char('y')))]
+    fn confirm(&mut self) {
+        self.confirmed = true;
+    }
+}
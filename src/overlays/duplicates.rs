@@ -0,0 +1,152 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame, render_scrollbar, scroll_to_show};
+use crate::theme::Theme;
+
+/// Most groups listed at once; the rest scroll
+const MAX_GROUPS: usize = 4;
+
+/// Overlay listing groups of tasks that look like the same task, to merge one group at a time
+pub struct DuplicatesOverlay {
+    /// Rows describing the tasks of each group, the one kept first
+    groups: Vec<Vec<String>>,
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl DuplicatesOverlay {
+    pub const fn new(groups: Vec<Vec<String>>) -> Self {
+        Self {
+            groups,
+            selected: 0,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the index of the group to merge, or None if dismissed
+    pub fn result(&self) -> Option<usize> {
+        self.chosen.then_some(self.selected)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let offset = scroll_to_show(self.selected, self.groups.len(), MAX_GROUPS);
+        let mut lines = vec![Line::from("")];
+        for (i, group) in self.groups.iter().enumerate().skip(offset).take(MAX_GROUPS) {
+            let (marker, style) = if i == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            for (row, task) in group.iter().enumerate() {
+                // The first task is the one kept
+                let (marker, style) = if row == 0 {
+                    (marker, style)
+                } else {
+                    ("  ", dim)
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                    Span::styled(task.as_str(), style),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+        let list_rows = lines.len() - 1;
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Merge into first "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Close"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 56, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Duplicate Tasks ", theme.accent);
+        let track = Rect {
+            y: inner.y + 1,
+            height: (list_rows as u16).min(inner.height.saturating_sub(1)),
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(lines), inner);
+        render_scrollbar(frame, track, self.groups.len(), MAX_GROUPS, offset);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl DuplicatesOverlay {
+    /// Close
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Merge group
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn choose(&mut self) {
+        self.chosen = !self.groups.is_empty();
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.groups.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{Event, KeyEvent, KeyModifiers};
+    use ratatui_input_manager::KeyMap;
+
+    fn press(overlay: &mut DuplicatesOverlay, code: KeyCode) {
+        KeyMap::handle(
+            overlay,
+            &Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+        );
+    }
+
+    #[test]
+    fn test_choose_group() {
+        let group = |text: &str| vec![format!("Current: {text}"), format!("Backlog: {text}")];
+        let mut overlay = DuplicatesOverlay::new(vec![group("Plan"), group("Review")]);
+        press(&mut overlay, KeyCode::Char('j'));
+        press(&mut overlay, KeyCode::Char('j'));
+        assert_eq!(overlay.result(), None);
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some(1));
+    }
+}
@@ -6,7 +6,7 @@ use ratatui::{
     Frame,
 };
 
-use super::util::{centered_rect, render_overlay_frame};
+use super::util::{overlay_rect, render_overlay_frame};
 
 pub fn render_error_overlay(frame: &mut Frame, message: &str) {
     let overlay_width = 40u16;
@@ -18,17 +18,17 @@ pub fn render_error_overlay(frame: &mut Frame, message: &str) {
         1
     };
     let content_height = (1 + msg_lines + 1 + 1 + 1) as u16 + 2;
-    let overlay_height = content_height.min(frame.area().height.saturating_sub(4));
 
-    let overlay_area = centered_rect(frame.area(), overlay_width, overlay_height);
+    let overlay_area = overlay_rect(frame.area(), overlay_width, content_height);
     let inner = render_overlay_frame(frame, overlay_area, " Error ", Color::Red);
 
+    // The message gives up rows first so the hint stays visible in short frames
     let rows = Layout::vertical([
-        Constraint::Length(1),                // pad
-        Constraint::Length(msg_lines as u16), // message
-        Constraint::Length(1),                // pad
-        Constraint::Length(1),                // hint
-        Constraint::Min(0),                   // pad
+        Constraint::Length(1), // pad
+        Constraint::Min(1),    // message
+        Constraint::Length(1), // pad
+        Constraint::Length(1), // hint
+        Constraint::Length(1), // pad
     ])
     .split(inner);
 
@@ -1,13 +1,21 @@
 use ratatui::{
+    layout::Margin,
     style::{Color, Style},
     widgets::{Block, Borders, Clear, Padding},
     Frame,
 };
 use ratatui_input_manager::{widgets::Help, CrosstermBackend, KeyBind};
 
-use super::util::centered_rect;
+use super::util::{max_scroll, overlay_rect, render_scrollbar};
+use crate::theme::Theme;
 
-pub fn render_help_overlay(frame: &mut Frame, keybinds: &[KeyBind<CrosstermBackend>]) {
+/// Render the keybinds starting from row `scroll`, returning the offset clamped to the content
+pub fn render_help_overlay(
+    frame: &mut Frame,
+    keybinds: &[KeyBind<CrosstermBackend>],
+    theme: &Theme,
+    scroll: usize,
+) -> usize {
     let key_col_width = keybinds
         .iter()
         .map(|kb| {
@@ -25,21 +33,30 @@ pub fn render_help_overlay(frame: &mut Frame, keybinds: &[KeyBind<CrosstermBacke
         .unwrap_or(0) as u16;
 
     // 2 borders + 2 horizontal padding + key column + 1 default table column spacing + description column
-    let overlay_width =
-        (4 + key_col_width + 1 + desc_col_width).min(frame.area().width.saturating_sub(4));
-    let overlay_height = (keybinds.len() as u16 + 2).min(frame.area().height.saturating_sub(4));
+    let overlay_width = 4 + key_col_width + 1 + desc_col_width;
+    let overlay_height = keybinds.len() as u16 + 2;
 
-    let overlay_area = centered_rect(frame.area(), overlay_width, overlay_height);
+    let overlay_area = overlay_rect(frame.area(), overlay_width, overlay_height);
     frame.render_widget(Clear, overlay_area);
 
-    let help = Help::new(keybinds)
+    let visible = overlay_area.height.saturating_sub(2) as usize;
+    let scroll = scroll.min(max_scroll(keybinds.len(), visible));
+
+    let help = Help::new(&keybinds[scroll..])
         .block(
             Block::default()
                 .title(" Help ")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.accent))
                 .padding(Padding::horizontal(1)),
         )
         .key_style(Style::default().fg(Color::Yellow));
     frame.render_widget(help, overlay_area);
+    let track = overlay_area.inner(Margin {
+        horizontal: 0,
+        vertical: 1,
+    });
+    render_scrollbar(frame, track, keybinds.len(), visible, scroll);
+
+    scroll
 }
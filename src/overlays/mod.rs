@@ -1,10 +1,47 @@
+mod backups;
+mod confirm;
+mod duplicates;
 mod error;
 mod help;
+mod note;
+mod palette;
+mod pause_reason;
+mod presets;
+mod project;
+mod quit;
+mod recovery;
+mod reset_day;
+mod session_complete;
+mod slept;
 mod sync;
+mod tag_filter;
 mod task_input;
+mod task_switcher;
+mod toast;
+mod until;
+mod url_picker;
 mod util;
 
+pub use backups::BackupOverlay;
+pub use confirm::{ConfirmOverlay, CONFIRM_WIDTH};
+pub use duplicates::DuplicatesOverlay;
 pub use error::render_error_overlay;
 pub use help::render_help_overlay;
-pub use sync::{SyncItem, SyncOverlay, SyncResolution};
-pub use task_input::TaskInputOverlay;
+pub use note::NoteOverlay;
+pub use palette::CommandPaletteOverlay;
+pub use pause_reason::PauseReasonOverlay;
+pub use presets::PresetOverlay;
+pub use project::{ProjectChoice, ProjectOverlay};
+pub use quit::{QuitChoice, QuitOverlay};
+pub use recovery::{RecoveryChoice, RecoveryOverlay};
+pub use reset_day::ResetDayOverlay;
+pub use session_complete::{SessionChoice, SessionCompleteOverlay, TaskOutcome};
+pub use slept::{SleepChoice, SleptOverlay};
+pub use sync::{SyncItem, SyncOrigin, SyncOverlay, SyncResolution};
+pub use tag_filter::{TagChoice, TagFilterOverlay};
+pub use task_input::{TaskInputAction, TaskInputOverlay};
+pub use task_switcher::TaskSwitcherOverlay;
+pub use toast::Toast;
+pub use until::UntilOverlay;
+pub use url_picker::UrlPickerOverlay;
+pub use util::render_scrollbar;
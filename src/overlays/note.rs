@@ -0,0 +1,221 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::{keymap, KeyMap};
+use unicode_width::UnicodeWidthStr;
+
+use super::util::{overlay_rect, render_overlay_frame, scroll_to_show};
+use crate::task::TaskSection;
+use crate::theme::Theme;
+
+/// Rows of the note shown at once
+const NOTE_ROWS: usize = 8;
+
+/// Overlay for writing the multi-line note of a task
+pub struct NoteOverlay {
+    section: TaskSection,
+    index: usize,
+    /// Text of the task the note belongs to, shown above it
+    task: String,
+    text: String,
+    /// Characters before the cursor
+    cursor: usize,
+    dismissed: bool,
+    saved: bool,
+}
+
+impl NoteOverlay {
+    /// Start from the task's current note, with the cursor at the end
+    pub fn new(section: TaskSection, index: usize, task: &str, note: &str) -> Self {
+        Self {
+            section,
+            index,
+            task: task.to_string(),
+            text: note.to_string(),
+            cursor: note.chars().count(),
+            dismissed: false,
+            saved: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.saved
+    }
+
+    /// Returns where the task is and its new note, or None if dismissed
+    pub fn result(&self) -> Option<(TaskSection, usize, &str)> {
+        self.saved
+            .then_some((self.section, self.index, self.text.as_str()))
+    }
+
+    pub fn handle(&mut self, event: &Event) -> bool {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = event
+        {
+            // Ctrl and Alt chords are shortcuts, not text
+            if !(*modifiers - KeyModifiers::SHIFT).is_empty() {
+                return KeyMap::handle(self, event);
+            }
+            self.insert_char(*c);
+            true
+        } else {
+            KeyMap::handle(self, event)
+        }
+    }
+
+    fn insert_char(&mut self, c: char) {
+        // Pasted tabs and escape sequences arrive as key presses; keep them out of the text
+        if c.is_control() && c != '\n' {
+            return;
+        }
+        self.text.insert(self.byte_offset(self.cursor), c);
+        self.cursor += 1;
+    }
+
+    /// Byte position of the `index`th character, as the cursor counts characters
+    fn byte_offset(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let overlay_area = overlay_rect(frame.area(), 50, NOTE_ROWS as u16 + 7);
+        let inner = render_overlay_frame(frame, overlay_area, " Note ", theme.accent);
+
+        let rows = Layout::vertical([
+            Constraint::Length(1),                // pad
+            Constraint::Length(1),                // task
+            Constraint::Length(1),                // pad
+            Constraint::Length(NOTE_ROWS as u16), // note
+            Constraint::Length(1),                // pad
+            Constraint::Length(1),                // hints
+            Constraint::Min(0),                   // pad
+        ])
+        .split(inner);
+        let inset = |area: Rect| Rect {
+            x: area.x + 1,
+            width: area.width.saturating_sub(2),
+            ..area
+        };
+
+        let task_area = inset(rows[1]);
+        frame.render_widget(
+            Paragraph::new(self.task.as_str()).style(Style::default().fg(Color::DarkGray)),
+            task_area,
+        );
+
+        // Scroll to keep the cursor's line in view
+        let before: String = self.text.chars().take(self.cursor).collect();
+        let cursor_row = before.matches('\n').count();
+        let cursor_col = before.rsplit('\n').next().unwrap_or_default().width() as u16;
+        let lines: Vec<&str> = self.text.split('\n').collect();
+        let scroll = scroll_to_show(cursor_row, lines.len(), NOTE_ROWS);
+        let note_area = inset(rows[3]);
+        let visible: Vec<Line> = lines
+            .iter()
+            .skip(scroll)
+            .map(|line| Line::styled(*line, Style::default().fg(Color::White)))
+            .collect();
+        frame.render_widget(Paragraph::new(visible), note_area);
+        let cursor = (
+            note_area.x + cursor_col,
+            note_area.y + (cursor_row - scroll) as u16,
+        );
+        if cursor.0 < note_area.right() && cursor.1 < note_area.bottom() {
+            frame.set_cursor_position(cursor);
+        }
+
+        let hints = Line::from(vec![
+            Span::styled("[Ctrl-s]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Save "),
+            Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Cancel"),
+        ]);
+        frame.render_widget(
+            Paragraph::new(hints).alignment(Alignment::Center),
+            inset(rows[5]),
+        );
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl NoteOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Save note
+    #[keybind(pressed(key=KeyCode::Char('s'), modifiers=KeyModifiers::CONTROL))]
+    fn save(&mut self) {
+        self.saved = true;
+    }
+
+    /// New line
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn new_line(&mut self) {
+        self.insert_char('\n');
+    }
+
+    /// Delete character
+    #[keybind(pressed(key=KeyCode::Backspace))]
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.text.remove(self.byte_offset(self.cursor - 1));
+            self.cursor -= 1;
+        }
+    }
+
+    /// Move cursor left
+    #[keybind(pressed(key=KeyCode::Left))]
+    fn cursor_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Move cursor right
+    #[keybind(pressed(key=KeyCode::Right))]
+    fn cursor_right(&mut self) {
+        if self.cursor < self.text.chars().count() {
+            self.cursor += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(overlay: &mut NoteOverlay, code: KeyCode) {
+        overlay.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn test_write_note() {
+        let mut overlay = NoteOverlay::new(TaskSection::Current, 1, "Fix parser", "Fails");
+        press(&mut overlay, KeyCode::Enter);
+        for c in "see #12".chars() {
+            press(&mut overlay, KeyCode::Char(c));
+        }
+        assert_eq!(overlay.result(), None);
+        overlay.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('s'),
+            KeyModifiers::CONTROL,
+        )));
+        assert_eq!(
+            overlay.result(),
+            Some((TaskSection::Current, 1, "Fails\nsee #12"))
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::{keymap, KeyMap};
+
+use super::util::{overlay_rect, render_overlay_frame, render_scrollbar, scroll_to_show};
+use crate::theme::Theme;
+
+/// Most commands listed at once; the rest scroll
+const MAX_ROWS: usize = 10;
+
+/// Overlay picking a command by typing part of its name
+pub struct CommandPaletteOverlay {
+    labels: Vec<&'static str>,
+    query: String,
+    /// Indices into `labels` matching the query, best first
+    matches: Vec<usize>,
+    /// Row picked among the matches
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl CommandPaletteOverlay {
+    pub fn new(labels: Vec<&'static str>) -> Self {
+        let matches = (0..labels.len()).collect();
+        Self {
+            labels,
+            query: String::new(),
+            matches,
+            selected: 0,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the index of the chosen label, or None if dismissed
+    pub fn result(&self) -> Option<usize> {
+        self.matches
+            .get(self.selected)
+            .copied()
+            .filter(|_| self.chosen)
+    }
+
+    pub fn handle(&mut self, event: &Event) -> bool {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = event
+        {
+            // Ctrl and Alt chords are shortcuts, not text
+            if !(*modifiers - KeyModifiers::SHIFT).is_empty() {
+                return KeyMap::handle(self, event);
+            }
+            if !c.is_control() {
+                self.query.push(*c);
+                self.update_matches();
+            }
+            true
+        } else {
+            KeyMap::handle(self, event)
+        }
+    }
+
+    fn update_matches(&mut self) {
+        let mut scored: Vec<(usize, u32)> = self
+            .labels
+            .iter()
+            .enumerate()
+            .filter_map(|(i, label)| Some((i, fuzzy_score(label, &self.query)?)))
+            .collect();
+        // Stable, so equal scores keep the listed order
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        self.matches = scored.into_iter().map(|(i, _)| i).collect();
+        self.selected = 0;
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let offset = scroll_to_show(self.selected, self.matches.len(), MAX_ROWS);
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("  > ", Style::default().fg(theme.accent)),
+                Span::styled(&self.query, Style::default().fg(Color::White)),
+            ]),
+            Line::from(""),
+        ];
+        for (row, &i) in self.matches.iter().enumerate().skip(offset).take(MAX_ROWS) {
+            let (marker, style) = if row == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                Span::styled(self.labels[i], style),
+            ]));
+        }
+        if self.matches.is_empty() {
+            lines.push(Line::from(Span::styled("    No matching commands", dim)));
+        }
+        // Keep the height steady while the list narrows
+        let list_rows = MAX_ROWS.min(self.labels.len()).max(1);
+        lines.resize(3 + list_rows, Line::from(""));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Run "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 40, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Commands ", theme.accent);
+        let cursor_x = inner.x + 4 + Line::from(self.query.as_str()).width() as u16;
+        if cursor_x < inner.right() && inner.height > 1 {
+            frame.set_cursor_position((cursor_x, inner.y + 1));
+        }
+        let track = Rect {
+            y: inner.y + 3,
+            height: (list_rows as u16).min(inner.height.saturating_sub(3)),
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(lines), inner);
+        render_scrollbar(frame, track, self.matches.len(), MAX_ROWS, offset);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl CommandPaletteOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Run command
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn choose(&mut self) {
+        self.chosen = !self.matches.is_empty();
+    }
+
+    /// Delete character
+    #[keybind(pressed(key=KeyCode::Backspace))]
+    fn backspace(&mut self) {
+        if self.query.pop().is_some() {
+            self.update_matches();
+        }
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('n'), modifiers=KeyModifiers::CONTROL))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('p'), modifiers=KeyModifiers::CONTROL))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// How well `query` matches `text` when its characters appear in order, ignoring case, or
+/// None if they don't; runs of characters and word starts score higher
+fn fuzzy_score(text: &str, query: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut prev: Option<usize> = None;
+    let chars: Vec<char> = text.chars().collect();
+    let mut from = 0;
+    for q in query.chars().flat_map(char::to_lowercase) {
+        let at = (from..chars.len()).find(|&i| chars[i].to_lowercase().eq(std::iter::once(q)))?;
+        score += 1;
+        if prev.is_some_and(|prev| prev + 1 == at) {
+            score += 3;
+        }
+        if at == 0 || chars[at - 1] == ' ' {
+            score += 2;
+        }
+        prev = Some(at);
+        from = at + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(overlay: &mut CommandPaletteOverlay, code: KeyCode) {
+        overlay.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("Sync tasks with file", "stf").is_some());
+        assert!(fuzzy_score("Sync tasks with file", "fts").is_none());
+        assert!(fuzzy_score("Add task", "ADD").is_some());
+        // Word starts and runs beat letters scattered through the text
+        assert!(fuzzy_score("Add task", "at") > fuzzy_score("Start or pause timer", "at"));
+        assert!(fuzzy_score("Add task", "task") > fuzzy_score("Filter tasks by tag", "tak"));
+    }
+
+    #[test]
+    fn test_choose_command() {
+        let labels = vec!["Start or pause timer", "Add task", "Sync tasks with file"];
+        let mut overlay = CommandPaletteOverlay::new(labels);
+        for c in "sync".chars() {
+            press(&mut overlay, KeyCode::Char(c));
+        }
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some(2));
+
+        // Nothing to run when nothing matches
+        let mut overlay = CommandPaletteOverlay::new(vec!["Add task"]);
+        press(&mut overlay, KeyCode::Char('z'));
+        press(&mut overlay, KeyCode::Enter);
+        assert!(!overlay.is_done());
+        press(&mut overlay, KeyCode::Backspace);
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some(0));
+    }
+}
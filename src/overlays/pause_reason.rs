@@ -0,0 +1,119 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::{keymap, KeyMap};
+use unicode_width::UnicodeWidthStr;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Overlay asking why the work session was paused
+#[derive(Default)]
+pub struct PauseReasonOverlay {
+    text: String,
+    dismissed: bool,
+    submitted: bool,
+}
+
+impl PauseReasonOverlay {
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.submitted
+    }
+
+    /// Returns the reason given, or None if skipped
+    pub fn result(&self) -> Option<String> {
+        self.submitted.then(|| self.text.trim().to_string())
+    }
+
+    pub fn handle(&mut self, event: &Event) -> bool {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = event
+        {
+            if (*modifiers - KeyModifiers::SHIFT).is_empty() && !c.is_control() {
+                self.text.push(*c);
+                return true;
+            }
+        }
+        KeyMap::handle(self, event)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let overlay_area = overlay_rect(frame.area(), 44, 7);
+        let inner = render_overlay_frame(frame, overlay_area, " Why pause? ", theme.accent);
+
+        let rows = Layout::vertical([
+            Constraint::Length(1), // pad
+            Constraint::Length(1), // input
+            Constraint::Length(1), // pad
+            Constraint::Length(1), // hints
+            Constraint::Min(0),    // pad
+        ])
+        .split(inner);
+
+        let input_area = Rect {
+            x: rows[1].x + 1,
+            width: rows[1].width.saturating_sub(2),
+            ..rows[1]
+        };
+        // Keep the end of the text, where typing happens, in view
+        let available = usize::from(input_area.width.saturating_sub(1));
+        let mut visible = self.text.as_str();
+        while visible.width() > available {
+            let mut chars = visible.chars();
+            chars.next();
+            visible = chars.as_str();
+        }
+        let (text, style) = if self.text.is_empty() {
+            ("phone call, meeting, distraction…", Color::DarkGray)
+        } else {
+            (visible, Color::White)
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(text, Style::default().fg(style))),
+            input_area,
+        );
+        frame.set_cursor_position((input_area.x + visible.width() as u16, input_area.y));
+
+        let hints = Line::from(vec![
+            Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Log "),
+            Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Skip"),
+        ]);
+        frame.render_widget(Paragraph::new(hints).alignment(Alignment::Center), rows[3]);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl PauseReasonOverlay {
+    /// Skip
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Log reason
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn submit(&mut self) {
+        if self.text.trim().is_empty() {
+            self.dismissed = true;
+        } else {
+            self.submitted = true;
+        }
+    }
+
+    /// Delete character
+    #[keybind(pressed(key=KeyCode::Backspace))]
+    fn backspace(&mut self) {
+        self.text.pop();
+    }
+}
@@ -0,0 +1,119 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::config::Preset;
+use crate::theme::Theme;
+
+/// Overlay listing the configured presets to switch the session lengths to
+pub struct PresetOverlay {
+    presets: Vec<Preset>,
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl PresetOverlay {
+    pub const fn new(presets: Vec<Preset>) -> Self {
+        Self {
+            presets,
+            selected: 0,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the chosen preset, or None if dismissed
+    pub fn result(&self) -> Option<&Preset> {
+        self.presets.get(self.selected).filter(|_| self.chosen)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let mut lines = vec![Line::from("")];
+        if self.presets.is_empty() {
+            lines.push(Line::from(Span::styled("  No presets in config", dim)));
+        }
+        for (i, preset) in self.presets.iter().enumerate() {
+            let (marker, style) = if i == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                Span::styled(format!("{:<20}", preset.name), style),
+                Span::styled(
+                    format!(
+                        "{}/{}/{} min",
+                        preset.work, preset.short_break, preset.long_break
+                    ),
+                    dim,
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Apply "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 46, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Presets ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl PresetOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Apply preset
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn choose(&mut self) {
+        if self.selected < self.presets.len() {
+            self.chosen = true;
+        }
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.presets.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
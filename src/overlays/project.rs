@@ -0,0 +1,167 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Which project to show, picked from the list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectChoice {
+    All,
+    Project(String),
+}
+
+/// Overlay listing the projects from `##` headers in the task file, to show only the tasks
+/// of one
+pub struct ProjectOverlay {
+    projects: Vec<String>,
+    /// Row picked, where the first row shows every project
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl ProjectOverlay {
+    /// Starts on the project currently shown, if any
+    pub fn new(projects: Vec<String>, current: Option<&str>) -> Self {
+        let selected = current
+            .and_then(|project| projects.iter().position(|p| p == project))
+            .map_or(0, |i| i + 1);
+        Self {
+            projects,
+            selected,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the choice, or None if dismissed
+    pub fn result(&self) -> Option<ProjectChoice> {
+        self.chosen.then(|| {
+            self.selected
+                .checked_sub(1)
+                .and_then(|i| self.projects.get(i))
+                .map_or(ProjectChoice::All, |project| {
+                    ProjectChoice::Project(project.clone())
+                })
+        })
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let rows = std::iter::once("All projects".to_string()).chain(self.projects.iter().cloned());
+        let mut lines = vec![Line::from("")];
+        for (i, row) in rows.enumerate() {
+            let (marker, style) = if i == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else if i == 0 {
+                ("  ", dim)
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                Span::styled(row, style),
+            ]));
+        }
+        if self.projects.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "    No ## headers in the file",
+                dim,
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Switch "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 36, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Projects ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl ProjectOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Switch project
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn choose(&mut self) {
+        self.chosen = true;
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected < self.projects.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{Event, KeyEvent, KeyModifiers};
+    use ratatui_input_manager::KeyMap;
+
+    fn press(overlay: &mut ProjectOverlay, code: KeyCode) {
+        KeyMap::handle(
+            overlay,
+            &Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+        );
+    }
+
+    #[test]
+    fn test_choose_project() {
+        let projects = vec!["Garden".to_string(), "Website".to_string()];
+        let mut overlay = ProjectOverlay::new(projects.clone(), Some("Garden"));
+        press(&mut overlay, KeyCode::Char('j'));
+        assert_eq!(overlay.result(), None);
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(
+            overlay.result(),
+            Some(ProjectChoice::Project("Website".to_string()))
+        );
+
+        // The first row shows every project
+        let mut overlay = ProjectOverlay::new(projects, None);
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some(ProjectChoice::All));
+    }
+}
@@ -0,0 +1,95 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// What to do with task changes not yet in the file when quitting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuitChoice {
+    /// Write them to the task file, then quit
+    Sync,
+    Discard,
+}
+
+/// Overlay shown on quitting with changes made in the app that the task file doesn't have
+pub struct QuitOverlay {
+    changes: usize,
+    choice: Option<QuitChoice>,
+    cancelled: bool,
+}
+
+impl QuitOverlay {
+    pub const fn new(changes: usize) -> Self {
+        Self {
+            changes,
+            choice: None,
+            cancelled: false,
+        }
+    }
+
+    pub const fn is_done(&self) -> bool {
+        self.cancelled || self.choice.is_some()
+    }
+
+    /// What to do before quitting, or None to stay
+    pub const fn result(&self) -> Option<QuitChoice> {
+        self.choice
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let key = Style::default().fg(Color::Yellow);
+        let changes = match self.changes {
+            1 => "1 unsynced change".to_string(),
+            count => format!("{count} unsynced changes"),
+        };
+        let lines = vec![
+            Line::from(""),
+            Line::from(format!("  You have {changes} to the task file")),
+            Line::from(""),
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[s]", key),
+                Span::raw(" Sync "),
+                Span::styled("[d]", key),
+                Span::raw(" Discard "),
+                Span::styled("[Esc]", key),
+                Span::raw(" Cancel quit"),
+            ]),
+            Line::from(""),
+        ];
+
+        let overlay_area = overlay_rect(frame.area(), 50, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Quit ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl QuitOverlay {
+    /// Sync changes to the task file and quit
+    #[keybind(pressed(key=KeyCode::Enter))]
+    #[keybind(pressed(key=KeyCode::Char('s')))]
+    fn sync(&mut self) {
+        self.choice = Some(QuitChoice::Sync);
+    }
+
+    /// Discard changes and quit
+    #[keybind(pressed(key=KeyCode::Char('d')))]
+    fn discard(&mut self) {
+        self.choice = Some(QuitChoice::Discard);
+    }
+
+    /// Cancel quitting
+    #[keybind(pressed(key=KeyCode::Esc))]
+    #[keybind(pressed(key=KeyCode::Char('c')))]
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
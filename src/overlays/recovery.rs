@@ -0,0 +1,115 @@
+use chrono::Local;
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::history::format_focused;
+use crate::recovery::SessionSnapshot;
+use crate::theme::Theme;
+
+/// What to do with a session the app died during
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryChoice {
+    Resume,
+    Reset,
+    /// Record it in history as if it had finished
+    Log,
+}
+
+/// Overlay shown at startup when the last run ended mid-session
+pub struct RecoveryOverlay {
+    snapshot: SessionSnapshot,
+    choice: Option<RecoveryChoice>,
+}
+
+impl RecoveryOverlay {
+    pub const fn new(snapshot: SessionSnapshot) -> Self {
+        Self {
+            snapshot,
+            choice: None,
+        }
+    }
+
+    pub const fn snapshot(&self) -> &SessionSnapshot {
+        &self.snapshot
+    }
+
+    pub const fn is_done(&self) -> bool {
+        self.choice.is_some()
+    }
+
+    pub const fn result(&self) -> Option<RecoveryChoice> {
+        self.choice
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+        let session = self.snapshot.session_type();
+        let remaining = self.snapshot.remaining(Local::now());
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("  {} session was interrupted", session.label()),
+                Style::default()
+                    .fg(theme.session_color(session))
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "  {} of {} left",
+                    format_focused(remaining),
+                    format_focused(self.snapshot.length())
+                ),
+                dim,
+            )),
+        ];
+        if let Some(ref task) = self.snapshot.task {
+            lines.push(Line::from(Span::styled(format!("  Task: {task}"), dim)));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Resume "),
+            Span::styled("[r]", key),
+            Span::raw(" Reset "),
+            Span::styled("[l]", key),
+            Span::raw(" Log as completed"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 52, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Session Recovery ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl RecoveryOverlay {
+    /// Resume the session
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn resume(&mut self) {
+        self.choice = Some(RecoveryChoice::Resume);
+    }
+
+    /// Reset the session
+    #[keybind(pressed(key=KeyCode::Char('r')))]
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn reset(&mut self) {
+        self.choice = Some(RecoveryChoice::Reset);
+    }
+
+    /// Log the session as completed
+    #[keybind(pressed(key=KeyCode::Char('l')))]
+    fn log(&mut self) {
+        self.choice = Some(RecoveryChoice::Log);
+    }
+}
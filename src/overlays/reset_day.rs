@@ -0,0 +1,150 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Most of the Current tasks listed before the rest are counted
+const MAX_LISTED: usize = 8;
+
+/// Overlay previewing a reset of the day: the Current tasks going back to Backlog, and
+/// whether old completed tasks get archived too
+pub struct ResetDayOverlay {
+    tasks: Vec<String>,
+    archivable: usize,
+    archive: bool,
+    confirmed: bool,
+    dismissed: bool,
+}
+
+impl ResetDayOverlay {
+    /// `archivable` completed tasks can be archived, which starts ticked if `archive`
+    pub fn new(tasks: Vec<String>, archivable: usize, archive: bool) -> Self {
+        Self {
+            tasks,
+            archivable,
+            archive: archive && archivable > 0,
+            confirmed: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.confirmed
+    }
+
+    /// Whether to archive as well, or None if dismissed
+    pub fn result(&self) -> Option<bool> {
+        self.confirmed.then_some(self.archive)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let mut lines = vec![Line::from("")];
+        if self.tasks.is_empty() {
+            lines.push(Line::from(Span::styled("  Nothing in Current", dim)));
+        } else {
+            lines.push(Line::from(format!(
+                "  Move {} tasks back to Backlog:",
+                self.tasks.len()
+            )));
+            for task in self.tasks.iter().take(MAX_LISTED) {
+                lines.push(Line::from(vec![
+                    Span::styled("    · ", dim),
+                    Span::raw(task.as_str()),
+                ]));
+            }
+            if self.tasks.len() > MAX_LISTED {
+                lines.push(Line::from(Span::styled(
+                    format!("    and {} more", self.tasks.len() - MAX_LISTED),
+                    dim,
+                )));
+            }
+        }
+        lines.push(Line::from(""));
+        if self.archivable > 0 {
+            let checkbox = if self.archive { "[x]" } else { "[ ]" };
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[a]", key),
+                Span::raw(format!(
+                    " {checkbox} Archive {} completed tasks",
+                    self.archivable
+                )),
+            ]));
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[y]", key),
+            Span::raw(" Reset "),
+            Span::styled("[n]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 50, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Reset Day ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl ResetDayOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    #[keybind(pressed(key=KeyCode::Char('n')))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Reset
+    #[keybind(pressed(key=KeyCode::Enter))]
+    #[keybind(pressed(key=KeyCode::Char('y')))]
+    fn confirm(&mut self) {
+        self.confirmed = true;
+    }
+
+    /// Toggle archiving completed tasks
+    #[keybind(pressed(key=KeyCode::Char('a')))]
+    fn toggle_archive(&mut self) {
+        self.archive = !self.archive && self.archivable > 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{Event, KeyEvent, KeyModifiers};
+    use ratatui_input_manager::KeyMap;
+
+    fn press(overlay: &mut ResetDayOverlay, code: KeyCode) {
+        KeyMap::handle(
+            overlay,
+            &Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+        );
+    }
+
+    #[test]
+    fn test_toggle_archive() {
+        let mut overlay = ResetDayOverlay::new(vec!["Write".to_string()], 2, true);
+        press(&mut overlay, KeyCode::Char('a'));
+        assert_eq!(overlay.result(), None);
+        press(&mut overlay, KeyCode::Char('y'));
+        assert_eq!(overlay.result(), Some(false));
+
+        // Nothing to archive can't be ticked
+        let mut overlay = ResetDayOverlay::new(Vec::new(), 0, true);
+        press(&mut overlay, KeyCode::Char('a'));
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some(false));
+    }
+}
@@ -0,0 +1,212 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+use crate::timer::SessionType;
+
+/// What to do after a session finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionChoice {
+    StartNext,
+    Skip,
+    Extend,
+    /// Put off the break coming next
+    Snooze,
+}
+
+/// Answer to "Did you finish the task?" after a work session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskOutcome {
+    Finished,
+    Partial,
+    NotFinished,
+}
+
+/// Overlay summarizing a finished session and asking what to do next.
+///
+/// After a work session with an active task, it first asks whether the task was finished.
+pub struct SessionCompleteOverlay {
+    completed: SessionType,
+    next: SessionType,
+    next_minutes: u64,
+    pomodoros: u32,
+    /// Active task, while its outcome has not been answered
+    task: Option<String>,
+    task_outcome: Option<TaskOutcome>,
+    choice: Option<SessionChoice>,
+    dismissed: bool,
+}
+
+impl SessionCompleteOverlay {
+    pub fn new(
+        completed: SessionType,
+        next: SessionType,
+        next_minutes: u64,
+        pomodoros: u32,
+        task: Option<String>,
+    ) -> Self {
+        Self {
+            completed,
+            next,
+            next_minutes,
+            pomodoros,
+            task,
+            task_outcome: None,
+            choice: None,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.choice.is_some()
+    }
+
+    /// Returns the chosen next step, or None if dismissed
+    pub fn result(&self) -> Option<SessionChoice> {
+        self.choice
+    }
+
+    /// Whether the task question is still waiting for an answer
+    pub const fn asks_about_task(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// The answer to the task question, once given; cleared once taken
+    pub fn take_task_outcome(&mut self) -> Option<TaskOutcome> {
+        self.task_outcome.take()
+    }
+
+    fn answer(&mut self, outcome: TaskOutcome) {
+        if self.task.take().is_some() {
+            self.task_outcome = Some(outcome);
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("  {} session complete", self.completed.label()),
+                Style::default()
+                    .fg(theme.session_color(self.completed))
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                format!("  Pomodoros completed: {}", self.pomodoros),
+                dim,
+            )),
+        ];
+        lines.push(Line::from(vec![
+            Span::styled("  Up next: ", dim),
+            Span::styled(
+                format!("{} ({} min)", self.next.label(), self.next_minutes),
+                Style::default().fg(theme.session_color(self.next)),
+            ),
+        ]));
+        lines.push(Line::from(""));
+        if let Some(ref task) = self.task {
+            lines.push(Line::from(format!("  Did you finish '{task}'?")));
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[y]", key),
+                Span::raw(" Yes "),
+                Span::styled("[n]", key),
+                Span::raw(" No "),
+                Span::styled("[p]", key),
+                Span::raw(" Partially"),
+            ]));
+        } else {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[Enter]", key),
+                Span::raw(" Start "),
+                Span::styled("[s]", key),
+                Span::raw(" Skip "),
+                Span::styled("[e]", key),
+                Span::raw(" +5 min"),
+            ]));
+            let mut close = vec![Span::raw("  ")];
+            if self.next != SessionType::Work {
+                close.push(Span::styled("[z]", key));
+                close.push(Span::raw(" Snooze "));
+            }
+            close.push(Span::styled("[Esc]", key));
+            close.push(Span::raw(" Close"));
+            lines.push(Line::from(close));
+        }
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 50, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Session Complete ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl SessionCompleteOverlay {
+    /// Close without starting
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Start next session
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn start_next(&mut self) {
+        if self.task.is_none() {
+            self.choice = Some(SessionChoice::StartNext);
+        }
+    }
+
+    /// Skip next session
+    #[keybind(pressed(key=KeyCode::Char('s')))]
+    fn skip(&mut self) {
+        if self.task.is_none() {
+            self.choice = Some(SessionChoice::Skip);
+        }
+    }
+
+    /// Extend finished session by 5 minutes
+    #[keybind(pressed(key=KeyCode::Char('e')))]
+    fn extend(&mut self) {
+        if self.task.is_none() {
+            self.choice = Some(SessionChoice::Extend);
+        }
+    }
+
+    /// Snooze the coming break
+    #[keybind(pressed(key=KeyCode::Char('z')))]
+    fn snooze(&mut self) {
+        if self.task.is_none() && self.next != SessionType::Work {
+            self.choice = Some(SessionChoice::Snooze);
+        }
+    }
+
+    /// Task finished
+    #[keybind(pressed(key=KeyCode::Char('y')))]
+    fn answer_finished(&mut self) {
+        self.answer(TaskOutcome::Finished);
+    }
+
+    /// Task not finished
+    #[keybind(pressed(key=KeyCode::Char('n')))]
+    fn answer_not_finished(&mut self) {
+        self.answer(TaskOutcome::NotFinished);
+    }
+
+    /// Task partially done
+    #[keybind(pressed(key=KeyCode::Char('p')))]
+    fn answer_partial(&mut self) {
+        self.answer(TaskOutcome::Partial);
+    }
+}
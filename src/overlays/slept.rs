@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::history::format_focused;
+use crate::theme::Theme;
+
+/// What to do with a session that was running while the machine slept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepChoice {
+    /// Carry on from where the session was
+    Resume,
+    Reset,
+    /// Count the time asleep and carry on
+    Count,
+}
+
+/// Overlay asking how to treat time the machine spent asleep mid-session
+pub struct SleptOverlay {
+    slept: Duration,
+    choice: Option<SleepChoice>,
+    dismissed: bool,
+}
+
+impl SleptOverlay {
+    pub const fn new(slept: Duration) -> Self {
+        Self {
+            slept,
+            choice: None,
+            dismissed: false,
+        }
+    }
+
+    pub const fn slept(&self) -> Duration {
+        self.slept
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.choice.is_some()
+    }
+
+    /// Returns the choice made, or None to leave the session paused
+    pub const fn result(&self) -> Option<SleepChoice> {
+        self.choice
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let key = Style::default().fg(Color::Yellow);
+        let lines = vec![
+            Line::from(""),
+            Line::from(format!("  System slept for {}", format_focused(self.slept))),
+            Line::from(""),
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[Enter]", key),
+                Span::raw(" Resume "),
+                Span::styled("[r]", key),
+                Span::raw(" Reset "),
+                Span::styled("[c]", key),
+                Span::raw(" Count it"),
+            ]),
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[Esc]", key),
+                Span::raw(" Stay paused"),
+            ]),
+            Line::from(""),
+        ];
+
+        let overlay_area = overlay_rect(frame.area(), 46, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Welcome Back ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl SleptOverlay {
+    /// Stay paused
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Resume where the session was
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn resume(&mut self) {
+        self.choice = Some(SleepChoice::Resume);
+    }
+
+    /// Reset the session
+    #[keybind(pressed(key=KeyCode::Char('r')))]
+    fn reset(&mut self) {
+        self.choice = Some(SleepChoice::Reset);
+    }
+
+    /// Count the time asleep
+    #[keybind(pressed(key=KeyCode::Char('c')))]
+    fn count(&mut self) {
+        self.choice = Some(SleepChoice::Count);
+    }
+}
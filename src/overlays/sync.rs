@@ -1,12 +1,14 @@
 use ratatui::{
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::Paragraph,
     Frame,
 };
 use ratatui_input_manager::keymap;
 
-use super::util::centered_rect;
+use super::util::{overlay_rect, render_overlay_frame, render_scrollbar, scroll_to_show};
+use crate::theme::Theme;
 use crossterm::event::KeyCode;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,11 +18,99 @@ pub enum SyncResolution {
     Remove,
 }
 
+/// Where a task stands in the app and in the file when a sync is worked out, and where it
+/// stood in the file when the app last read or wrote it, as `Incomplete` or `Complete`, or
+/// None where it is missing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncOrigin {
+    pub app: Option<SyncResolution>,
+    pub file: Option<SyncResolution>,
+    pub baseline: Option<SyncResolution>,
+}
+
+impl SyncOrigin {
+    /// The way the one side that changed since the baseline went, or None if both changed.
+    /// Missing from the side that changed means it was deleted there.
+    pub fn merged(self) -> Option<SyncResolution> {
+        if self.app == self.baseline {
+            Some(self.file.unwrap_or(SyncResolution::Remove))
+        } else if self.file == self.baseline {
+            Some(self.app.unwrap_or(SyncResolution::Remove))
+        } else {
+            None
+        }
+    }
+
+    /// Which sides changed the item since the baseline, shown beside what will happen to it
+    fn changed(self) -> &'static str {
+        match self.merged() {
+            None => "changed in both",
+            Some(_) if self.app == self.baseline => "changed in file",
+            Some(_) => "changed in app",
+        }
+    }
+
+    /// Heading of the group the overlay lists the item under, with the group's place
+    fn group(self) -> (usize, &'static str) {
+        match (self.app, self.file) {
+            (None, Some(_)) => (0, "In file only"),
+            (Some(_), None) => (1, "In app only"),
+            (Some(SyncResolution::Incomplete), Some(SyncResolution::Complete)) => {
+                (2, "Completed in file")
+            }
+            (Some(SyncResolution::Complete), Some(SyncResolution::Incomplete)) => {
+                (3, "Completed in app")
+            }
+            _ => (4, "Other changes"),
+        }
+    }
+
+    /// What applying `resolution` does to each side, e.g. "will be added to app"
+    fn action(self, resolution: SyncResolution) -> String {
+        let mut changes = Vec::new();
+        for (side, name) in [(self.app, "app"), (self.file, "file")] {
+            match (side, resolution) {
+                (None, SyncResolution::Remove) => {}
+                (Some(_), SyncResolution::Remove) => changes.push(format!("removed from {name}")),
+                (None, _) => changes.push(format!("added to {name}")),
+                (Some(was), _) if was != resolution => {
+                    let checkbox = if resolution == SyncResolution::Complete {
+                        "[x]"
+                    } else {
+                        "[ ]"
+                    };
+                    changes.push(format!("marked {checkbox} in {name}"));
+                }
+                (Some(_), _) => {}
+            }
+        }
+        if changes.is_empty() {
+            "no change".to_string()
+        } else {
+            format!("will be {}", changes.join(" and "))
+        }
+    }
+}
+
 /// A task item to sync with desired resolution (incomplete/complete/remove)
 #[derive(Debug, Clone)]
 pub struct SyncItem {
     pub text: String,
     pub resolution: SyncResolution,
+    pub origin: SyncOrigin,
+}
+
+/// Items for tests of writing the file, which don't care where an item came from
+#[cfg(test)]
+impl SyncItem {
+    /// An item resolved as `resolution`, with no record of where it came from
+    pub fn new(text: String, resolution: SyncResolution) -> Self {
+        Self {
+            text,
+            resolution,
+            origin: SyncOrigin::default(),
+        }
+    }
 }
 
 /// Overlay for reviewing and applying task file sync changes
@@ -32,7 +122,9 @@ pub struct SyncOverlay {
 }
 
 impl SyncOverlay {
-    pub fn new(items: Vec<SyncItem>) -> Self {
+    /// Items are listed grouped by where they came from, in their order within each group
+    pub fn new(mut items: Vec<SyncItem>) -> Self {
+        items.sort_by_key(|item| item.origin.group().0);
         Self {
             items,
             focused: 0,
@@ -50,19 +142,31 @@ impl SyncOverlay {
         self.applied.then_some(&self.items)
     }
 
-    pub fn render(&self, frame: &mut Frame) {
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
         let mut lines: Vec<Line> = Vec::new();
-        lines.push(Line::from(""));
+        // Line showing what happens to the focused item, kept in view
+        let mut focused_line = 0;
 
         if self.items.is_empty() {
-            lines.push(Line::from(Span::styled(
-                "  No changes",
-                Style::default().fg(Color::DarkGray),
-            )));
+            lines.push(Line::from(Span::styled("  No changes", dim)));
         } else {
+            let mut heading = None;
             for (i, item) in self.items.iter().enumerate() {
                 let is_focused = i == self.focused;
 
+                let group = item.origin.group().1;
+                if heading != Some(group) {
+                    if heading.is_some() {
+                        lines.push(Line::from(""));
+                    }
+                    heading = Some(group);
+                    lines.push(Line::from(Span::styled(
+                        format!("  {group}"),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )));
+                }
+
                 let (checkbox, color) = match item.resolution {
                     SyncResolution::Incomplete => ("[ ]", Color::Blue),
                     SyncResolution::Complete => ("[x]", Color::Green),
@@ -71,7 +175,7 @@ impl SyncOverlay {
 
                 let prefix = if is_focused { "> " } else { "  " };
                 let prefix_style = if is_focused {
-                    Style::default().fg(Color::Cyan)
+                    Style::default().fg(theme.accent)
                 } else {
                     Style::default()
                 };
@@ -88,44 +192,68 @@ impl SyncOverlay {
                     Span::styled(format!("{checkbox} "), Style::default().fg(color)),
                     Span::styled(&item.text, text_style),
                 ]));
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "          → {}, {}",
+                        item.origin.changed(),
+                        item.origin.action(item.resolution)
+                    ),
+                    dim,
+                )));
+                if is_focused {
+                    focused_line = lines.len() - 1;
+                }
             }
         }
 
-        lines.push(Line::from(""));
-        lines.push(Line::from(vec![
-            Span::raw("      "),
-            Span::styled("[Space]", Style::default().fg(Color::Blue)),
-            Span::raw(" "),
-            Span::styled("[x]", Style::default().fg(Color::Green)),
-            Span::raw(" "),
-            Span::styled("[d]", Style::default().fg(Color::Red)),
-            Span::raw(" Change State"),
-        ]));
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled("[j/k]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Navigate "),
-            Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Apply "),
-            Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Cancel"),
-        ]));
-        lines.push(Line::from(""));
-
-        let content_height = lines.len() as u16 + 2;
-        let overlay_width = 50u16;
-        let overlay_height = content_height.min(frame.area().height.saturating_sub(4));
-
-        let overlay_area = centered_rect(frame.area(), overlay_width, overlay_height);
-        frame.render_widget(Clear, overlay_area);
-
-        let block = Block::default()
-            .title(" Sync ")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan));
-
-        let paragraph = Paragraph::new(lines).block(block);
-        frame.render_widget(paragraph, overlay_area);
+        let hints = vec![
+            Line::from(vec![
+                Span::raw("      "),
+                Span::styled("[Space]", Style::default().fg(Color::Blue)),
+                Span::raw(" "),
+                Span::styled("[x]", Style::default().fg(Color::Green)),
+                Span::raw(" "),
+                Span::styled("[d]", Style::default().fg(Color::Red)),
+                Span::raw(" Change State"),
+            ]),
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[j/k]", Style::default().fg(Color::Yellow)),
+                Span::raw(" Navigate "),
+                Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+                Span::raw(" Apply "),
+                Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+                Span::raw(" Cancel"),
+            ]),
+        ];
+
+        // Items plus blank rows around them and the hints, inside the borders
+        let content_height = (lines.len() + hints.len()) as u16 + 3 + 2;
+        let overlay_width = 60u16;
+
+        let overlay_area = overlay_rect(frame.area(), overlay_width, content_height);
+        let inner = render_overlay_frame(frame, overlay_area, " Sync ", theme.accent);
+
+        // The item list gives up rows first and scrolls to keep the focused item in view
+        let rows = Layout::vertical([
+            Constraint::Length(1),                  // pad
+            Constraint::Min(1),                     // items
+            Constraint::Length(1),                  // pad
+            Constraint::Length(hints.len() as u16), // hints
+            Constraint::Length(1),                  // pad
+        ])
+        .split(inner);
+
+        let visible = rows[1].height as usize;
+        let scroll = scroll_to_show(focused_line, lines.len(), visible);
+        let track = Rect {
+            x: overlay_area.x,
+            width: overlay_area.width,
+            ..rows[1]
+        };
+        render_scrollbar(frame, track, lines.len(), visible, scroll);
+        frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), rows[1]);
+        frame.render_widget(Paragraph::new(hints), rows[3]);
     }
 }
 
@@ -183,3 +311,94 @@ impl SyncOverlay {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SyncResolution::{Complete, Incomplete, Remove};
+
+    fn origin(
+        app: Option<SyncResolution>,
+        file: Option<SyncResolution>,
+        baseline: Option<SyncResolution>,
+    ) -> SyncOrigin {
+        SyncOrigin {
+            app,
+            file,
+            baseline,
+        }
+    }
+
+    #[test]
+    fn test_merged() {
+        // Only the file changed
+        assert_eq!(
+            origin(Some(Complete), Some(Incomplete), Some(Complete)).merged(),
+            Some(Incomplete)
+        );
+        // Only the app changed, deleting it there
+        assert_eq!(
+            origin(None, Some(Incomplete), Some(Incomplete)).merged(),
+            Some(Remove)
+        );
+        // Both changed
+        assert_eq!(
+            origin(Some(Complete), None, Some(Incomplete)).merged(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_changed() {
+        assert_eq!(
+            origin(Some(Complete), Some(Incomplete), Some(Complete)).changed(),
+            "changed in file"
+        );
+        assert_eq!(
+            origin(None, Some(Incomplete), Some(Incomplete)).changed(),
+            "changed in app"
+        );
+        assert_eq!(
+            origin(Some(Complete), None, Some(Incomplete)).changed(),
+            "changed in both"
+        );
+    }
+
+    fn item(text: &str, app: Option<SyncResolution>, file: Option<SyncResolution>) -> SyncItem {
+        SyncItem {
+            text: text.to_string(),
+            resolution: SyncResolution::Complete,
+            origin: origin(app, file, None),
+        }
+    }
+
+    #[test]
+    fn test_items_grouped_with_actions() {
+        let overlay = SyncOverlay::new(vec![
+            item("Done in app", Some(Complete), Some(Incomplete)),
+            item("New in app", Some(Incomplete), None),
+            item("New in file", None, Some(Complete)),
+        ]);
+        let texts: Vec<&str> = overlay.items.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, ["New in file", "New in app", "Done in app"]);
+
+        let actions: Vec<String> = overlay
+            .items
+            .iter()
+            .map(|i| i.origin.action(i.resolution))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                "will be added to app",
+                "will be marked [x] in app and added to file",
+                "will be marked [x] in file",
+            ]
+        );
+        let origin = SyncOrigin {
+            app: Some(Incomplete),
+            ..SyncOrigin::default()
+        };
+        assert_eq!(origin.action(Remove), "will be removed from app");
+    }
+}
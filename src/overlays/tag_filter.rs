@@ -0,0 +1,159 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// What to show, picked from the list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagChoice {
+    All,
+    Tag(String),
+}
+
+/// Overlay listing the tags found across sections, to show only the tasks with one
+pub struct TagFilterOverlay {
+    tags: Vec<String>,
+    /// Row picked, where the first row clears the filter
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl TagFilterOverlay {
+    /// Starts on the tag currently filtered by, if any
+    pub fn new(tags: Vec<String>, current: Option<&str>) -> Self {
+        let selected = current
+            .and_then(|tag| tags.iter().position(|t| t == tag))
+            .map_or(0, |i| i + 1);
+        Self {
+            tags,
+            selected,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the choice, or None if dismissed
+    pub fn result(&self) -> Option<TagChoice> {
+        self.chosen.then(|| {
+            self.selected
+                .checked_sub(1)
+                .and_then(|i| self.tags.get(i))
+                .map_or(TagChoice::All, |tag| TagChoice::Tag(tag.clone()))
+        })
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let rows = std::iter::once("All tasks".to_string())
+            .chain(self.tags.iter().map(|tag| format!("#{tag}")));
+        let mut lines = vec![Line::from("")];
+        for (i, row) in rows.enumerate() {
+            let (marker, style) = if i == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else if i == 0 {
+                ("  ", dim)
+            } else {
+                ("  ", Style::default().fg(Color::Magenta))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                Span::styled(row, style),
+            ]));
+        }
+        if self.tags.is_empty() {
+            lines.push(Line::from(Span::styled("    No #tags in any task", dim)));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Filter "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 36, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Filter by Tag ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl TagFilterOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Filter by tag
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn choose(&mut self) {
+        self.chosen = true;
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected < self.tags.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{Event, KeyEvent, KeyModifiers};
+    use ratatui_input_manager::KeyMap;
+
+    fn press(overlay: &mut TagFilterOverlay, code: KeyCode) {
+        KeyMap::handle(
+            overlay,
+            &Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+        );
+    }
+
+    #[test]
+    fn test_choose_tag() {
+        let tags = vec!["home".to_string(), "work".to_string()];
+        let mut overlay = TagFilterOverlay::new(tags.clone(), Some("work"));
+        assert_eq!(overlay.result(), None);
+        press(&mut overlay, KeyCode::Char('k'));
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some(TagChoice::Tag("home".to_string())));
+
+        // The first row clears the filter
+        let mut overlay = TagFilterOverlay::new(tags, None);
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some(TagChoice::All));
+    }
+}
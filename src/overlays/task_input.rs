@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Style},
@@ -7,15 +7,26 @@ use ratatui::{
     Frame,
 };
 use ratatui_input_manager::{keymap, KeyMap};
+use unicode_width::UnicodeWidthStr;
 
-use super::util::{centered_rect, render_overlay_frame};
-use crate::task::TaskSection;
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::task::{Task, TaskSection};
+use crate::theme::Theme;
 
-/// Overlay for adding new tasks
+/// What the submitted text is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskInputAction {
+    /// A new task at the end of the section
+    Add(TaskSection),
+    /// New text for the task at `index` in `section`
+    Edit { section: TaskSection, index: usize },
+}
+
+/// Overlay for adding new tasks or editing an existing one
 pub struct TaskInputOverlay {
     text: String,
     cursor: usize,
-    section: TaskSection,
+    action: TaskInputAction,
     dismissed: bool,
     submitted: bool,
 }
@@ -25,7 +36,18 @@ impl TaskInputOverlay {
         Self {
             text: String::new(),
             cursor: 0,
-            section,
+            action: TaskInputAction::Add(section),
+            dismissed: false,
+            submitted: false,
+        }
+    }
+
+    /// Start from a task's current text, with the cursor at the end
+    pub fn edit(section: TaskSection, index: usize, text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+            action: TaskInputAction::Edit { section, index },
             dismissed: false,
             submitted: false,
         }
@@ -35,19 +57,24 @@ impl TaskInputOverlay {
         self.dismissed || self.submitted
     }
 
-    /// Returns the submitted task text and section, or None if dismissed
-    pub fn result(&self) -> Option<(String, TaskSection)> {
+    /// Returns the submitted task text and what it is for, or None if dismissed
+    pub fn result(&self) -> Option<(String, TaskInputAction)> {
         self.submitted
-            .then(|| (self.text.trim().to_string(), self.section))
+            .then(|| (self.text.trim().to_string(), self.action))
     }
 
     pub fn handle(&mut self, event: &Event) -> bool {
         if let Event::Key(KeyEvent {
             code: KeyCode::Char(c),
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = event
         {
+            // Ctrl and Alt chords are shortcuts, not text
+            if !(*modifiers - KeyModifiers::SHIFT).is_empty() {
+                return KeyMap::handle(self, event);
+            }
             self.insert_char(*c);
             true
         } else {
@@ -56,26 +83,40 @@ impl TaskInputOverlay {
     }
 
     fn insert_char(&mut self, c: char) {
-        self.text.insert(self.cursor, c);
+        // Pasted tabs and escape sequences arrive as key presses; keep them out of the text
+        if c.is_control() {
+            return;
+        }
+        self.text.insert(self.byte_offset(self.cursor), c);
         self.cursor += 1;
     }
 
-    pub fn render(&self, frame: &mut Frame) {
-        let title = match self.section {
-            TaskSection::Backlog => " Add to Backlog ",
-            TaskSection::Current => " Add to Current ",
-            TaskSection::Completed => " Add Task ",
+    /// Byte position of the `index`th character, as the cursor counts characters
+    fn byte_offset(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let (title, submit) = match self.action {
+            TaskInputAction::Add(TaskSection::Backlog) => (" Add to Backlog ", " Add "),
+            TaskInputAction::Add(TaskSection::Current) => (" Add to Current ", " Add "),
+            TaskInputAction::Add(TaskSection::Completed) => (" Add Task ", " Add "),
+            TaskInputAction::Edit { .. } => (" Edit Task ", " Save "),
         };
 
         let overlay_width = 40u16;
-        let overlay_height = 7u16;
+        let overlay_height = 8u16;
 
-        let overlay_area = centered_rect(frame.area(), overlay_width, overlay_height);
-        let inner = render_overlay_frame(frame, overlay_area, title, Color::Cyan);
+        let overlay_area = overlay_rect(frame.area(), overlay_width, overlay_height);
+        let inner = render_overlay_frame(frame, overlay_area, title, theme.accent);
 
         let rows = Layout::vertical([
             Constraint::Length(1), // pad
             Constraint::Length(1), // input
+            Constraint::Length(1), // preview
             Constraint::Length(1), // pad
             Constraint::Length(1), // hints
             Constraint::Min(0),    // pad
@@ -104,21 +145,34 @@ impl TaskInputOverlay {
         ));
         frame.render_widget(Paragraph::new(input_line), input_area);
 
-        let cursor_x = input_area.x + cursor_pos as u16;
+        let cursor_x = input_area.x
+            + visible_text
+                .chars()
+                .take(cursor_pos)
+                .collect::<String>()
+                .width() as u16;
         if cursor_x < input_area.x + input_area.width {
             frame.set_cursor_position((cursor_x, input_area.y));
         }
 
         let hints = Line::from(vec![
             Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
-            Span::raw(" Add "),
+            Span::raw(submit),
             Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
             Span::raw(" Cancel"),
         ]);
+        let preview_area = Rect {
+            x: rows[2].x + 1,
+            width: rows[2].width.saturating_sub(2),
+            ..rows[2]
+        };
+        let task = Task::new(self.text.clone());
+        frame.render_widget(Paragraph::new(preview(&task)), preview_area);
+
         let hints_area = Rect {
-            x: rows[3].x + 1,
-            width: rows[3].width.saturating_sub(2),
-            ..rows[3]
+            x: rows[4].x + 1,
+            width: rows[4].width.saturating_sub(2),
+            ..rows[4]
         };
         frame.render_widget(
             Paragraph::new(hints).alignment(Alignment::Center),
@@ -127,6 +181,50 @@ impl TaskInputOverlay {
     }
 }
 
+/// Fields picked out of the text as typed, e.g. `high · #backend · ~3🍅 · due Fri 7 Jun`
+fn preview(task: &Task) -> Line<'static> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut fields = Vec::new();
+    if let Some(priority) = task.priority {
+        fields.push(Span::styled(
+            priority.label(),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    for tag in &task.tags {
+        fields.push(Span::styled(
+            format!("#{tag}"),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+    if let Some(estimate) = task.estimate {
+        fields.push(Span::styled(format!("~{estimate}🍅"), dim));
+    }
+    if let Some(duration) = task.duration() {
+        fields.push(Span::styled(
+            format!("{}m work", duration.as_secs() / 60),
+            dim,
+        ));
+    }
+    if let Some(due) = task.due {
+        fields.push(Span::styled(
+            format!("due {}", due.format("%a %-d %b")),
+            dim,
+        ));
+    }
+    if let Some(recurrence) = task.recurrence {
+        fields.push(Span::styled(recurrence.label(), dim));
+    }
+    let mut spans = Vec::new();
+    for field in fields {
+        if !spans.is_empty() {
+            spans.push(Span::styled(" · ", dim));
+        }
+        spans.push(field);
+    }
+    Line::from(spans)
+}
+
 #[keymap(backend = "crossterm")]
 impl TaskInputOverlay {
     /// Cancel
@@ -135,7 +233,7 @@ impl TaskInputOverlay {
         self.dismissed = true;
     }
 
-    /// Add task
+    /// Add or save task
     #[keybind(pressed(key=KeyCode::Enter))]
     fn submit(&mut self) {
         if self.text.trim().is_empty() {
@@ -149,7 +247,7 @@ impl TaskInputOverlay {
     #[keybind(pressed(key=KeyCode::Backspace))]
     fn backspace(&mut self) {
         if self.cursor > 0 {
-            self.text.remove(self.cursor - 1);
+            self.text.remove(self.byte_offset(self.cursor - 1));
             self.cursor -= 1;
         }
     }
@@ -165,8 +263,89 @@ impl TaskInputOverlay {
     /// Move cursor right
     #[keybind(pressed(key=KeyCode::Right))]
     fn cursor_right(&mut self) {
-        if self.cursor < self.text.len() {
+        if self.cursor < self.text.chars().count() {
             self.cursor += 1;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(overlay: &mut TaskInputOverlay, code: KeyCode) {
+        overlay.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn test_edit_multibyte_text() {
+        let mut overlay = TaskInputOverlay::new(TaskSection::Backlog);
+        for c in "🍕 café".chars() {
+            press(&mut overlay, KeyCode::Char(c));
+        }
+        press(&mut overlay, KeyCode::Char('\t'));
+        press(&mut overlay, KeyCode::Left);
+        press(&mut overlay, KeyCode::Backspace);
+        press(&mut overlay, KeyCode::Char('e'));
+        press(&mut overlay, KeyCode::Right);
+        press(&mut overlay, KeyCode::Right);
+        press(&mut overlay, KeyCode::Enter);
+
+        assert_eq!(
+            overlay.result(),
+            Some((
+                "🍕 caeé".to_string(),
+                TaskInputAction::Add(TaskSection::Backlog)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_control_chords_are_not_typed() {
+        let mut overlay = TaskInputOverlay::new(TaskSection::Current);
+        overlay.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('A'),
+            KeyModifiers::SHIFT,
+        )));
+        overlay.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('i'),
+            KeyModifiers::CONTROL,
+        )));
+        press(&mut overlay, KeyCode::Enter);
+
+        assert_eq!(
+            overlay.result(),
+            Some(("A".to_string(), TaskInputAction::Add(TaskSection::Current)))
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        let task = Task::new("Fix login bug #backend !high ~3 due:2024-06-07".to_string());
+        assert_eq!(
+            preview(&task).to_string(),
+            "high · #backend · ~3🍅 · due Fri 7 Jun"
+        );
+        let task = Task::new("Water plants every:fri".to_string());
+        assert_eq!(preview(&task).to_string(), "every Fri");
+        assert_eq!(preview(&Task::new("Plain".to_string())).to_string(), "");
+    }
+
+    #[test]
+    fn test_edit_starts_from_task_text() {
+        let mut overlay = TaskInputOverlay::edit(TaskSection::Current, 2, "Write repor");
+        press(&mut overlay, KeyCode::Char('t'));
+        press(&mut overlay, KeyCode::Enter);
+
+        assert_eq!(
+            overlay.result(),
+            Some((
+                "Write report".to_string(),
+                TaskInputAction::Edit {
+                    section: TaskSection::Current,
+                    index: 2
+                }
+            ))
+        );
+    }
+}
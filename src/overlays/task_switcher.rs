@@ -0,0 +1,111 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Overlay listing the Current tasks to pick which one is active
+pub struct TaskSwitcherOverlay {
+    tasks: Vec<String>,
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl TaskSwitcherOverlay {
+    pub const fn new(tasks: Vec<String>) -> Self {
+        Self {
+            tasks,
+            selected: 0,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the index in Current of the task to make active, or None if dismissed
+    pub fn result(&self) -> Option<usize> {
+        (self.chosen && self.selected < self.tasks.len()).then_some(self.selected)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let key = Style::default().fg(Color::Yellow);
+
+        let mut lines = vec![Line::from("")];
+        if self.tasks.is_empty() {
+            lines.push(Line::from(Span::styled("  No tasks in Current", dim)));
+        }
+        for (i, task) in self.tasks.iter().enumerate() {
+            let (marker, style) = if i == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                Span::styled(task.as_str(), style),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Make active "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 50, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Active Task ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl TaskSwitcherOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Make task active
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn choose(&mut self) {
+        if self.selected < self.tasks.len() {
+            self.chosen = true;
+        }
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.tasks.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
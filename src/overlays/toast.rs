@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::theme::Theme;
+
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Transient message shown in the bottom-right corner without capturing input
+pub struct Toast {
+    message: String,
+    shown_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            shown_at: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.shown_at.elapsed() >= TOAST_DURATION
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let area = frame.area();
+        let width = (self.message.chars().count() as u16 + 4).min(area.width.saturating_sub(2));
+        let height = 3u16.min(area.height);
+        if width < 5 || height < 3 {
+            return;
+        }
+
+        let toast_area = Rect::new(
+            area.x + area.width - width - 1,
+            area.y + area.height - height,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, toast_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent));
+        let paragraph = Paragraph::new(self.message.as_str())
+            .style(Style::default().fg(Color::White))
+            .block(block);
+        frame.render_widget(paragraph, toast_area);
+    }
+}
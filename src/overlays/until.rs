@@ -0,0 +1,175 @@
+use chrono::{Local, NaiveTime};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::{keymap, KeyMap};
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Longest input accepted, `HH:MM`
+const MAX_LEN: usize = 5;
+
+/// Overlay asking for a clock time to count down to, or to schedule a session at
+pub struct UntilOverlay {
+    title: &'static str,
+    /// What Enter does, shown in the hints
+    action: &'static str,
+    text: String,
+    /// Why the last submitted text was rejected
+    error: Option<String>,
+    target: Option<NaiveTime>,
+    dismissed: bool,
+}
+
+impl Default for UntilOverlay {
+    fn default() -> Self {
+        Self::new(" Count down until ", "Start")
+    }
+}
+
+impl UntilOverlay {
+    pub const fn new(title: &'static str, action: &'static str) -> Self {
+        Self {
+            title,
+            action,
+            text: String::new(),
+            error: None,
+            target: None,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.target.is_some()
+    }
+
+    /// Returns the entered time, or None if dismissed
+    pub const fn result(&self) -> Option<NaiveTime> {
+        self.target
+    }
+
+    pub fn handle(&mut self, event: &Event) -> bool {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char(c @ ('0'..='9' | ':')),
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        {
+            if self.text.len() < MAX_LEN {
+                self.text.push(*c);
+            }
+            self.error = None;
+            true
+        } else {
+            KeyMap::handle(self, event)
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let overlay_area = overlay_rect(frame.area(), 32, 7);
+        let inner = render_overlay_frame(frame, overlay_area, self.title, theme.accent);
+
+        let rows = Layout::vertical([
+            Constraint::Length(1), // pad
+            Constraint::Length(1), // input
+            Constraint::Length(1), // error
+            Constraint::Length(1), // hints
+            Constraint::Min(0),    // pad
+        ])
+        .split(inner);
+
+        let input_area = Rect {
+            x: rows[1].x + 1,
+            width: rows[1].width.saturating_sub(2),
+            ..rows[1]
+        };
+        let input = Line::from(vec![
+            Span::styled("Time (HH:MM): ", Style::default().fg(Color::DarkGray)),
+            Span::styled(&self.text, Style::default().fg(Color::White)),
+        ]);
+        let cursor_x = input_area.x + input.width() as u16;
+        frame.render_widget(Paragraph::new(input), input_area);
+        if cursor_x < input_area.right() {
+            frame.set_cursor_position((cursor_x, input_area.y));
+        }
+
+        if let Some(ref error) = self.error {
+            frame.render_widget(
+                Paragraph::new(error.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .alignment(Alignment::Center),
+                rows[2],
+            );
+        }
+
+        let hints = Line::from(vec![
+            Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" {} ", self.action)),
+            Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Cancel"),
+        ]);
+        frame.render_widget(Paragraph::new(hints).alignment(Alignment::Center), rows[3]);
+    }
+}
+
+/// Parse `HH:MM` as a time later today
+fn parse_target(text: &str, now: NaiveTime) -> Result<NaiveTime, String> {
+    let time =
+        NaiveTime::parse_from_str(text, "%H:%M").map_err(|_| format!("'{text}' is not a time"))?;
+    if time <= now {
+        return Err(format!("{} has already passed", time.format("%H:%M")));
+    }
+    Ok(time)
+}
+
+#[keymap(backend = "crossterm")]
+impl UntilOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Start the countdown
+    #[keybind(pressed(key=KeyCode::Enter))]
+    fn submit(&mut self) {
+        match parse_target(&self.text, Local::now().time()) {
+            Ok(time) => self.target = Some(time),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Delete character
+    #[keybind(pressed(key=KeyCode::Backspace))]
+    fn backspace(&mut self) {
+        self.text.pop();
+        self.error = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("14:30", time(9, 0)), Ok(time(14, 30)));
+        assert_eq!(parse_target("9:05", time(9, 0)), Ok(time(9, 5)));
+        assert_eq!(
+            parse_target("08:00", time(9, 0)),
+            Err("08:00 has already passed".to_string())
+        );
+        assert!(parse_target("25:00", time(9, 0)).is_err());
+        assert!(parse_target("", time(9, 0)).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use ratatui_input_manager::keymap;
+
+use super::util::{overlay_rect, render_overlay_frame};
+use crate::theme::Theme;
+
+/// Overlay picking one of the links in a task to open
+pub struct UrlPickerOverlay {
+    urls: Vec<String>,
+    selected: usize,
+    chosen: bool,
+    dismissed: bool,
+}
+
+impl UrlPickerOverlay {
+    pub const fn new(urls: Vec<String>) -> Self {
+        Self {
+            urls,
+            selected: 0,
+            chosen: false,
+            dismissed: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.dismissed || self.chosen
+    }
+
+    /// Returns the link to open, or None if dismissed
+    pub fn result(&self) -> Option<&str> {
+        self.urls
+            .get(self.selected)
+            .map(String::as_str)
+            .filter(|_| self.chosen)
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let key = Style::default().fg(Color::Yellow);
+
+        let mut lines = vec![Line::from("")];
+        for (i, url) in self.urls.iter().enumerate() {
+            let (marker, style) = if i == self.selected {
+                (
+                    "> ",
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::White))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {marker}"), Style::default().fg(theme.accent)),
+                Span::styled(url.as_str(), style),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("[Enter]", key),
+            Span::raw(" Open "),
+            Span::styled("[Esc]", key),
+            Span::raw(" Cancel"),
+        ]));
+        lines.push(Line::from(""));
+
+        let overlay_area = overlay_rect(frame.area(), 60, lines.len() as u16 + 2);
+        let inner = render_overlay_frame(frame, overlay_area, " Open Link ", theme.accent);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[keymap(backend = "crossterm")]
+impl UrlPickerOverlay {
+    /// Cancel
+    #[keybind(pressed(key=KeyCode::Esc))]
+    fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Open link
+    #[keybind(pressed(key=KeyCode::Enter))]
+    #[keybind(pressed(key=KeyCode::Char('o')))]
+    fn choose(&mut self) {
+        self.chosen = true;
+    }
+
+    /// Move down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.urls.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{Event, KeyEvent, KeyModifiers};
+    use ratatui_input_manager::KeyMap;
+
+    fn press(overlay: &mut UrlPickerOverlay, code: KeyCode) {
+        KeyMap::handle(
+            overlay,
+            &Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+        );
+    }
+
+    #[test]
+    fn test_choose_url() {
+        let urls = vec![
+            "https://a.example".to_string(),
+            "https://b.example".to_string(),
+        ];
+        let mut overlay = UrlPickerOverlay::new(urls);
+        press(&mut overlay, KeyCode::Char('j'));
+        press(&mut overlay, KeyCode::Char('j'));
+        assert_eq!(overlay.result(), None);
+        press(&mut overlay, KeyCode::Enter);
+        assert_eq!(overlay.result(), Some("https://b.example"));
+    }
+}
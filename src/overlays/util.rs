@@ -1,10 +1,13 @@
 use ratatui::{
     layout::Rect,
     style::{Color, Style},
-    widgets::{Block, Borders, Clear},
+    widgets::{Block, Borders, Clear, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
 
+/// Gap kept between an overlay and each frame edge, when the frame is big enough
+const OVERLAY_MARGIN: u16 = 2;
+
 pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
     let w = width.min(area.width);
     let h = height.min(area.height);
@@ -13,6 +16,59 @@ pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
     Rect::new(x, y, w, h)
 }
 
+/// Center an overlay of the requested size, shrinking it to stay inside the frame
+pub fn overlay_rect(frame_area: Rect, width: u16, height: u16) -> Rect {
+    let fit = |len: u16| {
+        if len > OVERLAY_MARGIN * 2 {
+            len - OVERLAY_MARGIN * 2
+        } else {
+            len
+        }
+    };
+    centered_rect(
+        frame_area,
+        width.min(fit(frame_area.width)),
+        height.min(fit(frame_area.height)),
+    )
+}
+
+/// Largest scroll offset that still fills `visible` rows from `total`
+pub const fn max_scroll(total: usize, visible: usize) -> usize {
+    total.saturating_sub(visible)
+}
+
+/// Scroll offset keeping row `anchor` in view, scrolling only as far as needed
+pub fn scroll_to_show(anchor: usize, total: usize, visible: usize) -> usize {
+    if visible == 0 {
+        return 0;
+    }
+    anchor
+        .saturating_sub(visible - 1)
+        .min(max_scroll(total, visible))
+}
+
+/// Draw a scrollbar in the rightmost column of `track` when `total` rows overflow `visible`
+pub fn render_scrollbar(
+    frame: &mut Frame,
+    track: Rect,
+    total: usize,
+    visible: usize,
+    offset: usize,
+) {
+    if total <= visible {
+        return;
+    }
+    // One state position per scroll offset, so the thumb reaches the bottom at the last one
+    let mut state = ScrollbarState::new(max_scroll(total, visible) + 1)
+        .viewport_content_length(visible)
+        .position(offset);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .style(Style::default().fg(Color::DarkGray));
+    frame.render_stateful_widget(scrollbar, track, &mut state);
+}
+
 pub fn render_overlay_frame(frame: &mut Frame, area: Rect, title: &str, color: Color) -> Rect {
     frame.render_widget(Clear, area);
     let block = Block::default()
@@ -23,3 +79,35 @@ pub fn render_overlay_frame(frame: &mut Frame, area: Rect, title: &str, color: C
     frame.render_widget(block, area);
     inner
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_rect_stays_inside_frame() {
+        let frame = Rect::new(0, 0, 80, 24);
+        assert_eq!(overlay_rect(frame, 40, 10), Rect::new(20, 7, 40, 10));
+
+        // Oversized requests shrink to leave the margin
+        assert_eq!(overlay_rect(frame, 200, 100), Rect::new(2, 2, 76, 20));
+
+        // Tiny frames use all available space rather than nothing
+        let tiny = Rect::new(5, 5, 3, 2);
+        assert_eq!(overlay_rect(tiny, 40, 10), tiny);
+    }
+
+    #[test]
+    fn test_scroll_to_show() {
+        // Everything fits
+        assert_eq!(scroll_to_show(3, 5, 10), 0);
+        // Anchor within the first page
+        assert_eq!(scroll_to_show(4, 20, 5), 0);
+        // Anchor past the first page is kept on the last visible row
+        assert_eq!(scroll_to_show(7, 20, 5), 3);
+        // Never scrolls past the end
+        assert_eq!(scroll_to_show(30, 20, 5), 15);
+        // No visible rows
+        assert_eq!(scroll_to_show(7, 20, 0), 0);
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::{Days, Local, NaiveDate};
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{HighlightSpacing, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use ratatui_input_manager::{keymap, KeyMap};
+
+use super::util::{panel_block, truncate_with_ellipsis, SCROLL_PADDING};
+use crate::history::{format_focused, History, SessionRecord};
+use crate::interruptions::InterruptionLog;
+use crate::theme::Theme;
+
+/// Browsable log of finished sessions, one day at a time
+#[derive(Default)]
+pub struct HistoryPanel {
+    /// Days before today being shown, so the panel follows the date past midnight
+    days_back: u64,
+    /// Selected row, clamped to the day's sessions on render
+    selected: usize,
+    list_state: ListState,
+}
+
+impl HistoryPanel {
+    pub fn handle(&mut self, event: &Event) -> bool {
+        KeyMap::handle(self, event)
+    }
+
+    /// Day currently shown
+    pub fn day(&self) -> NaiveDate {
+        let today = Local::now().date_naive();
+        today
+            .checked_sub_days(Days::new(self.days_back))
+            .unwrap_or(today)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        focused: bool,
+        history: &History,
+        interruptions: &InterruptionLog,
+        estimates: Option<(u32, u32)>,
+        theme: &Theme,
+    ) {
+        let block = panel_block(" History ", focused, theme);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let day = self.day();
+        let sessions = history.sessions_on(day);
+        self.selected = self.selected.min(sessions.len().saturating_sub(1));
+
+        let chunks = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+        let mut header = vec![day_header(day, self.days_back, history.focused_on(day))];
+        header.extend(estimates.map(estimate_line));
+        frame.render_widget(
+            Paragraph::new(header).alignment(Alignment::Center),
+            chunks[0],
+        );
+
+        if sessions.is_empty() {
+            let placeholder = Paragraph::new("(no sessions)")
+                .style(Style::default().fg(Color::DarkGray))
+                .alignment(Alignment::Center);
+            frame.render_widget(placeholder, chunks[1]);
+            return;
+        }
+
+        let task_width = (chunks[1].width as usize).saturating_sub(ROW_PREFIX_WIDTH);
+        self.list_state.select(focused.then_some(self.selected));
+        let items: Vec<ListItem> = sessions
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                let selected = self.list_state.selected() == Some(i);
+                ListItem::new(session_row(record, selected, task_width, theme))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_symbol(Span::styled("> ", Style::default().fg(theme.accent)))
+            .highlight_spacing(HighlightSpacing::Always)
+            .scroll_padding(SCROLL_PADDING);
+        frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
+
+        // Pause reasons logged during the selected session
+        if let Some(record) = sessions.get(self.selected).filter(|_| focused) {
+            let reasons: Vec<&str> = if record.paused_at.is_empty() {
+                // Older lines don't keep the pause times, so take what was logged near the end
+                interruptions
+                    .between(record.end - record.duration, record.end)
+                    .map(|i| i.reason.as_str())
+                    .collect()
+            } else {
+                interruptions
+                    .at_times(&record.paused_at)
+                    .map(|i| i.reason.as_str())
+                    .collect()
+            };
+            if !reasons.is_empty() {
+                let text = format!("⏸ {}", reasons.join(" · "));
+                let width = usize::from(chunks[2].width);
+                frame.render_widget(
+                    Paragraph::new(truncate_with_ellipsis(&text, width))
+                        .style(Style::default().fg(Color::Yellow)),
+                    chunks[2],
+                );
+            }
+        }
+    }
+}
+
+/// Cursor, start time, session, duration and interruptions ahead of the task text
+const ROW_PREFIX_WIDTH: usize = 2 + 6 + 12 + 5 + 4;
+
+/// Date with arrows hinting at day navigation, and that day's focused time
+fn day_header(day: NaiveDate, days_back: u64, focused: std::time::Duration) -> Line<'static> {
+    let next = if days_back == 0 { "  " } else { " >" };
+    let label = if days_back == 0 {
+        format!("Today, {}", day.format("%a %d %b"))
+    } else {
+        day.format("%a %d %b %Y").to_string()
+    };
+    Line::from(vec![
+        Span::styled("< ", Style::default().fg(Color::DarkGray)),
+        Span::styled(label, Style::default().fg(Color::White)),
+        Span::styled(next, Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            format!("  {} focused", format_focused(focused)),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+/// How the tasks finished on the day measured up to their estimates, red once over
+fn estimate_line((actual, estimate): (u32, u32)) -> Line<'static> {
+    let color = if actual > estimate {
+        Color::Red
+    } else {
+        Color::DarkGray
+    };
+    Line::styled(
+        format!("Finished tasks: {actual}/{estimate} 🍅 estimated"),
+        Style::default().fg(color),
+    )
+}
+
+fn session_row(
+    record: &SessionRecord,
+    selected: bool,
+    task_width: usize,
+    theme: &Theme,
+) -> Line<'static> {
+    let start = record.end - record.duration;
+    let interruptions = if record.interruptions == 0 {
+        String::new()
+    } else {
+        format!("⏸{}", record.interruptions)
+    };
+    let task = record
+        .task
+        .as_deref()
+        .map(|t| truncate_with_ellipsis(t, task_width))
+        .unwrap_or_default();
+    let task_style = if selected {
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Gray)
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("{} ", start.format("%H:%M")),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            format!("{:<12}", record.session.label()),
+            Style::default().fg(theme.session_color(record.session)),
+        ),
+        Span::raw(format!("{:>4} ", format_focused(record.duration))),
+        Span::styled(
+            format!("{interruptions:<4}"),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(task, task_style),
+    ])
+}
+
+#[keymap(backend = "crossterm")]
+impl HistoryPanel {
+    /// Move focus down
+    #[keybind(pressed(key=KeyCode::Char('j')))]
+    #[keybind(pressed(key=KeyCode::Down))]
+    fn key_move_down(&mut self) {
+        self.selected += 1;
+    }
+
+    /// Move focus up
+    #[keybind(pressed(key=KeyCode::Char('k')))]
+    #[keybind(pressed(key=KeyCode::Up))]
+    fn key_move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Previous day
+    #[keybind(pressed(key=KeyCode::Char('h')))]
+    #[keybind(pressed(key=KeyCode::Left))]
+    fn key_prev_day(&mut self) {
+        self.days_back += 1;
+        self.selected = 0;
+    }
+
+    /// Next day
+    #[keybind(pressed(key=KeyCode::Char('l')))]
+    #[keybind(pressed(key=KeyCode::Right))]
+    fn key_next_day(&mut self) {
+        if self.days_back > 0 {
+            self.days_back -= 1;
+            self.selected = 0;
+        }
+    }
+
+    /// Jump to today
+    #[keybind(pressed(key=KeyCode::Home))]
+    fn key_today(&mut self) {
+        self.days_back = 0;
+        self.selected = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn press(panel: &mut HistoryPanel, code: KeyCode) -> bool {
+        panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    #[test]
+    fn test_day_navigation_stops_at_today() {
+        let mut panel = HistoryPanel::default();
+        let today = panel.day();
+
+        assert!(press(&mut panel, KeyCode::Char('l')));
+        assert_eq!(panel.day(), today);
+
+        press(&mut panel, KeyCode::Char('h'));
+        press(&mut panel, KeyCode::Left);
+        assert_eq!(panel.day(), today - Days::new(2));
+
+        press(&mut panel, KeyCode::Right);
+        assert_eq!(panel.day(), today - Days::new(1));
+
+        press(&mut panel, KeyCode::Home);
+        assert_eq!(panel.day(), today);
+    }
+}
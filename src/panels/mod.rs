@@ -1,8 +1,12 @@
+mod history;
+mod plugin;
 mod tasks;
 mod timer;
 mod util;
 
+pub use history::HistoryPanel;
+pub use plugin::PluginPanel;
 pub use tasks::TasksPanel;
-pub use timer::TimerPanel;
-pub use timer::TIMER_MIN_WIDTH;
+pub use timer::{TimerPanel, TimerStatus};
+pub use timer::{TIMER_MIN_HEIGHT, TIMER_MIN_WIDTH};
 pub use util::PanelId;
@@ -0,0 +1,122 @@
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::util::panel_block;
+use crate::config::PluginConfig;
+use crate::theme::Theme;
+
+/// Maximum number of output rows shown for a single plugin
+const PLUGIN_MAX_LINES: usize = 8;
+
+type PluginOutput = Result<Vec<String>, String>;
+
+/// Panel showing the output of a user-configured external command, refreshed on an interval
+pub struct PluginPanel {
+    config: PluginConfig,
+    output: PluginOutput,
+    last_run: Option<Instant>,
+    /// Receives the result of the command currently running in the background
+    pending: Option<Receiver<PluginOutput>>,
+}
+
+impl PluginPanel {
+    pub fn new(config: PluginConfig) -> Self {
+        Self {
+            config,
+            output: Ok(Vec::new()),
+            last_run: None,
+            pending: None,
+        }
+    }
+
+    /// Collect finished command output and start a new run when the interval has elapsed
+    pub fn tick(&mut self) {
+        if let Some(ref rx) = self.pending {
+            match rx.try_recv() {
+                Ok(output) => {
+                    self.output = output;
+                    self.pending = None;
+                }
+                Err(TryRecvError::Disconnected) => self.pending = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        let interval = Duration::from_secs(self.config.interval_secs);
+        let due = self.last_run.is_none_or(|last| last.elapsed() >= interval);
+        if due && self.pending.is_none() {
+            self.spawn();
+        }
+    }
+
+    fn spawn(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        let command = self.config.command.clone();
+        thread::spawn(move || {
+            // The receiver is dropped if the app exits first, which is fine to ignore
+            let _ = tx.send(run_command(&command));
+        });
+        self.pending = Some(rx);
+        self.last_run = Some(Instant::now());
+    }
+
+    /// Height needed to show the current output, including borders
+    pub fn desired_height(&self) -> u16 {
+        let lines = self.output.as_ref().map_or(1, Vec::len);
+        lines.clamp(1, PLUGIN_MAX_LINES) as u16 + 2
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let title = format!(" {} ", self.config.title);
+        let block = panel_block(&title, false, theme);
+
+        let lines: Vec<Line> = match self.output {
+            Ok(ref output) if output.is_empty() => vec![Line::styled(
+                if self.pending.is_some() {
+                    "Loading..."
+                } else {
+                    "(no output)"
+                },
+                Style::default().fg(Color::DarkGray),
+            )],
+            Ok(ref output) => output
+                .iter()
+                .take(PLUGIN_MAX_LINES)
+                .map(|line| Line::styled(line.as_str(), Style::default().fg(Color::Gray)))
+                .collect(),
+            Err(ref e) => vec![Line::styled(e.as_str(), Style::default().fg(Color::Red))],
+        };
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// Run a shell command and split its stdout into lines
+fn run_command(command: &str) -> PluginOutput {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run plugin: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(String::from)
+        .collect())
+}
@@ -1,20 +1,51 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
 use std::io;
-use std::path::PathBuf;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crossterm::event::{Event, KeyCode};
+use chrono::{DateTime, Days, Local, NaiveDate};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, HighlightSpacing, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 use ratatui_input_manager::{keymap, KeyMap};
-
-use super::util::panel_block;
-use crate::overlays::{SyncItem, SyncOverlay, TaskInputOverlay};
-use crate::task::{Task, TaskSection};
+use unicode_width::UnicodeWidthStr;
+
+use super::util::{panel_block, truncate_with_ellipsis, SCROLL_PADDING};
+use crate::backup::Backups;
+use crate::clipboard::{pasted_tasks, Clipboard};
+use crate::config::TasksConfig;
+use crate::fileio::{read_task_file, ParsedTasks};
+use crate::history::{format_focused, History, SessionRecord};
+use crate::overlays::{
+    render_scrollbar, BackupOverlay, ConfirmOverlay, DuplicatesOverlay, NoteOverlay, ProjectChoice,
+    ProjectOverlay, ResetDayOverlay, SyncItem, SyncOverlay, TagChoice, TagFilterOverlay,
+    TaskInputAction, TaskInputOverlay, UrlPickerOverlay, CONFIRM_WIDTH,
+};
+use crate::task::{find_ignore_case, Priority, SortMode, Task, TaskFilter, TaskSection};
 use crate::task_manager::TaskManager;
+use crate::theme::Theme;
+use crate::trash::Trash;
+
+/// Where each task of a group is, as section and index
+type TaskGroup = Vec<(TaskSection, usize)>;
+
+/// Rows of the details drawer, its top border included
+const DETAILS_HEIGHT: u16 = 10;
+
+/// Narrowest the panel can be, inside its border, to show the sections as columns
+const COLUMNS_MIN_WIDTH: u16 = 90;
 
 const SECTIONS: [(TaskSection, &str, &str, bool); 3] = [
     (TaskSection::Backlog, "Backlog", "[ ]", true),
@@ -22,7 +53,44 @@ const SECTIONS: [(TaskSection, &str, &str, bool); 3] = [
     (TaskSection::Completed, "Completed", "[x]", false),
 ];
 
-/// Current focus position within the tasks panel (section and index)
+/// Position of `section` in `SECTIONS`, for the per-section arrays
+const fn slot(section: TaskSection) -> usize {
+    match section {
+        TaskSection::Backlog => 0,
+        TaskSection::Current => 1,
+        TaskSection::Completed => 2,
+    }
+}
+
+/// A number below `n`, different each call, which is random enough to pick a task
+fn random_below(n: usize) -> usize {
+    let seed = RandomState::new().hash_one(Instant::now());
+    (seed % n as u64) as usize
+}
+
+/// Rows given to each section out of `height`: a folded section keeps only its title and
+/// border, and the rest share what is left equally, the first taking any spare rows
+fn section_heights(height: u16, collapsed: [bool; 3]) -> [u16; 3] {
+    let folded: [u16; 3] = std::array::from_fn(|i| 1 + u16::from(SECTIONS[i].3));
+    let expanded = collapsed.iter().filter(|&&c| !c).count() as u16;
+    let left = collapsed
+        .iter()
+        .zip(folded)
+        .filter(|&(&c, _)| c)
+        .fold(height, |left, (_, rows)| left.saturating_sub(rows));
+    let mut extra = left % expanded.max(1);
+    std::array::from_fn(|i| {
+        if collapsed[i] {
+            folded[i].min(height)
+        } else {
+            let rows = left / expanded + u16::from(extra > 0);
+            extra = extra.saturating_sub(1);
+            rows
+        }
+    })
+}
+
+/// Current focus position within the tasks panel (section, and row among the tasks shown)
 #[derive(Debug, Clone)]
 struct TaskFocus {
     section: TaskSection,
@@ -38,15 +106,86 @@ impl Default for TaskFocus {
     }
 }
 
+/// Structural edits on the focused task that `.` can repeat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TaskAction {
+    CycleSection,
+    ToggleCompletion,
+    MoveTo(TaskSection),
+    Delete,
+}
+
 /// Tasks panel displaying backlog, current, and completed task sections
 pub struct TasksPanel {
     focus: TaskFocus,
+    /// Where visual-select mode started in the focused section; the selection runs from here
+    /// to the focused task
+    select_anchor: Option<usize>,
+    /// Whether `m` was pressed and the next key picks the section to move to
+    move_pending: bool,
+    /// Whether `z` was pressed and the next key folds the focused section
+    fold_pending: bool,
+    /// Whether the drawer showing everything about the focused task is open
+    details_visible: bool,
+    /// Whether the sections were last drawn side by side, so `h` and `l` move between them
+    columns_shown: bool,
+    /// Sections folded down to their title, in `SECTIONS` order
+    collapsed: [bool; 3],
+    /// Order each section is listed in, in `SECTIONS` order; the tasks themselves stay in
+    /// their manual order
+    sort_modes: [SortMode; 3],
+    /// Hides tasks from all three lists without touching them
+    filter: TaskFilter,
+    tag_filter_overlay: Option<TagFilterOverlay>,
+    project_overlay: Option<ProjectOverlay>,
+    /// Text typed after `/`, while the search bar takes the keyboard; the filter's query
+    /// follows it live
+    search: Option<String>,
+    /// Number typed after `:`, while the bar takes the keyboard in place of the search
+    jump: Option<String>,
+    /// Digits typed so far for `g` to jump to, when line numbers are shown
+    count: Option<usize>,
     /// Visible task rows per section (updated during render)
     section_page_size: usize,
+    /// Scroll position of each section, in `SECTIONS` order
+    list_states: [ListState; 3],
     task_manager: TaskManager,
     task_input_overlay: Option<TaskInputOverlay>,
     sync_overlay: Option<SyncOverlay>,
+    note_overlay: Option<NoteOverlay>,
+    /// Asks before the focused task is deleted
+    confirm_overlay: Option<ConfirmOverlay>,
+    /// Asks before old completed tasks are archived
+    archive_overlay: Option<ConfirmOverlay>,
+    /// Previews resetting the day
+    reset_overlay: Option<ResetDayOverlay>,
+    /// Lists duplicate tasks, holding where each group's tasks are
+    duplicates_overlay: Option<(DuplicatesOverlay, Vec<TaskGroup>)>,
+    /// Asks before the group picked from the duplicates is merged, holding where its tasks are
+    merge_overlay: Option<(ConfirmOverlay, TaskGroup)>,
+    /// Picks which link to open when the focused task has several
+    url_picker: Option<UrlPickerOverlay>,
+    /// Picks a backup of the task file to restore
+    backup_overlay: Option<BackupOverlay>,
+    /// Asks whether to write the task file when another instance has it open
+    lock_overlay: Option<ConfirmOverlay>,
     pending_error: Option<String>,
+    /// Outcome to show briefly, e.g. how many tasks were archived
+    pending_message: Option<String>,
+    /// Text of tasks added through the input overlay since the last `take_added_tasks`
+    added_tasks: Vec<String>,
+    last_action: Option<TaskAction>,
+    config: TasksConfig,
+    clipboard: Clipboard,
+    /// When the file was last read in the background
+    last_auto_sync: Instant,
+    /// The task file being read in the background for the next auto-sync
+    auto_sync_read: Option<Receiver<Result<ParsedTasks, io::Error>>>,
+    /// Texts of the conflicts last asked about in the background, so the same ones aren't
+    /// asked about again until they change
+    raised_conflicts: Vec<String>,
+    /// Whether syncing may not create the default task file, as in the demo
+    file_disabled: bool,
 }
 
 impl Default for TasksPanel {
@@ -56,12 +195,22 @@ impl Default for TasksPanel {
 }
 
 impl TasksPanel {
-    pub fn from_file(path: Option<PathBuf>) -> (Self, Option<String>) {
+    /// Tasks from the file at `path`, locked through a lock file in `locks`
+    pub fn from_file(path: Option<PathBuf>, locks: Option<PathBuf>) -> (Self, Option<String>) {
         let Some(path) = path else {
-            return (Self::default(), None);
+            let mut task_manager = TaskManager::new();
+            task_manager.set_locks(locks);
+            return (Self::new(task_manager), None);
         };
-        match TaskManager::load(path) {
-            Ok(tm) => (Self::new(tm), None),
+        match TaskManager::load(path, locks) {
+            Ok(tm) => {
+                let mut panel = Self::new(tm);
+                if panel.task_manager.is_read_only() {
+                    let message = "Open in another pomo-tui. Edit here anyway?".to_string();
+                    panel.lock_overlay = Some(ConfirmOverlay::new(" Task File In Use ", message));
+                }
+                (panel, None)
+            }
             Err(e) => (Self::default(), Some(format!("Failed to load tasks: {e}"))),
         }
     }
@@ -69,23 +218,126 @@ impl TasksPanel {
     fn new(task_manager: TaskManager) -> Self {
         Self {
             focus: TaskFocus::default(),
+            select_anchor: None,
+            move_pending: false,
+            fold_pending: false,
+            details_visible: false,
+            columns_shown: false,
+            collapsed: [false; 3],
+            sort_modes: [SortMode::Manual; 3],
+            filter: TaskFilter::default(),
+            tag_filter_overlay: None,
+            project_overlay: None,
+            search: None,
+            jump: None,
+            count: None,
             section_page_size: 10,
+            list_states: Default::default(),
             task_manager,
             task_input_overlay: None,
             sync_overlay: None,
+            note_overlay: None,
+            confirm_overlay: None,
+            archive_overlay: None,
+            reset_overlay: None,
+            duplicates_overlay: None,
+            merge_overlay: None,
+            url_picker: None,
+            backup_overlay: None,
+            lock_overlay: None,
             pending_error: None,
+            pending_message: None,
+            added_tasks: Vec::new(),
+            last_action: None,
+            config: TasksConfig::default(),
+            clipboard: Clipboard::default(),
+            last_auto_sync: Instant::now(),
+            auto_sync_read: None,
+            raised_conflicts: Vec::new(),
+            file_disabled: false,
         }
     }
 
     /// Route the event to the active overlay if one is open, otherwise dispatch keybindings
     pub fn handle(&mut self, event: &Event) -> bool {
-        let consumed = if let Some(ref mut overlay) = self.task_input_overlay {
+        let digit = self.count_digit(event);
+        let consumed = if let Some(ref mut overlay) = self.lock_overlay {
+            overlay.handle(event)
+        } else if let Some(ref mut overlay) = self.task_input_overlay {
             overlay.handle(event)
         } else if let Some(ref mut overlay) = self.sync_overlay {
             overlay.handle(event)
+        } else if let Some(ref mut overlay) = self.note_overlay {
+            overlay.handle(event)
+        } else if let Some(ref mut overlay) = self.confirm_overlay {
+            overlay.handle(event)
+        } else if let Some(ref mut overlay) = self.archive_overlay {
+            overlay.handle(event)
+        } else if let Some((ref mut overlay, _)) = self.merge_overlay {
+            overlay.handle(event)
+        } else if let Some(ref mut overlay) = self.reset_overlay {
+            KeyMap::handle(overlay, event);
+            true
+        } else if let Some((ref mut overlay, _)) = self.duplicates_overlay {
+            KeyMap::handle(overlay, event);
+            true
+        } else if let Some(ref mut overlay) = self.url_picker {
+            KeyMap::handle(overlay, event);
+            true
+        } else if let Some(ref mut overlay) = self.backup_overlay {
+            KeyMap::handle(overlay, event);
+            true
+        } else if let Some(ref mut overlay) = self.tag_filter_overlay {
+            KeyMap::handle(overlay, event);
+            true
+        } else if let Some(ref mut overlay) = self.project_overlay {
+            KeyMap::handle(overlay, event);
+            true
+        } else if self.search.is_some() {
+            self.handle_search(event)
+        } else if self.jump.is_some() {
+            self.handle_jump(event)
+        } else if self.move_pending && pressed_key(event).is_some() {
+            // Any other key cancels the move
+            self.move_pending = false;
+            let target = match pressed_key(event) {
+                Some(KeyCode::Char('b')) => Some(TaskSection::Backlog),
+                Some(KeyCode::Char('c')) => Some(TaskSection::Current),
+                Some(KeyCode::Char('x')) => Some(TaskSection::Completed),
+                _ => None,
+            };
+            if let Some(target) = target {
+                self.apply_action(TaskAction::MoveTo(target));
+            }
+            true
+        } else if self.fold_pending && pressed_key(event).is_some() {
+            // Any other key cancels the fold
+            self.fold_pending = false;
+            if pressed_key(event) == Some(KeyCode::Char('a')) {
+                let slot = slot(self.focus.section);
+                self.collapsed[slot] = !self.collapsed[slot];
+                self.select_anchor = None;
+                self.focus.index = 0;
+            }
+            true
+        } else if self.select_anchor.is_some() && pressed_key(event) == Some(KeyCode::Esc) {
+            // Only taken while selecting, so Esc still quits otherwise
+            self.select_anchor = None;
+            true
+        } else if self.filter.query.is_some() && pressed_key(event) == Some(KeyCode::Esc) {
+            self.set_query(None);
+            true
+        } else if let Some(digit) = digit {
+            let count = self.count.unwrap_or_default();
+            self.count = Some(count.saturating_mul(10).saturating_add(digit));
+            true
         } else {
-            self.handle(event)
+            KeyMap::handle(self, event)
         };
+        // Any other key ends the count, once `g` has had the chance to use it
+        if digit.is_none() && pressed_key(event).is_some() {
+            self.count = None;
+        }
 
         if consumed {
             self.process_overlay();
@@ -102,14 +354,432 @@ impl TasksPanel {
         self.sync_overlay.as_ref()
     }
 
+    pub fn note_overlay(&self) -> Option<&NoteOverlay> {
+        self.note_overlay.as_ref()
+    }
+
+    pub fn confirm_overlay(&self) -> Option<&ConfirmOverlay> {
+        self.lock_overlay
+            .as_ref()
+            .or(self.confirm_overlay.as_ref())
+            .or(self.archive_overlay.as_ref())
+            .or_else(|| self.merge_overlay.as_ref().map(|(overlay, _)| overlay))
+    }
+
+    pub fn reset_overlay(&self) -> Option<&ResetDayOverlay> {
+        self.reset_overlay.as_ref()
+    }
+
+    pub fn duplicates_overlay(&self) -> Option<&DuplicatesOverlay> {
+        self.duplicates_overlay.as_ref().map(|(overlay, _)| overlay)
+    }
+
+    pub fn url_picker(&self) -> Option<&UrlPickerOverlay> {
+        self.url_picker.as_ref()
+    }
+
+    pub fn backup_overlay(&self) -> Option<&BackupOverlay> {
+        self.backup_overlay.as_ref()
+    }
+
+    pub fn tag_filter_overlay(&self) -> Option<&TagFilterOverlay> {
+        self.tag_filter_overlay.as_ref()
+    }
+
+    pub fn project_overlay(&self) -> Option<&ProjectOverlay> {
+        self.project_overlay.as_ref()
+    }
+
+    /// Whether keys are being typed into the search or jump bar
+    pub const fn is_searching(&self) -> bool {
+        self.search.is_some() || self.jump.is_some()
+    }
+
     pub fn take_error(&mut self) -> Option<String> {
         self.pending_error.take()
     }
 
+    pub fn take_message(&mut self) -> Option<String> {
+        self.pending_message.take().or_else(|| {
+            self.task_manager
+                .take_backup_error()
+                .map(|e| format!("Backup failed, saved anyway: {e}"))
+        })
+    }
+
+    pub fn take_added_tasks(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.added_tasks)
+    }
+
+    pub fn set_trash(&mut self, trash: Trash) {
+        self.task_manager.set_trash(trash);
+    }
+
+    pub fn unsynced_changes(&self) -> usize {
+        self.task_manager.unsynced_changes()
+    }
+
+    /// Write changes made in the app to the task file, as auto-save does
+    pub fn save(&mut self) -> Result<(), String> {
+        self.task_manager
+            .save()
+            .map_err(|e| format!("Failed to save tasks: {e}"))
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.task_manager.is_read_only()
+    }
+
+    pub fn set_backups(&mut self, backups: Backups) {
+        self.task_manager.set_backups(backups);
+    }
+
+    /// Keep syncing from creating the default task file, leaving the tasks in memory only
+    pub const fn disable_file(&mut self) {
+        self.file_disabled = true;
+    }
+
+    pub const fn set_config(&mut self, config: TasksConfig) {
+        self.task_manager
+            .set_completion_dates(config.completion_dates);
+        self.task_manager.set_creation_dates(config.creation_dates);
+        self.config = config;
+    }
+
+    /// With auto-save on, write any changes made since the last write to the task file,
+    /// turning auto-save off for the session if that fails so the error is not repeated
+    pub fn auto_save(&mut self) -> Result<(), String> {
+        if !self.config.auto_save
+            || !self.task_manager.is_dirty()
+            || self.task_manager.file_path().is_none()
+            || self.task_manager.is_read_only()
+        {
+            return Ok(());
+        }
+        self.task_manager.save().map_err(|e| {
+            self.config.auto_save = false;
+            format!("Auto-save failed, so it is off until restart: {e}")
+        })
+    }
+
+    /// Every `auto_sync_secs`, read the task file in the background, then apply changes made
+    /// to only the app or only the file and find any new conflicts, returning whether there
+    /// are some. The sync overlay opens for them only with the panel `focused`. The file is
+    /// only written, as for `s`, when something changed. Waits while an overlay or key mode
+    /// is open, and turns itself off for the session if the sync fails.
+    pub fn auto_sync(&mut self, focused: bool) -> Result<bool, String> {
+        let interval = Duration::from_secs(self.config.auto_sync_secs);
+        let Some(path) = self.task_manager.file_path() else {
+            return Ok(false);
+        };
+        if interval.is_zero() || self.task_manager.is_read_only() {
+            return Ok(false);
+        }
+
+        let mut read = None;
+        if let Some(ref rx) = self.auto_sync_read {
+            match rx.try_recv() {
+                Ok(result) => {
+                    read = Some(result);
+                    self.auto_sync_read = None;
+                }
+                Err(TryRecvError::Disconnected) => self.auto_sync_read = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        let Some(read) = read else {
+            if self.auto_sync_read.is_none() && self.last_auto_sync.elapsed() >= interval {
+                let (tx, rx) = mpsc::channel();
+                let path = path.to_path_buf();
+                thread::spawn(move || {
+                    // The receiver is dropped if the app exits first, which is fine to ignore
+                    let _ = tx.send(read_task_file(&path));
+                });
+                self.auto_sync_read = Some(rx);
+                self.last_auto_sync = Instant::now();
+            }
+            return Ok(false);
+        };
+        // Tried again next time, as the file may change before the overlay closes
+        if self.overlay_open() {
+            return Ok(false);
+        }
+
+        let conflicts = read
+            .map(|on_disk| self.task_manager.needs_sync(&on_disk))
+            .and_then(|needed| {
+                if needed {
+                    self.task_manager.auto_sync()
+                } else {
+                    Ok(Vec::new())
+                }
+            })
+            .map_err(|e| {
+                self.config.auto_sync_secs = 0;
+                format!("Auto-sync failed, so it is off until restart: {e}")
+            })?;
+        self.clamp_focus();
+        let texts: Vec<String> = conflicts.iter().map(|item| item.text.clone()).collect();
+        if conflicts.is_empty() || texts == self.raised_conflicts {
+            return Ok(false);
+        }
+        self.raised_conflicts = texts;
+        if focused {
+            self.sync_overlay = Some(SyncOverlay::new(conflicts));
+        }
+        Ok(true)
+    }
+
+    /// Whether any overlay or key mode is open, which may hold task indices a sync would
+    /// move
+    pub const fn overlay_open(&self) -> bool {
+        self.lock_overlay.is_some()
+            || self.task_input_overlay.is_some()
+            || self.sync_overlay.is_some()
+            || self.note_overlay.is_some()
+            || self.confirm_overlay.is_some()
+            || self.archive_overlay.is_some()
+            || self.reset_overlay.is_some()
+            || self.duplicates_overlay.is_some()
+            || self.merge_overlay.is_some()
+            || self.url_picker.is_some()
+            || self.backup_overlay.is_some()
+            || self.tag_filter_overlay.is_some()
+            || self.project_overlay.is_some()
+            || self.select_anchor.is_some()
+            || self.search.is_some()
+            || self.jump.is_some()
+            || self.count.is_some()
+            || self.move_pending
+            || self.fold_pending
+    }
+
+    /// Add a task to the project shown, if any
+    pub fn add_task(&mut self, text: impl Into<String>, section: TaskSection) {
+        self.task_manager
+            .add_task(text, section)
+            .project
+            .clone_from(&self.filter.project);
+    }
+
+    /// Type into the search bar: Enter keeps the query, Esc clears it
+    fn handle_search(&mut self, event: &Event) -> bool {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return false;
+        };
+        let Some(mut text) = self.search.take() else {
+            return false;
+        };
+        match code {
+            KeyCode::Enter => return true,
+            KeyCode::Esc => {
+                self.set_query(None);
+                return true;
+            }
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            // Ctrl and Alt chords are shortcuts, not text
+            KeyCode::Char(c)
+                if (*modifiers - KeyModifiers::SHIFT).is_empty() && !c.is_control() =>
+            {
+                text.push(*c);
+            }
+            _ => {}
+        }
+        self.set_query(Some(text.clone()).filter(|text| !text.is_empty()));
+        self.search = Some(text);
+        true
+    }
+
+    /// Type the number to jump to: Enter jumps, Esc cancels
+    fn handle_jump(&mut self, event: &Event) -> bool {
+        let Some(code) = pressed_key(event) else {
+            return false;
+        };
+        let Some(mut text) = self.jump.take() else {
+            return false;
+        };
+        match code {
+            KeyCode::Enter => {
+                if let Ok(row) = text.parse() {
+                    self.jump_to_row(row);
+                }
+                return true;
+            }
+            KeyCode::Esc => return true,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() => text.push(c),
+            _ => {}
+        }
+        self.jump = Some(text);
+        true
+    }
+
+    /// The digit a key press adds to the count for `g`, if line numbers are shown. A count
+    /// can't start with 0, nor with the 1 to 3 that focus panels, so tasks numbered from
+    /// those are reached with `:` instead
+    fn count_digit(&self, event: &Event) -> Option<usize> {
+        if !self.config.line_numbers {
+            return None;
+        }
+        let Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return None;
+        };
+        let digit = c.to_digit(10)? as usize;
+        (modifiers.is_empty() && (digit > 3 || self.count.is_some())).then_some(digit)
+    }
+
+    /// Focus the task numbered `row` in the focused section, counting from 1, or the last
+    /// one if there are fewer
+    fn jump_to_row(&mut self, row: usize) {
+        let len = self.row_count(self.focus.section);
+        if len > 0 {
+            self.focus.index = row.clamp(1, len) - 1;
+        }
+    }
+
+    /// Show only tasks containing `query`, focusing the first one shown
+    fn set_query(&mut self, query: Option<String>) {
+        self.filter.query = query;
+        self.select_anchor = None;
+        self.focus.index = 0;
+    }
+
+    const fn sort_mode(&self, section: TaskSection) -> SortMode {
+        self.sort_modes[slot(section)]
+    }
+
+    /// List the focused section in `mode`, keeping the focused task focused
+    fn set_sort_mode(&mut self, mode: SortMode) {
+        let focused = self.focused_index();
+        self.sort_modes[slot(self.focus.section)] = mode;
+        self.select_anchor = None;
+        if let Some(row) = focused.and_then(|index| {
+            self.rows(self.focus.section)
+                .iter()
+                .position(|&i| i == index)
+        }) {
+            self.focus.index = row;
+        }
+    }
+
     fn process_overlay(&mut self) {
         if let Some(overlay) = self.task_input_overlay.take_if(|o| o.is_done()) {
-            if let Some((text, section)) = overlay.result() {
-                self.task_manager.add_task(text, section);
+            match overlay.result() {
+                Some((text, TaskInputAction::Add(section))) => {
+                    self.add_task(&text, section);
+                    self.added_tasks.push(text);
+                }
+                Some((text, TaskInputAction::Edit { section, index })) => {
+                    self.task_manager.edit_task(section, index, &text);
+                }
+                None => {}
+            }
+        }
+
+        if let Some(overlay) = self.note_overlay.take_if(|o| o.is_done()) {
+            if let Some((section, index, note)) = overlay.result() {
+                if let Err(e) = self.task_manager.set_note(section, index, note) {
+                    self.pending_error = Some(format!("Failed to save note: {e}"));
+                }
+            }
+        }
+
+        if let Some(overlay) = self.confirm_overlay.take_if(|o| o.is_done()) {
+            if overlay.result() {
+                self.apply_action(TaskAction::Delete);
+            }
+        }
+
+        if let Some(overlay) = self.lock_overlay.take_if(|o| o.is_done()) {
+            if overlay.result() {
+                self.task_manager.ignore_lock();
+            } else {
+                self.pending_message = Some("Task file is read-only here".to_string());
+            }
+        }
+
+        if let Some(overlay) = self.archive_overlay.take_if(|o| o.is_done()) {
+            if overlay.result() {
+                // Found again, as the tasks may have changed while asking
+                let indices = self.task_manager.archivable(self.config.archive_after_days);
+                self.archive(&indices);
+            }
+        }
+
+        if let Some(overlay) = self.reset_overlay.take_if(|o| o.is_done()) {
+            if let Some(archive) = overlay.result() {
+                // Found again, as the tasks may have changed while asking
+                let indices = if archive {
+                    self.reset_archivable()
+                } else {
+                    Vec::new()
+                };
+                self.reset_day(&indices);
+            }
+        }
+
+        if let Some((overlay, groups)) = self.duplicates_overlay.take_if(|(o, _)| o.is_done()) {
+            if let Some(group) = overlay.result().and_then(|i| groups.get(i)) {
+                let others = group.len() - 1;
+                let message = format!("Merge {others} tasks into the first? They go to the trash.");
+                let overlay = ConfirmOverlay::new(" Merge Tasks ", message);
+                self.merge_overlay = Some((overlay, group.clone()));
+            }
+        }
+
+        if let Some((overlay, group)) = self.merge_overlay.take_if(|(o, _)| o.is_done()) {
+            if overlay.result() {
+                self.merge_tasks(&group);
+            }
+        }
+
+        if let Some(overlay) = self.url_picker.take_if(|o| o.is_done()) {
+            if let Some(url) = overlay.result() {
+                self.open_url(url);
+            }
+        }
+
+        if let Some(overlay) = self.backup_overlay.take_if(|o| o.is_done()) {
+            if let Some(backup) = overlay.result() {
+                self.restore_backup(backup);
+            }
+        }
+
+        if let Some(overlay) = self.tag_filter_overlay.take_if(|o| o.is_done()) {
+            if let Some(choice) = overlay.result() {
+                self.filter.tag = match choice {
+                    TagChoice::All => None,
+                    TagChoice::Tag(tag) => Some(tag),
+                };
+                self.select_anchor = None;
+                self.focus.index = 0;
+            }
+        }
+
+        if let Some(overlay) = self.project_overlay.take_if(|o| o.is_done()) {
+            if let Some(choice) = overlay.result() {
+                self.filter.project = match choice {
+                    ProjectChoice::All => None,
+                    ProjectChoice::Project(project) => Some(project),
+                };
+                self.select_anchor = None;
+                self.focus.index = 0;
             }
         }
 
@@ -122,54 +792,227 @@ impl TasksPanel {
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
-        let block = panel_block(" Tasks ", focused);
+    /// Panel title, marked `*` like an editor's while changes aren't in the task file, and
+    /// noting any filter and whether the file is read-only
+    fn title(&self) -> String {
+        let modified = if self.unsynced_changes() > 0 { "*" } else { "" };
+        let project = self
+            .filter
+            .project
+            .as_ref()
+            .map(|project| format!("· {project} "));
+        let tag = self.filter.tag.as_ref().map(|tag| format!("#{tag} "));
+        let triage = if self.filter.stale_before.is_some() {
+            "· stale "
+        } else {
+            ""
+        };
+        let read_only = if self.task_manager.is_read_only() {
+            "· read-only "
+        } else {
+            ""
+        };
+        format!(
+            " Tasks{modified} {}{}{triage}{read_only}",
+            project.unwrap_or_default(),
+            tag.unwrap_or_default()
+        )
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        focused: bool,
+        history: &History,
+        theme: &Theme,
+    ) {
+        let title = self.title();
+        let block = panel_block(&title, focused, theme);
 
-        let inner = block.inner(area);
+        let mut inner = block.inner(area);
         frame.render_widget(block, area);
 
-        // Split into three equal sections manually to avoid rounding issues
-        let h = inner.height;
-        let third = h / 3;
-        let remainder = h % 3;
-        // Distribute remainder: first section gets +1 if remainder >= 1, second if >= 2
-        let h0 = third + u16::from(remainder >= 1);
-        let h1 = third + u16::from(remainder >= 2);
-        let h2 = h - h0 - h1;
-        let chunks = Layout::vertical([
-            Constraint::Length(h0),
-            Constraint::Length(h1),
-            Constraint::Length(h2),
-        ])
-        .split(inner);
+        if self.search.is_some() || self.jump.is_some() || self.filter.query.is_some() {
+            let [lists, search_bar] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+            self.render_search_bar(frame, search_bar, theme);
+            inner = lists;
+        }
 
-        // Store page size for page up/down
-        // Section inner height = chunk height - border (1) - ellipsis row (1)
-        self.section_page_size = (third as usize).saturating_sub(3).max(1);
+        if self.details_visible {
+            // Leave the sections at least half the panel
+            let height = DETAILS_HEIGHT.min(inner.height / 2);
+            let [lists, details] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(height)]).areas(inner);
+            self.render_details(frame, details, history);
+            inner = lists;
+        }
 
-        for (i, ((section, title, checkbox, bottom_border), tasks)) in SECTIONS
-            .iter()
-            .zip([
-                self.task_manager.backlog(),
-                self.task_manager.current(),
-                self.task_manager.completed(),
-            ])
-            .enumerate()
-        {
+        let chunks = self.split_sections(inner);
+
+        let today = Local::now().date_naive();
+        let stale_before = self.stale_cutoff(today);
+        for (i, (section, title, checkbox, divider)) in SECTIONS.iter().enumerate() {
+            let all = self.task_manager.section(*section);
+            let tasks: Vec<&Task> = self
+                .rows(*section)
+                .into_iter()
+                .filter_map(|index| all.get(index))
+                .collect();
             let section_focused = focused && self.focus.section == *section;
-            let cursor = if section_focused {
-                Some(self.focus.index)
+            let selection = self.selection().filter(|_| section_focused);
+            let title = format!("{title} ({})", self.count_badge(*section));
+            let title = self.sort_modes[i]
+                .label()
+                .map_or_else(|| title.clone(), |label| format!("{title} ({label})"));
+            // How finished tasks measured up to what they were expected to take
+            let estimates = (*section == TaskSection::Completed && self.config.show_pomodoros)
+                .then(|| self.task_manager.estimate_totals(*section))
+                .flatten();
+            let title = match estimates {
+                Some((actual, estimate)) => format!("{title} · {actual}/{estimate} 🍅 estimated"),
+                None => title,
+            };
+            let title = if section_focused && self.fold_pending {
+                format!("{title} · [a] fold or unfold")
+            } else if section_focused && self.move_pending {
+                format!("{title} · move to [b]acklog [c]urrent [x]completed")
+            } else if let Some(count) = self.count.filter(|_| section_focused) {
+                format!("{title} · go to {count}")
             } else {
-                None
+                selection.as_ref().map_or_else(
+                    || title.clone(),
+                    |range| format!("{title} · {} selected", range.clone().count()),
+                )
+            };
+            let state = &mut self.list_states[i];
+            state.select(section_focused.then_some(self.focus.index));
+            let borders = match (*divider, self.columns_shown) {
+                (false, _) => Borders::NONE,
+                (true, false) => Borders::BOTTOM,
+                (true, true) => Borders::RIGHT,
             };
             let inner = Self::render_section_frame(
                 frame,
                 chunks[i],
-                title,
+                &title,
                 section_focused,
-                *bottom_border,
+                borders,
+                theme,
+            );
+            if self.collapsed[i] {
+                continue;
+            }
+            Self::render_task_list(
+                frame,
+                inner,
+                &tasks,
+                checkbox,
+                state,
+                selection.as_ref(),
+                self.filter.query.as_deref(),
+                // Finished tasks can't be late
+                (*section != TaskSection::Completed).then_some(today),
+                stale_before.filter(|_| *section == TaskSection::Backlog),
+                self.config.show_pomodoros,
+                self.config.line_numbers,
+                theme,
             );
-            Self::render_task_list(frame, inner, tasks, checkbox, cursor);
+        }
+    }
+
+    /// Day before which Backlog tasks count as stale by `today`, unless staleness is off
+    fn stale_cutoff(&self, today: NaiveDate) -> Option<NaiveDate> {
+        (self.config.stale_after_days > 0)
+            .then(|| today.checked_sub_days(Days::new(self.config.stale_after_days.into())))
+            .flatten()
+    }
+
+    /// Number of tasks shown in `section`, and for Current with a WIP limit how many it holds
+    /// out of the limit, hidden ones included
+    fn count_badge(&self, section: TaskSection) -> String {
+        match self.config.wip_limit {
+            Some(limit) if section == TaskSection::Current => {
+                format!("{}/{limit}", self.task_manager.section_len(section))
+            }
+            _ => self
+                .task_manager
+                .filtered(section, &self.filter)
+                .len()
+                .to_string(),
+        }
+    }
+
+    /// Areas of the sections, side by side if set to and there is room or else stacked
+    fn split_sections(&mut self, area: Rect) -> Rc<[Rect]> {
+        self.columns_shown = self.config.columns && area.width >= COLUMNS_MIN_WIDTH;
+        if self.columns_shown {
+            self.section_page_size = (area.height as usize).saturating_sub(2).max(1);
+            return Layout::horizontal([Constraint::Fill(1); 3]).split(area);
+        }
+
+        // Split the sections manually to avoid rounding issues
+        let heights = section_heights(area.height, self.collapsed);
+
+        // Store page size for page up/down
+        // Section inner height = chunk height - border (1)
+        self.section_page_size = (heights[slot(self.focus.section)] as usize)
+            .saturating_sub(2)
+            .max(1);
+        Layout::vertical(heights.map(Constraint::Length)).split(area)
+    }
+
+    /// Drawer below the sections with the focused task's full text, dates, note and sessions
+    fn render_details(&self, frame: &mut Frame, area: Rect, history: &History) {
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(" Details ")
+            .title_style(Style::default().fg(Color::DarkGray))
+            .title_alignment(Alignment::Right);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let task = self
+            .focused_index()
+            .and_then(|index| self.task_manager.section(self.focus.section).get(index));
+        let lines = task.map_or_else(
+            || {
+                vec![Line::styled(
+                    "No task focused",
+                    Style::default().fg(Color::DarkGray),
+                )]
+            },
+            |task| detail_lines(task, &history.sessions_for(&task.text)),
+        );
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    }
+
+    fn render_search_bar(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        // The jump bar stands in for the search while a number is typed
+        let (marker, text) = self.jump.as_deref().map_or_else(
+            || {
+                let query = self.search.as_deref().or(self.filter.query.as_deref());
+                (" /", query.unwrap_or_default())
+            },
+            |number| (" :", number),
+        );
+        let style = if self.is_searching() {
+            Style::default().fg(theme.accent)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let line = Line::from(vec![
+            Span::styled(marker, style),
+            Span::styled(text.to_string(), Style::default().fg(Color::White)),
+        ]);
+        frame.render_widget(Paragraph::new(line), area);
+        if self.is_searching() {
+            let cursor_x = area.x + 2 + text.width() as u16;
+            if cursor_x < area.right() {
+                frame.set_cursor_position((cursor_x, area.y));
+            }
         }
     }
 
@@ -177,14 +1020,269 @@ impl TasksPanel {
         self.task_manager.active_task()
     }
 
+    /// Pomodoros recorded and estimated for the tasks completed on `day` that had an
+    /// estimate, or None if none did
+    pub fn estimate_totals_on(&self, day: NaiveDate) -> Option<(u32, u32)> {
+        self.task_manager.estimate_totals_on(day)
+    }
+
+    /// Texts of the Current tasks, the active one first
+    pub fn current_tasks(&self) -> Vec<String> {
+        self.task_manager
+            .section(TaskSection::Current)
+            .iter()
+            .map(|task| task.text.clone())
+            .collect()
+    }
+
+    /// Make the Current task at `index` the active one
+    pub fn activate_task(&mut self, index: usize) {
+        self.task_manager.activate_task(index);
+    }
+
+    pub fn file_path(&self) -> Option<&Path> {
+        self.task_manager.file_path()
+    }
+
+    /// Whether there are in-app edits that a sync would write to the file
+    pub const fn has_unsynced_changes(&self) -> bool {
+        self.task_manager.is_dirty()
+    }
+
+    pub const fn last_sync(&self) -> Option<DateTime<Local>> {
+        self.task_manager.last_sync()
+    }
+
     fn apply_sync(&mut self, items: &[SyncItem]) -> Result<(), io::Error> {
         self.task_manager.apply_sync(items)?;
         self.clamp_focus();
         Ok(())
     }
 
-    pub fn complete_current_task(&mut self) {
+    /// Complete the active task, and with `auto_promote` refill an emptied Current from the
+    /// top of Backlog, returning the text of the task promoted
+    pub fn complete_current_task(&mut self) -> Option<String> {
         self.task_manager.complete_current_task();
+        if !self.config.auto_promote || self.task_manager.section_len(TaskSection::Current) > 0 {
+            return None;
+        }
+        self.task_manager.promote_task(0);
+        self.clamp_focus();
+        self.task_manager
+            .active_task()
+            .map(|task| task.text.clone())
+    }
+
+    pub fn record_pomodoro(&mut self) -> Result<(), String> {
+        self.task_manager
+            .record_pomodoro()
+            .map_err(|e| format!("Failed to save pomodoros: {e}"))
+    }
+
+    /// Tasks marked in visual-select mode, within the focused section
+    fn selection(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.select_anchor?;
+        let index = self.focus.index;
+        Some(anchor.min(index)..=anchor.max(index))
+    }
+
+    /// Indices into `section` of the tasks the filter shows, in row order
+    fn rows(&self, section: TaskSection) -> Vec<usize> {
+        // A folded section shows no rows, so nothing in it can be focused
+        if self.collapsed[slot(section)] {
+            return Vec::new();
+        }
+        self.task_manager
+            .sorted(section, &self.filter, self.sort_mode(section))
+    }
+
+    fn row_count(&self, section: TaskSection) -> usize {
+        if self.filter.is_active() || self.collapsed[slot(section)] {
+            self.rows(section).len()
+        } else {
+            self.task_manager.section_len(section)
+        }
+    }
+
+    /// Index into its section of the focused task
+    fn focused_index(&self) -> Option<usize> {
+        self.rows(self.focus.section).get(self.focus.index).copied()
+    }
+
+    /// Apply a structural edit to the selected tasks, or else the focused task, and remember
+    /// it for `.`
+    fn apply_action(&mut self, action: TaskAction) {
+        let section = self.focus.section;
+        if let Some(range) = self.selection() {
+            self.select_anchor = None;
+            let rows = self.rows(section);
+            let first = *range.start();
+            let indices: Vec<usize> = range.filter_map(|row| rows.get(row).copied()).collect();
+            match action {
+                TaskAction::CycleSection => {
+                    self.task_manager.cycle_tasks_section(section, &indices);
+                }
+                TaskAction::ToggleCompletion => {
+                    self.task_manager.toggle_completions(section, &indices);
+                }
+                TaskAction::MoveTo(target) => {
+                    self.task_manager.move_tasks(section, &indices, target);
+                }
+                TaskAction::Delete => {
+                    self.task_manager.delete_tasks(section, &indices);
+                    if let Some(e) = self.task_manager.take_trash_error() {
+                        self.pending_error = Some(format!("Failed to keep deleted tasks: {e}"));
+                    }
+                }
+            }
+            // Land where the first selected task was
+            self.focus.index = first;
+            self.clamp_focus();
+            self.last_action = Some(action);
+            return;
+        }
+        let Some(index) = self.focused_index() else {
+            return;
+        };
+        match action {
+            TaskAction::CycleSection => self.task_manager.cycle_task_section(section, index),
+            TaskAction::ToggleCompletion => self.task_manager.toggle_completion(section, index),
+            TaskAction::MoveTo(target) => self.task_manager.move_task(section, index, target),
+            TaskAction::Delete => {
+                self.task_manager.delete_task(section, index);
+                if let Some(e) = self.task_manager.take_trash_error() {
+                    self.pending_error = Some(format!("Failed to keep deleted task: {e}"));
+                }
+            }
+        }
+        self.clamp_focus();
+        self.last_action = Some(action);
+    }
+
+    /// Apply an edit, first asking to confirm a delete if `tasks.confirm_delete` is set
+    fn request_action(&mut self, action: TaskAction) {
+        let focused = self
+            .focused_index()
+            .and_then(|index| self.task_manager.section(self.focus.section).get(index));
+        let selected = self.selection().map_or(1, Iterator::count);
+        match focused {
+            Some(_)
+                if action == TaskAction::Delete && self.config.confirm_delete && selected > 1 =>
+            {
+                let message = format!("Delete {selected} tasks?");
+                self.confirm_overlay = Some(ConfirmOverlay::new(" Delete Tasks ", message));
+            }
+            Some(task) if action == TaskAction::Delete && self.config.confirm_delete => {
+                // Room for the quotes and question around the text
+                let width = usize::from(CONFIRM_WIDTH) - 16;
+                let message = format!("Delete '{}'?", truncate_with_ellipsis(&task.text, width));
+                self.confirm_overlay = Some(ConfirmOverlay::new(" Delete Task ", message));
+            }
+            _ => self.apply_action(action),
+        }
+    }
+
+    fn archive(&mut self, indices: &[usize]) {
+        let Some(path) = self.task_manager.archive_path() else {
+            return;
+        };
+        match self.task_manager.archive_tasks(indices) {
+            Ok(()) => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                self.pending_message = Some(format!("Archived {} tasks to {name}", indices.len()));
+                self.select_anchor = None;
+                self.clamp_focus();
+            }
+            Err(e) => self.pending_error = Some(format!("Archive failed: {e}")),
+        }
+    }
+
+    /// Completed tasks resetting the day can archive, which needs a file to archive to
+    fn reset_archivable(&self) -> Vec<usize> {
+        if self.task_manager.archive_path().is_some() {
+            self.task_manager.archivable(self.config.archive_after_days)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Archive the completed tasks at `archive` and move Current back to Backlog
+    fn reset_day(&mut self, archive: &[usize]) {
+        let moved = self.task_manager.section_len(TaskSection::Current);
+        let result = self.task_manager.reset_day(archive);
+        self.select_anchor = None;
+        self.clamp_focus();
+        if let Err(e) = result {
+            self.pending_error = Some(format!("Archive failed: {e}"));
+            return;
+        }
+        self.pending_message = Some(if archive.is_empty() {
+            format!("Moved {moved} tasks back to Backlog")
+        } else {
+            format!(
+                "Moved {moved} tasks back to Backlog and archived {}",
+                archive.len()
+            )
+        });
+    }
+
+    /// Merge the duplicate tasks of `group`, then show the next group, if any
+    fn merge_tasks(&mut self, group: &[(TaskSection, usize)]) {
+        self.task_manager.merge_tasks(group);
+        if let Some(e) = self.task_manager.take_trash_error() {
+            self.pending_error = Some(format!("Failed to keep merged tasks: {e}"));
+        }
+        self.select_anchor = None;
+        self.clamp_focus();
+        self.show_duplicates();
+        if self.duplicates_overlay.is_none() {
+            self.pending_message = Some("No more duplicate tasks".to_string());
+        }
+    }
+
+    fn restore_backup(&mut self, backup: &Path) {
+        if let Err(e) = self.task_manager.restore_backup(backup) {
+            self.pending_error = Some(format!("Failed to restore backup: {e}"));
+            return;
+        }
+        self.select_anchor = None;
+        self.clamp_focus();
+        let name = backup
+            .file_stem()
+            .map(|stem| stem.to_string_lossy())
+            .unwrap_or_default();
+        self.pending_message = Some(format!("Restored tasks from {name}"));
+    }
+
+    fn open_url(&mut self, url: &str) {
+        if let Err(e) = open_url(url) {
+            self.pending_error = Some(format!("Failed to open {url}: {e}"));
+        }
+    }
+
+    /// Open the duplicates overlay if any tasks look alike
+    fn show_duplicates(&mut self) {
+        let groups = self.task_manager.duplicates();
+        if groups.is_empty() {
+            return;
+        }
+        let rows = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|&(section, index)| {
+                        let name = SECTIONS
+                            .iter()
+                            .find(|(s, ..)| *s == section)
+                            .map_or("", |(_, name, ..)| name);
+                        let task = &self.task_manager.section(section)[index];
+                        format!("{name}: {}{}", task.text, task_suffix(task))
+                    })
+                    .collect()
+            })
+            .collect();
+        self.duplicates_overlay = Some((DuplicatesOverlay::new(rows), groups));
     }
 
     // -- Focus/navigation methods --
@@ -192,10 +1290,14 @@ impl TasksPanel {
     /// Prepare a `SyncOverlay` by computing sync items from the task manager
     fn sync_tasks(&mut self) -> Result<SyncOverlay, String> {
         if !self.task_manager.has_file_path() {
+            if self.file_disabled {
+                return Err("Sync is off without a task file".to_string());
+            }
             if let Err(e) = self.task_manager.create_default_file() {
                 return Err(format!("Failed to create default task file: {e}"));
             }
         }
+        self.task_manager.resolve_due_dates();
         self.task_manager
             .compute_sync_items()
             .map(SyncOverlay::new)
@@ -203,14 +1305,14 @@ impl TasksPanel {
     }
 
     fn clamp_focus(&mut self) {
-        let len = self.task_manager.section_len(self.focus.section);
+        let len = self.row_count(self.focus.section);
         if self.focus.index >= len {
             self.focus.index = len.saturating_sub(1);
         }
     }
 
     fn move_down(&mut self) {
-        let len = self.task_manager.section_len(self.focus.section);
+        let len = self.row_count(self.focus.section);
         if len > 0 && self.focus.index + 1 < len {
             self.focus.index += 1;
         }
@@ -222,25 +1324,47 @@ impl TasksPanel {
         }
     }
 
+    /// Whether the focused section is in manual order, so moving a task by hand shows;
+    /// explains why not otherwise
+    fn check_manual_order(&mut self) -> bool {
+        let manual = self.sort_mode(self.focus.section) == SortMode::Manual;
+        if !manual {
+            self.pending_message = Some("Switch to manual order with O to reorder".to_string());
+        }
+        manual
+    }
+
     fn reorder_down(&mut self) {
+        if !self.check_manual_order() {
+            return;
+        }
+        let Some(index) = self.focused_index() else {
+            return;
+        };
         self.task_manager
-            .reorder_down(self.focus.section, self.focus.index);
-        let len = self.task_manager.section_len(self.focus.section);
+            .reorder_down(self.focus.section, index, &self.filter);
+        let len = self.row_count(self.focus.section);
         if self.focus.index + 1 < len {
             self.focus.index += 1;
         }
     }
 
     fn reorder_up(&mut self) {
+        if !self.check_manual_order() {
+            return;
+        }
+        let Some(index) = self.focused_index() else {
+            return;
+        };
         self.task_manager
-            .reorder_up(self.focus.section, self.focus.index);
+            .reorder_up(self.focus.section, index, &self.filter);
         if self.focus.index > 0 {
             self.focus.index -= 1;
         }
     }
 
     fn page_down(&mut self) {
-        let len = self.task_manager.section_len(self.focus.section);
+        let len = self.row_count(self.focus.section);
         if len > 0 {
             self.focus.index = (self.focus.index + self.section_page_size).min(len - 1);
         }
@@ -251,6 +1375,7 @@ impl TasksPanel {
     }
 
     fn next_section(&mut self) {
+        self.select_anchor = None;
         self.focus.section = match self.focus.section {
             TaskSection::Backlog => TaskSection::Current,
             TaskSection::Current => TaskSection::Completed,
@@ -259,7 +1384,39 @@ impl TasksPanel {
         self.clamp_focus();
     }
 
+    /// Focus the next shown task, or the previous one going back, crossing into the other
+    /// sections and wrapping around
+    fn jump_to_match(&mut self, forward: bool) {
+        if self.filter.query.is_none() {
+            return;
+        }
+        let rows: Vec<(TaskSection, usize)> = SECTIONS
+            .iter()
+            .flat_map(|&(section, ..)| {
+                (0..self.row_count(section)).map(move |index| (section, index))
+            })
+            .collect();
+        if rows.is_empty() {
+            return;
+        }
+        let at = rows.iter().position(|&(section, index)| {
+            section == self.focus.section && index == self.focus.index
+        });
+        let next = match (at, forward) {
+            (Some(i), true) => (i + 1) % rows.len(),
+            (Some(i), false) => (i + rows.len() - 1) % rows.len(),
+            (None, true) => 0,
+            (None, false) => rows.len() - 1,
+        };
+        let (section, index) = rows[next];
+        if section != self.focus.section {
+            self.select_anchor = None;
+        }
+        self.focus = TaskFocus { section, index };
+    }
+
     fn prev_section(&mut self) {
+        self.select_anchor = None;
         self.focus.section = match self.focus.section {
             TaskSection::Backlog => TaskSection::Completed,
             TaskSection::Current => TaskSection::Backlog,
@@ -275,20 +1432,15 @@ impl TasksPanel {
         area: Rect,
         title: &str,
         focused: bool,
-        show_bottom_border: bool,
+        borders: Borders,
+        theme: &Theme,
     ) -> Rect {
         let title_style = if focused {
-            Style::default().fg(Color::Cyan)
+            Style::default().fg(theme.accent)
         } else {
             Style::default().fg(Color::DarkGray)
         };
 
-        let borders = if show_bottom_border {
-            Borders::BOTTOM
-        } else {
-            Borders::NONE
-        };
-
         let block = Block::default()
             .borders(borders)
             .border_style(Style::default().fg(Color::DarkGray))
@@ -301,12 +1453,20 @@ impl TasksPanel {
         inner
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn render_task_list(
         frame: &mut Frame,
         area: Rect,
-        tasks: &[crate::task::Task],
+        tasks: &[&Task],
         checkbox: &str,
-        focused_index: Option<usize>,
+        state: &mut ListState,
+        selection: Option<&RangeInclusive<usize>>,
+        query: Option<&str>,
+        overdue_from: Option<NaiveDate>,
+        stale_before: Option<NaiveDate>,
+        show_pomodoros: bool,
+        line_numbers: bool,
+        theme: &Theme,
     ) {
         if tasks.is_empty() {
             let shrunk = Rect {
@@ -323,18 +1483,17 @@ impl TasksPanel {
             return;
         }
 
-        let total_height = area.height as usize;
-        if total_height == 0 {
+        if area.height == 0 {
             return;
         }
 
-        // Reserve last row for ellipsis indicator
-        let visible_height = total_height.saturating_sub(1);
-
-        let scroll_offset = calculate_scroll_offset(tasks.len(), visible_height, focused_index);
-        let has_more_below = scroll_offset + visible_height < tasks.len();
-
-        let prefix_width = 6; // "> [x] " or "  [x] "
+        // Wide enough for the largest number, right-aligned
+        let number_width = if line_numbers {
+            tasks.len().to_string().len()
+        } else {
+            0
+        };
+        let prefix_width = 6 + number_width + usize::from(line_numbers); // "> [x] " or "  [x] "
         let trailing_space = 10;
         let max_text_width = (area.width as usize)
             .saturating_sub(prefix_width)
@@ -342,49 +1501,76 @@ impl TasksPanel {
 
         let prefix = format!("{checkbox} ");
 
-        let mut items: Vec<ListItem> = tasks
+        let items: Vec<ListItem> = tasks
             .iter()
             .enumerate()
-            .skip(scroll_offset)
-            .take(visible_height)
             .map(|(i, task)| {
-                let is_selected = focused_index == Some(i);
-                let display_text = truncate_with_ellipsis(&task.text, max_text_width);
-
-                let content = if is_selected {
-                    Line::from(vec![
-                        Span::styled("> ", Style::default().fg(Color::Cyan)),
-                        Span::styled(&prefix, Style::default().fg(Color::DarkGray)),
-                        Span::styled(
-                            display_text,
-                            Style::default()
-                                .fg(Color::White)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                    ])
+                let text_style = if state.selected() == Some(i) {
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD)
                 } else {
-                    Line::from(vec![
-                        Span::raw("  "),
-                        Span::styled(&prefix, Style::default().fg(Color::DarkGray)),
-                        Span::styled(display_text, Style::default().fg(Color::Gray)),
-                    ])
+                    Style::default().fg(Color::Gray)
                 };
-
-                ListItem::new(content)
+                let text_style = if stale_before.is_some_and(|cutoff| task.is_stale(cutoff)) {
+                    text_style.fg(Color::DarkGray)
+                } else {
+                    text_style
+                };
+                let text_style = if overdue_from.is_some_and(|today| task.is_overdue(today)) {
+                    text_style.fg(Color::Red)
+                } else {
+                    text_style
+                };
+                let text_style = if selection.is_some_and(|r| r.contains(&i)) {
+                    text_style.fg(theme.accent)
+                } else {
+                    text_style
+                };
+                let suffix = if show_pomodoros {
+                    task_suffix(task)
+                } else {
+                    String::new()
+                };
+                let mut spans = Vec::new();
+                if line_numbers {
+                    spans.push(Span::styled(
+                        format!("{:>number_width$} ", i + 1),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                spans.push(Span::styled(
+                    prefix.clone(),
+                    Style::default().fg(priority_color(task.priority)),
+                ));
+                spans.extend(text_spans(
+                    &truncate_with_ellipsis(&task.text, max_text_width),
+                    &task.tags,
+                    query,
+                    text_style,
+                ));
+                let suffix_color = if task.estimate.is_some_and(|e| task.pomodoros > e) {
+                    Color::Red
+                } else {
+                    Color::DarkGray
+                };
+                spans.push(Span::styled(suffix, Style::default().fg(suffix_color)));
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
-        // Add ellipsis if there are more items below
-        items.push(if has_more_below {
-            ListItem::new(Line::from(Span::styled(
-                "  ...",
-                Style::default().fg(Color::DarkGray),
-            )))
-        } else {
-            ListItem::new(Line::from(""))
-        });
-
-        frame.render_widget(List::new(items), area);
+        let list = List::new(items)
+            .highlight_symbol(Span::styled("> ", Style::default().fg(theme.accent)))
+            .highlight_spacing(HighlightSpacing::Always)
+            .scroll_padding(SCROLL_PADDING);
+        frame.render_stateful_widget(list, area, state);
+        render_scrollbar(
+            frame,
+            area,
+            tasks.len(),
+            area.height as usize,
+            state.offset(),
+        );
     }
 }
 
@@ -402,6 +1588,18 @@ impl TasksPanel {
         self.move_up();
     }
 
+    /// Jump to the task numbered by digits typed first, or the first task
+    #[keybind(pressed(key=KeyCode::Char('g')))]
+    fn key_jump_to_count(&mut self) {
+        self.jump_to_row(self.count.unwrap_or(1));
+    }
+
+    /// Jump to a task by its number
+    #[keybind(pressed(key=KeyCode::Char(':')))]
+    fn key_jump(&mut self) {
+        self.jump = Some(String::new());
+    }
+
     /// Reorder task down
     #[keybind(pressed(key=KeyCode::Char('J')))]
     fn key_reorder_down(&mut self) {
@@ -426,30 +1624,134 @@ impl TasksPanel {
         self.prev_section();
     }
 
+    /// Column to the left, with sections side by side
+    #[keybind(pressed(key=KeyCode::Char('h')))]
+    fn key_column_left(&mut self) {
+        if self.columns_shown && self.focus.section != TaskSection::Backlog {
+            self.prev_section();
+        }
+    }
+
+    /// Column to the right, with sections side by side
+    #[keybind(pressed(key=KeyCode::Char('l')))]
+    fn key_column_right(&mut self) {
+        if self.columns_shown && self.focus.section != TaskSection::Completed {
+            self.next_section();
+        }
+    }
+
     /// Move task to next section
     #[keybind(pressed(key=KeyCode::Enter))]
     fn key_cycle_task(&mut self) {
-        self.task_manager
-            .cycle_task_section(self.focus.section, self.focus.index);
-        self.clamp_focus();
+        self.apply_action(TaskAction::CycleSection);
     }
 
     /// Toggle task completion
     #[keybind(pressed(key=KeyCode::Char('x')))]
     fn key_toggle_completion(&mut self) {
-        self.task_manager
-            .toggle_completion(self.focus.section, self.focus.index);
-        self.clamp_focus();
+        self.apply_action(TaskAction::ToggleCompletion);
+    }
+
+    /// Restore the last deleted tasks from the trash
+    // Ahead of `z`, which also matches with Ctrl held
+    #[keybind(pressed(key=KeyCode::Char('z'), modifiers=KeyModifiers::CONTROL))]
+    fn key_restore_deleted(&mut self) {
+        match self.task_manager.restore_deleted() {
+            Ok(0) => self.pending_message = Some("Trash is empty".to_string()),
+            Ok(1) => self.pending_message = Some("Restored 1 task".to_string()),
+            Ok(count) => self.pending_message = Some(format!("Restored {count} tasks")),
+            Err(e) => self.pending_error = Some(format!("Failed to restore tasks: {e}")),
+        }
+    }
+
+    /// Fold or unfold section with a
+    #[keybind(pressed(key=KeyCode::Char('z')))]
+    fn key_fold_section(&mut self) {
+        self.fold_pending = true;
+    }
+
+    /// Move task to a section picked with b, c or x
+    #[keybind(pressed(key=KeyCode::Char('m')))]
+    fn key_move_to_section(&mut self) {
+        self.move_pending = self.row_count(self.focus.section) > 0;
+    }
+
+    /// Cycle section order: manual, by name, oldest first, by priority
+    #[keybind(pressed(key=KeyCode::Char('O')))]
+    fn key_cycle_sort(&mut self) {
+        self.set_sort_mode(self.sort_mode(self.focus.section).next());
+    }
+
+    /// Toggle sorting section by priority
+    #[keybind(pressed(key=KeyCode::Char('P')))]
+    fn key_sort_by_priority(&mut self) {
+        let mode = match self.sort_mode(self.focus.section) {
+            SortMode::Priority => SortMode::Manual,
+            _ => SortMode::Priority,
+        };
+        self.set_sort_mode(mode);
+    }
+
+    /// Triage stale Backlog tasks
+    #[keybind(pressed(key=KeyCode::Char('u')))]
+    fn key_triage_stale(&mut self) {
+        if self.filter.stale_before.is_some() {
+            self.filter.stale_before = None;
+        } else if let Some(cutoff) = self.stale_cutoff(Local::now().date_naive()) {
+            self.filter.stale_before = Some(cutoff);
+            self.focus.section = TaskSection::Backlog;
+        } else {
+            self.pending_message =
+                Some("Set tasks.stale_after_days to find stale tasks".to_string());
+            return;
+        }
+        self.select_anchor = None;
+        self.focus.index = 0;
+    }
+
+    /// Toggle task details
+    #[keybind(pressed(key=KeyCode::Char('i')))]
+    fn key_toggle_details(&mut self) {
+        self.details_visible = !self.details_visible;
+    }
+
+    /// Start a random Backlog task
+    #[keybind(pressed(key=KeyCode::Char('r')))]
+    fn key_pick_random(&mut self) {
+        let candidates = self
+            .task_manager
+            .filtered(TaskSection::Backlog, &self.filter);
+        if candidates.is_empty() {
+            self.pending_message = Some("No Backlog tasks to pick from".to_string());
+            return;
+        }
+        let index = candidates[random_below(candidates.len())];
+        self.task_manager.promote_task(index);
+        self.select_anchor = None;
+        self.focus.section = TaskSection::Current;
+        self.focus.index = self
+            .rows(TaskSection::Current)
+            .iter()
+            .position(|&row| row == 0)
+            .unwrap_or_default();
+    }
+
+    /// Repeat last move, completion or delete
+    #[keybind(pressed(key=KeyCode::Char('.')))]
+    fn key_repeat_action(&mut self) {
+        if let Some(action) = self.last_action {
+            self.request_action(action);
+        }
     }
 
     /// Page down
-    #[keybind(pressed(key=KeyCode::Char(',')))]
+    #[keybind(pressed(key=KeyCode::PageDown))]
     fn key_page_down(&mut self) {
         self.page_down();
     }
 
     /// Page up
-    #[keybind(pressed(key=KeyCode::Char('.')))]
+    #[keybind(pressed(key=KeyCode::PageUp))]
     fn key_page_up(&mut self) {
         self.page_up();
     }
@@ -462,6 +1764,63 @@ impl TasksPanel {
         }
     }
 
+    /// Filter tasks by tag
+    #[keybind(pressed(key=KeyCode::Char('f')))]
+    fn key_filter_by_tag(&mut self) {
+        let overlay = TagFilterOverlay::new(self.task_manager.tags(), self.filter.tag.as_deref());
+        self.tag_filter_overlay = Some(overlay);
+    }
+
+    /// Switch project
+    #[keybind(pressed(key=KeyCode::Char('w')))]
+    fn key_switch_project(&mut self) {
+        let overlay =
+            ProjectOverlay::new(self.task_manager.projects(), self.filter.project.as_deref());
+        self.project_overlay = Some(overlay);
+    }
+
+    /// Search tasks
+    #[keybind(pressed(key=KeyCode::Char('/')))]
+    fn key_search(&mut self) {
+        self.search = Some(self.filter.query.clone().unwrap_or_default());
+    }
+
+    /// Next search match
+    #[keybind(pressed(key=KeyCode::Char('n')))]
+    fn key_next_match(&mut self) {
+        self.jump_to_match(true);
+    }
+
+    /// Previous search match
+    #[keybind(pressed(key=KeyCode::Char('N')))]
+    fn key_prev_match(&mut self) {
+        self.jump_to_match(false);
+    }
+
+    /// Edit the note of the focused task
+    #[keybind(pressed(key=KeyCode::Char('E')))]
+    fn key_edit_note(&mut self) {
+        let section = self.focus.section;
+        let Some(index) = self.focused_index() else {
+            return;
+        };
+        if let Some(task) = self.task_manager.section(section).get(index) {
+            self.note_overlay = Some(NoteOverlay::new(section, index, &task.text, &task.note));
+        }
+    }
+
+    /// Edit focused task
+    #[keybind(pressed(key=KeyCode::Char('e')))]
+    fn key_edit_task(&mut self) {
+        let section = self.focus.section;
+        let Some(index) = self.focused_index() else {
+            return;
+        };
+        if let Some(task) = self.task_manager.section(section).get(index) {
+            self.task_input_overlay = Some(TaskInputOverlay::edit(section, index, &task.text));
+        }
+    }
+
     /// Sync tasks with file
     #[keybind(pressed(key=KeyCode::Char('s')))]
     #[keybind(pressed(key=KeyCode::Char('S')))]
@@ -472,63 +1831,294 @@ impl TasksPanel {
         }
     }
 
+    /// Archive old completed tasks
+    #[keybind(pressed(key=KeyCode::Char('A')))]
+    fn key_archive(&mut self) {
+        if self.task_manager.archive_path().is_none() {
+            self.pending_error = Some("No task file to archive from".to_string());
+            return;
+        }
+        let indices = self.task_manager.archivable(self.config.archive_after_days);
+        let message = match (indices.len(), self.config.archive_after_days) {
+            (0, 0) => {
+                self.pending_message = Some("No completed tasks to archive".to_string());
+                return;
+            }
+            (0, _) => {
+                self.pending_message = Some("No completed tasks old enough to archive".to_string());
+                return;
+            }
+            (count, 0) => format!("Archive all {count} completed tasks?"),
+            (count, days) => format!("Archive {count} tasks done over {days} days ago?"),
+        };
+        let overlay = ConfirmOverlay::new(" Archive Tasks ", message);
+        self.archive_overlay = Some(overlay);
+    }
+
+    /// Reset day: move Current back to Backlog, optionally archiving
+    #[keybind(pressed(key=KeyCode::Char('R')))]
+    fn key_reset_day(&mut self) {
+        let archivable = self.reset_archivable();
+        let current = self.current_tasks();
+        if current.is_empty() && archivable.is_empty() {
+            self.pending_message = Some("Nothing to reset".to_string());
+            return;
+        }
+        let overlay = ResetDayOverlay::new(current, archivable.len(), self.config.archive_on_reset);
+        self.reset_overlay = Some(overlay);
+    }
+
+    /// Select several tasks, for moving, completing or deleting together
+    #[keybind(pressed(key=KeyCode::Char('v')))]
+    fn key_visual_select(&mut self) {
+        let empty = self.row_count(self.focus.section) == 0;
+        self.select_anchor = match self.select_anchor {
+            None if !empty => Some(self.focus.index),
+            _ => None,
+        };
+    }
+
     /// Delete focused task
     #[keybind(pressed(key=KeyCode::Char('d')))]
     fn key_delete_task(&mut self) {
-        self.task_manager
-            .delete_task(self.focus.section, self.focus.index);
-        self.clamp_focus();
+        self.request_action(TaskAction::Delete);
+    }
+
+    /// Copy focused task to the clipboard
+    #[keybind(pressed(key=KeyCode::Char('y')))]
+    fn key_yank_task(&mut self) {
+        let Some(index) = self.focused_index() else {
+            return;
+        };
+        let Some(task) = self.task_manager.section(self.focus.section).get(index) else {
+            return;
+        };
+        match self.clipboard.copy(&task.text) {
+            Ok(()) => self.pending_message = Some("Copied task".to_string()),
+            Err(e) => self.pending_error = Some(format!("Failed to copy: {e}")),
+        }
+    }
+
+    /// Paste tasks from the clipboard, one per line
+    #[keybind(pressed(key=KeyCode::Char('p')))]
+    fn key_paste_tasks(&mut self) {
+        let text = match self.clipboard.paste() {
+            Ok(text) => text,
+            Err(e) => {
+                self.pending_error = Some(format!("Failed to paste: {e}"));
+                return;
+            }
+        };
+        let tasks = pasted_tasks(&text);
+        for text in &tasks {
+            self.add_task(text, self.focus.section);
+        }
+        self.pending_message = Some(match tasks.len() {
+            0 => "Nothing to paste".to_string(),
+            1 => "Pasted 1 task".to_string(),
+            count => format!("Pasted {count} tasks"),
+        });
+        self.added_tasks.extend(tasks);
+    }
+
+    /// Open a link in the focused task
+    #[keybind(pressed(key=KeyCode::Char('o')))]
+    fn key_open_url(&mut self) {
+        let Some(index) = self.focused_index() else {
+            return;
+        };
+        let Some(task) = self.task_manager.section(self.focus.section).get(index) else {
+            return;
+        };
+        let urls: Vec<String> = task.urls().into_iter().map(String::from).collect();
+        match urls.as_slice() {
+            [] => self.pending_message = Some("No link in this task".to_string()),
+            [url] => self.open_url(&url.clone()),
+            _ => self.url_picker = Some(UrlPickerOverlay::new(urls)),
+        }
+    }
+
+    /// Merge duplicate tasks
+    #[keybind(pressed(key=KeyCode::Char('M')))]
+    fn key_merge_duplicates(&mut self) {
+        self.show_duplicates();
+        if self.duplicates_overlay.is_none() {
+            self.pending_message = Some("No duplicate tasks".to_string());
+        }
+    }
+
+    /// Restore the task file from a backup
+    #[keybind(pressed(key=KeyCode::Char('B')))]
+    fn key_restore_backup(&mut self) {
+        let backups = self.task_manager.backups();
+        if backups.is_empty() {
+            self.pending_message = Some("No backups of the task file".to_string());
+            return;
+        }
+        self.backup_overlay = Some(BackupOverlay::new(backups));
     }
 }
 
-/// Calculates scroll offset to keep focused item within margin from edges
-fn calculate_scroll_offset(total: usize, visible: usize, focused: Option<usize>) -> usize {
-    let Some(cursor) = focused else { return 0 };
-    if visible == 0 {
-        return 0;
-    }
-    let max_offset = total.saturating_sub(visible);
-    let margin = 2usize;
-    // Keep cursor at least `margin` from bottom when scrolling down
-    let min_offset_for_cursor =
-        cursor.saturating_sub(visible.saturating_sub(margin).saturating_sub(1));
-    // Keep cursor at least `margin` from top when scrolling up
-    let max_offset_for_cursor = cursor.saturating_sub(margin);
-    // Clamp between the two constraints
-    min_offset_for_cursor
-        .min(max_offset)
-        .max(0)
-        .min(max_offset_for_cursor.max(0).min(max_offset))
+/// Open `url` with the desktop's handler for links, without waiting for it
+fn open_url(url: &str) -> Result<(), io::Error> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let mut child = Command::new(opener)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Reaped in the background, as the opener can wait on the browser it starts
+    thread::spawn(move || child.wait());
+    Ok(())
 }
 
-fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
-    if text.len() <= max_width {
-        return text.to_string();
-    }
-    if max_width < 3 {
-        return ".".repeat(max_width);
+fn pressed_key(event: &Event) -> Option<KeyCode> {
+    match event {
+        Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) => Some(*code),
+        _ => None,
     }
-    let limit = max_width - 3; // room for "..."
-    let mut result = String::new();
-    for word in text.split_whitespace() {
-        if result.is_empty() {
-            if word.len() > limit {
-                return "...".to_string();
-            }
-            result = word.to_string();
-        } else if result.len() + 1 + word.len() <= limit {
-            result.push(' ');
-            result.push_str(word);
+}
+
+/// Task text split into spans with the words for `tags` picked out in their own color, and
+/// any text matching `query` highlighted
+fn text_spans(
+    text: &str,
+    tags: &[String],
+    query: Option<&str>,
+    style: Style,
+) -> Vec<Span<'static>> {
+    let is_tag = |word: &str| {
+        word.strip_prefix('#')
+            .is_some_and(|name| tags.iter().any(|tag| tag == name))
+    };
+    let matches = query.map_or_else(Vec::new, |query| find_ignore_case(text, query));
+    let highlight = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for word in text.split_inclusive(' ') {
+        let end = start + word.len();
+        let word_style = if is_tag(word.trim_end()) {
+            style.fg(Color::Magenta)
         } else {
-            break;
+            style
+        };
+        // Cut the word where matches start and end within it
+        let mut from = start;
+        for found in matches.iter().filter(|m| m.start < end && m.end > start) {
+            let (lit_start, lit_end) = (found.start.max(start), found.end.min(end));
+            if lit_start > from {
+                spans.push(Span::styled(text[from..lit_start].to_string(), word_style));
+            }
+            spans.push(Span::styled(
+                text[lit_start..lit_end].to_string(),
+                word_style.patch(highlight),
+            ));
+            from = lit_end;
+        }
+        if from < end {
+            spans.push(Span::styled(text[from..end].to_string(), word_style));
         }
+        start = end;
+    }
+    spans
+}
+
+/// Checkbox color marking how urgent a task is
+const fn priority_color(priority: Option<Priority>) -> Color {
+    match priority {
+        Some(Priority::High) => Color::Red,
+        Some(Priority::Medium) => Color::Yellow,
+        Some(Priority::Low) => Color::Blue,
+        None => Color::DarkGray,
+    }
+}
+
+/// Dim effort summary shown after a task, e.g. ` 🍅3`, or ` 2/3 🍅` against an estimate;
+/// fits in the row's trailing space
+fn task_suffix(task: &Task) -> String {
+    let pomodoros = match task.estimate {
+        Some(estimate) => format!(" {}/{estimate} 🍅", task.pomodoros),
+        None if task.pomodoros == 0 => String::new(),
+        None => format!(" 🍅{}", task.pomodoros),
+    };
+    // Marks tasks with a note under them, opened with `E`
+    if task.note.is_empty() {
+        pomodoros
+    } else {
+        format!("{pomodoros} ✎")
     }
-    format!("{result}...")
+}
+
+/// Everything known about `task` for the details drawer, given the work `sessions` on it
+fn detail_lines(task: &Task, sessions: &[&SessionRecord]) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{label:<10}"), dim),
+            Span::raw(value),
+        ])
+    };
+
+    let mut lines = vec![Line::styled(
+        task.text.clone(),
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(ref project) = task.project {
+        lines.push(field("Project", project.clone()));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{tag}")).collect();
+        lines.push(field("Tags", tags.join(" ")));
+    }
+    for (label, day) in [
+        ("Added", task.created_on),
+        ("Due", task.due),
+        ("Completed", task.completed_on),
+    ] {
+        if let Some(day) = day {
+            lines.push(field(label, day.format("%Y-%m-%d").to_string()));
+        }
+    }
+    let pomodoros = task.estimate.map_or_else(
+        || task.pomodoros.to_string(),
+        |estimate| format!("{} of ~{estimate}", task.pomodoros),
+    );
+    lines.push(field("Pomodoros", pomodoros));
+    if let Some(last) = sessions.last() {
+        let focused = sessions.iter().map(|r| r.duration).sum();
+        lines.push(field(
+            "Sessions",
+            format!(
+                "{} logged, {} focused, last {}",
+                sessions.len(),
+                format_focused(focused),
+                last.end.format("%Y-%m-%d %H:%M")
+            ),
+        ));
+    }
+    if !task.note.is_empty() {
+        lines.push(Line::from(""));
+        lines.extend(task.note.lines().map(|line| Line::raw(line.to_string())));
+    }
+    lines
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+    use ratatui::{backend::TestBackend, Terminal};
 
     #[test]
     fn test_truncate_with_ellipsis() {
@@ -558,36 +2148,183 @@ mod tests {
         assert_eq!(truncate_with_ellipsis("Hello", 1), ".");
         assert_eq!(truncate_with_ellipsis("Hello", 2), "..");
         assert_eq!(truncate_with_ellipsis("Hello", 3), "...");
+
+        // Wide characters take two columns each
+        assert_eq!(truncate_with_ellipsis("🍕🍕 party", 10), "🍕🍕 party");
+        assert_eq!(
+            truncate_with_ellipsis("🍕🍕 party time", 13),
+            "🍕🍕 party..."
+        );
+        assert_eq!(truncate_with_ellipsis("日本語のタスク", 10), "...");
+    }
+
+    #[test]
+    fn test_text_spans() {
+        let task = Task::new("Write #work report #".to_string());
+        let spans = text_spans(&task.text, &task.tags, None, Style::default());
+        let words: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(words, ["Write ", "#work ", "report ", "#"]);
+        assert_eq!(spans[1].style.fg, Some(Color::Magenta));
+        assert_eq!(spans[2].style.fg, None);
+        assert_eq!(spans[3].style.fg, None);
+
+        // Matches are cut out of the words they fall in, keeping the tag color
+        let spans = text_spans(&task.text, &task.tags, Some("OR"), Style::default());
+        let words: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(words, ["Write ", "#w", "or", "k ", "rep", "or", "t ", "#"]);
+        assert_eq!(spans[2].style.fg, Some(Color::Black));
+        assert_eq!(spans[2].style.bg, Some(Color::Yellow));
+        assert_eq!(spans[3].style.fg, Some(Color::Magenta));
     }
 
     #[test]
-    fn test_scroll_offset() {
-        // No focus returns 0
-        assert_eq!(calculate_scroll_offset(10, 5, None), 0);
+    fn test_task_suffix() {
+        let mut task = Task::new("Fix parser".to_string());
+        assert_eq!(task_suffix(&task), "");
+        task.pomodoros = 3;
+        assert_eq!(task_suffix(&task), " 🍅3");
+
+        let mut task = Task::new("Fix login ~3".to_string());
+        assert_eq!(task_suffix(&task), " 0/3 🍅");
+        task.pomodoros = 2;
+        assert_eq!(task_suffix(&task), " 2/3 🍅");
+        task.note = "Fails on empty input".to_string();
+        assert_eq!(task_suffix(&task), " 2/3 🍅 ✎");
+    }
 
-        // All items fit in view, no scroll needed
-        assert_eq!(calculate_scroll_offset(5, 10, Some(0)), 0);
-        assert_eq!(calculate_scroll_offset(5, 10, Some(4)), 0);
+    #[test]
+    fn test_detail_lines() {
+        let mut task = Task::new("Fix parser #work ~3".to_string());
+        task.pomodoros = 2;
+        task.created_on = NaiveDate::from_ymd_opt(2026, 3, 1);
+        task.note = "Fails on empty input".to_string();
+        let session = SessionRecord {
+            end: Local::now(),
+            session: crate::timer::SessionType::Work,
+            duration: std::time::Duration::from_mins(25),
+            interruptions: 0,
+            task: Some(task.text.clone()),
+            paused_at: Vec::new(),
+        };
 
-        // Cursor at top stays at offset 0
-        assert_eq!(calculate_scroll_offset(20, 10, Some(0)), 0);
+        let lines: Vec<String> = detail_lines(&task, &[&session, &session])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(lines[0], "Fix parser #work ~3");
+        assert!(lines.contains(&"Tags      #work".to_string()));
+        assert!(lines.contains(&"Added     2026-03-01".to_string()));
+        assert!(lines.contains(&"Pomodoros 2 of ~3".to_string()));
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("Sessions  2 logged, 50m focused")));
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some("Fails on empty input")
+        );
+    }
+
+    #[test]
+    fn test_columns_layout() -> Result<(), Box<dyn std::error::Error>> {
+        let mut panel = TasksPanel::default();
+        panel.config.columns = true;
+        panel.task_manager.add_task("Plan", TaskSection::Backlog);
+        panel.task_manager.add_task("Write", TaskSection::Current);
+        let draw =
+            |panel: &mut TasksPanel, width| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+                let mut terminal = Terminal::new(TestBackend::new(width, 20))?;
+                terminal.draw(|frame| {
+                    panel.render(
+                        frame,
+                        frame.area(),
+                        true,
+                        &History::default(),
+                        &Theme::default(),
+                    );
+                })?;
+                Ok((0..20)
+                    .map(|y| {
+                        (0..width)
+                            .map(|x| terminal.backend().buffer()[(x, y)].symbol())
+                            .collect()
+                    })
+                    .collect())
+            };
+
+        let rows = draw(&mut panel, 120)?;
+        assert!(rows
+            .iter()
+            .any(|row| row.contains("Plan") && row.contains("Write")));
+        let press = |panel: &mut TasksPanel, c| {
+            panel.handle(&Event::Key(KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+            )));
+        };
+        press(&mut panel, 'l');
+        assert_eq!(panel.focus.section, TaskSection::Current);
+        press(&mut panel, 'h');
+        press(&mut panel, 'h');
+        assert_eq!(panel.focus.section, TaskSection::Backlog);
+
+        // Too narrow for columns, so stacked and h and l do nothing
+        let rows = draw(&mut panel, 60)?;
+        assert!(!rows
+            .iter()
+            .any(|row| row.contains("Plan") && row.contains("Write")));
+        press(&mut panel, 'l');
+        assert_eq!(panel.focus.section, TaskSection::Backlog);
+        Ok(())
+    }
 
-        // Cursor near top with margin=2, still at offset 0
-        assert_eq!(calculate_scroll_offset(20, 10, Some(2)), 0);
+    #[test]
+    fn test_list_scrolls_to_focus() -> Result<(), Box<dyn std::error::Error>> {
+        let mut panel = TasksPanel::default();
+        for i in 0..20 {
+            panel
+                .task_manager
+                .add_task(format!("Task {i}"), TaskSection::Backlog);
+        }
+        panel.focus.index = 15;
 
-        // Cursor moves down: index 10, visible=10, margin=2
-        // min_offset_for_cursor = 10 - (10 - 2 - 1) = 3
-        assert_eq!(calculate_scroll_offset(20, 10, Some(10)), 3);
+        let mut terminal = Terminal::new(TestBackend::new(40, 30))?;
+        terminal.draw(|frame| {
+            panel.render(
+                frame,
+                frame.area(),
+                true,
+                &History::default(),
+                &Theme::default(),
+            );
+        })?;
+        let rows: Vec<String> = (0..30)
+            .map(|y| {
+                (0..40)
+                    .map(|x| terminal.backend().buffer()[(x, y)].symbol())
+                    .collect()
+            })
+            .collect();
 
-        // Cursor at bottom (index 19), visible=10
-        assert_eq!(calculate_scroll_offset(20, 10, Some(19)), 10);
+        assert!(rows.iter().any(|row| row.contains("> [ ] Task 15")));
+        // Two rows of padding stay visible below the focused task
+        assert!(rows.iter().any(|row| row.contains("Task 17")));
+        assert!(!rows.iter().any(|row| row.contains("Task 0 ")));
+        Ok(())
+    }
 
-        // Respects max offset
-        assert_eq!(calculate_scroll_offset(10, 10, Some(15)), 0);
-        assert_eq!(calculate_scroll_offset(15, 10, Some(20)), 5);
+    #[test]
+    fn test_handle_dispatches_keybindings() {
+        let mut panel = TasksPanel::default();
+        panel
+            .task_manager
+            .add_task("Task 1".to_string(), TaskSection::Backlog);
+        panel
+            .task_manager
+            .add_task("Task 2".to_string(), TaskSection::Backlog);
 
-        // Zero visible height
-        assert_eq!(calculate_scroll_offset(10, 0, Some(5)), 0);
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert!(panel.handle(&key));
+        assert_eq!(panel.focus.index, 1);
     }
 
     #[test]
@@ -674,6 +2411,727 @@ mod tests {
         assert_eq!(panel.focus.index, 0);
     }
 
+    #[test]
+    fn test_repeat_last_action() {
+        let mut panel = TasksPanel::default();
+        panel.config.confirm_delete = false;
+        for i in 0..4 {
+            panel
+                .task_manager
+                .add_task(format!("Task {i}"), TaskSection::Backlog);
+        }
+
+        // Nothing to repeat yet
+        panel.key_repeat_action();
+        assert_eq!(panel.task_manager.backlog().len(), 4);
+
+        // Moving to current, then repeating on the task that takes its place
+        panel.key_cycle_task();
+        panel.key_repeat_action();
+        assert_eq!(panel.task_manager.backlog().len(), 2);
+        assert_eq!(panel.task_manager.current()[1].text, "Task 1");
+
+        // Delete replaces the remembered action
+        panel.key_delete_task();
+        panel.key_repeat_action();
+        assert!(panel.task_manager.backlog().is_empty());
+        assert_eq!(panel.task_manager.current().len(), 2);
+    }
+
+    #[test]
+    fn test_edit_focused_task() {
+        let mut panel = TasksPanel::default();
+        panel.task_manager.add_task("Task 1", TaskSection::Backlog);
+        panel.task_manager.add_task("Task 2", TaskSection::Backlog);
+        panel.focus.index = 1;
+
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+        press(&mut panel, KeyCode::Char('e'));
+        press(&mut panel, KeyCode::Backspace);
+        press(&mut panel, KeyCode::Char('b'));
+        press(&mut panel, KeyCode::Enter);
+
+        assert!(panel.task_input_overlay.is_none());
+        assert_eq!(panel.task_manager.backlog()[1].text, "Task b");
+        assert_eq!(panel.task_manager.backlog().len(), 2);
+        assert!(panel.take_added_tasks().is_empty());
+    }
+
+    #[test]
+    fn test_confirm_delete() {
+        let mut panel = TasksPanel::default();
+        panel.task_manager.add_task("Task 1", TaskSection::Backlog);
+
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+        press(&mut panel, KeyCode::Char('d'));
+        assert!(panel.confirm_overlay().is_some());
+        press(&mut panel, KeyCode::Char('n'));
+        assert!(panel.confirm_overlay().is_none());
+        assert_eq!(panel.task_manager.backlog().len(), 1);
+
+        press(&mut panel, KeyCode::Char('d'));
+        press(&mut panel, KeyCode::Char('y'));
+        assert!(panel.confirm_overlay().is_none());
+        assert!(panel.task_manager.backlog().is_empty());
+    }
+
+    #[test]
+    fn test_visual_select() {
+        let mut panel = TasksPanel::default();
+        for i in 0..5 {
+            panel
+                .task_manager
+                .add_task(format!("Task {i}"), TaskSection::Backlog);
+        }
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+
+        // Select tasks 1 to 3, moving them to current together
+        panel.focus.index = 3;
+        press(&mut panel, KeyCode::Char('v'));
+        press(&mut panel, KeyCode::Char('k'));
+        press(&mut panel, KeyCode::Char('k'));
+        assert_eq!(panel.selection(), Some(1..=3));
+        press(&mut panel, KeyCode::Enter);
+        assert_eq!(panel.selection(), None);
+        assert_eq!(panel.task_manager.current().len(), 3);
+        assert_eq!(panel.task_manager.current()[0].text, "Task 1");
+        assert_eq!(panel.focus.index, 1);
+
+        // Esc leaves visual mode without touching anything
+        press(&mut panel, KeyCode::Char('v'));
+        press(&mut panel, KeyCode::Esc);
+        assert_eq!(panel.selection(), None);
+
+        // Bulk delete asks once for all of them
+        press(&mut panel, KeyCode::Char('k'));
+        press(&mut panel, KeyCode::Char('v'));
+        press(&mut panel, KeyCode::Char('j'));
+        press(&mut panel, KeyCode::Char('d'));
+        assert!(panel.confirm_overlay().is_some());
+        press(&mut panel, KeyCode::Char('y'));
+        assert!(panel.task_manager.backlog().is_empty());
+    }
+
+    #[test]
+    fn test_move_to_section() {
+        let mut panel = TasksPanel::default();
+        panel.task_manager.add_task("Task 1", TaskSection::Backlog);
+        panel.task_manager.add_task("Task 2", TaskSection::Backlog);
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+
+        press(&mut panel, KeyCode::Char('m'));
+        press(&mut panel, KeyCode::Char('x'));
+        assert_eq!(panel.task_manager.completed()[0].text, "Task 1");
+
+        // Any other key cancels, and is not acted on
+        press(&mut panel, KeyCode::Char('m'));
+        press(&mut panel, KeyCode::Char('d'));
+        assert_eq!(panel.task_manager.backlog().len(), 1);
+        assert!(panel.confirm_overlay().is_none());
+
+        press(&mut panel, KeyCode::Char('.'));
+        assert!(panel.task_manager.backlog().is_empty());
+        assert_eq!(panel.task_manager.completed().len(), 2);
+    }
+
+    #[test]
+    fn test_sort_by_priority() {
+        let mut panel = TasksPanel::default();
+        for text in ["Low (C)", "Plain", "Urgent !!", "Soon !"] {
+            panel.task_manager.add_task(text, TaskSection::Backlog);
+        }
+        let texts = |panel: &TasksPanel| {
+            let backlog = panel.task_manager.backlog();
+            panel
+                .rows(TaskSection::Backlog)
+                .into_iter()
+                .map(|i| backlog[i].text.clone())
+                .collect::<Vec<_>>()
+        };
+
+        panel.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('P'),
+            KeyModifiers::SHIFT,
+        )));
+        assert_eq!(texts(&panel), ["Urgent !!", "Soon !", "Low (C)", "Plain"]);
+
+        // New tasks take their place while sorting is on, sooner due dates first
+        panel.add_task("Also urgent (A) @2030-01-01", TaskSection::Backlog);
+        panel.add_task("Urgent too !! @2029-01-01", TaskSection::Backlog);
+        assert_eq!(texts(&panel)[0], "Urgent too !! @2029-01-01");
+        assert_eq!(texts(&panel)[1], "Also urgent (A) @2030-01-01");
+
+        // The tasks themselves keep the order they were added in
+        assert_eq!(panel.task_manager.backlog()[0].text, "Low (C)");
+    }
+
+    #[test]
+    fn test_sort_by_priority_keeps_file_order() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        std::fs::write(&path, "- [ ] Plain\n- [ ] Urgent !!\n")?;
+        let (mut panel, _) = TasksPanel::from_file(Some(path.clone()), None);
+        panel.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('P'),
+            KeyModifiers::SHIFT,
+        )));
+        panel.add_task("Soon !", TaskSection::Backlog);
+        panel.task_manager.save()?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "- [ ] Plain\n- [ ] Urgent !!\n- [ ] Soon !\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle_sort_modes() {
+        let mut panel = TasksPanel::default();
+        for text in ["review pr", "Plan week", "fix parser"] {
+            panel.task_manager.add_task(text, TaskSection::Backlog);
+        }
+        let texts = |panel: &TasksPanel| {
+            let backlog = panel.task_manager.backlog();
+            panel
+                .rows(TaskSection::Backlog)
+                .into_iter()
+                .map(|i| backlog[i].text.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let cycle = |panel: &mut TasksPanel| {
+            panel.handle(&Event::Key(KeyEvent::new(
+                KeyCode::Char('O'),
+                KeyModifiers::SHIFT,
+            )));
+        };
+
+        cycle(&mut panel);
+        assert_eq!(texts(&panel), "fix parser, Plan week, review pr");
+        // The focused task stays focused as the order changes
+        assert_eq!(panel.focus.index, 2);
+
+        // Added the same day, so oldest first leaves them as they were
+        cycle(&mut panel);
+        assert_eq!(texts(&panel), "review pr, Plan week, fix parser");
+
+        // Reordering by hand waits for manual order
+        panel.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('J'),
+            KeyModifiers::SHIFT,
+        )));
+        assert!(panel.take_message().is_some());
+
+        cycle(&mut panel);
+        cycle(&mut panel);
+        assert_eq!(texts(&panel), "review pr, Plan week, fix parser");
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        let mut panel = TasksPanel::default();
+        panel.config.confirm_delete = false;
+        for text in ["Plan #work", "Shop #home", "Review #work", "Call #home"] {
+            panel.task_manager.add_task(text, TaskSection::Backlog);
+        }
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+
+        // Tags are listed sorted, after the row that clears the filter
+        press(&mut panel, KeyCode::Char('f'));
+        press(&mut panel, KeyCode::Char('j'));
+        press(&mut panel, KeyCode::Char('j'));
+        press(&mut panel, KeyCode::Enter);
+        assert_eq!(panel.filter.tag.as_deref(), Some("work"));
+        assert_eq!(panel.row_count(TaskSection::Backlog), 2);
+
+        // Rows map to the tasks shown, skipping hidden ones
+        press(&mut panel, KeyCode::Char('j'));
+        assert_eq!(panel.focused_index(), Some(2));
+        press(&mut panel, KeyCode::Char('d'));
+        assert_eq!(panel.task_manager.backlog().len(), 3);
+        assert_eq!(panel.task_manager.backlog()[1].text, "Shop #home");
+        assert_eq!(panel.focus.index, 0);
+
+        // Clearing shows everything again
+        press(&mut panel, KeyCode::Char('f'));
+        press(&mut panel, KeyCode::Char('k'));
+        press(&mut panel, KeyCode::Char('k'));
+        press(&mut panel, KeyCode::Enter);
+        assert!(!panel.filter.is_active());
+        assert_eq!(panel.row_count(TaskSection::Backlog), 3);
+    }
+
+    #[test]
+    fn test_switch_project() {
+        let mut panel = TasksPanel::default();
+        for (text, project) in [("Plant beans", "Garden"), ("Fix header", "Website")] {
+            panel
+                .task_manager
+                .add_task(text, TaskSection::Backlog)
+                .project = Some(project.to_string());
+        }
+        panel
+            .task_manager
+            .add_task("Loose end", TaskSection::Backlog);
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+
+        // Projects are listed in the order they come up
+        press(&mut panel, KeyCode::Char('w'));
+        press(&mut panel, KeyCode::Char('j'));
+        press(&mut panel, KeyCode::Char('j'));
+        press(&mut panel, KeyCode::Enter);
+        assert_eq!(panel.filter.project.as_deref(), Some("Website"));
+        assert_eq!(panel.rows(TaskSection::Backlog), [1]);
+
+        // New tasks go in the project shown
+        panel.add_task("Add footer", TaskSection::Backlog);
+        assert_eq!(panel.rows(TaskSection::Backlog), [1, 3]);
+        assert_eq!(
+            panel.task_manager.backlog()[3].project.as_deref(),
+            Some("Website")
+        );
+
+        press(&mut panel, KeyCode::Char('w'));
+        press(&mut panel, KeyCode::Char('k'));
+        press(&mut panel, KeyCode::Char('k'));
+        press(&mut panel, KeyCode::Enter);
+        assert_eq!(panel.row_count(TaskSection::Backlog), 4);
+    }
+
+    #[test]
+    fn test_pick_random_task() {
+        let mut panel = TasksPanel::default();
+        for text in ["Plan", "Review #work", "Shop"] {
+            panel.task_manager.add_task(text, TaskSection::Backlog);
+        }
+        panel.task_manager.add_task("Write", TaskSection::Current);
+        let press = |panel: &mut TasksPanel| {
+            KeyMap::handle(
+                panel,
+                &Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE)),
+            );
+        };
+
+        // Only tasks the filter shows are picked from
+        panel.filter.tag = Some("work".to_string());
+        press(&mut panel);
+        let active = panel.task_manager.active_task().map(|t| t.text.as_str());
+        assert_eq!(active, Some("Review #work"));
+        assert_eq!(panel.focus.section, TaskSection::Current);
+        assert_eq!(panel.focused_index(), Some(0));
+
+        press(&mut panel);
+        assert!(panel.pending_message.is_some());
+        assert_eq!(panel.task_manager.section_len(TaskSection::Current), 2);
+    }
+
+    #[test]
+    fn test_reset_day() {
+        let mut panel = TasksPanel::default();
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+        press(&mut panel, KeyCode::Char('R'));
+        assert!(panel.reset_overlay().is_none());
+        assert!(panel.take_message().is_some());
+
+        panel.task_manager.add_task("Write", TaskSection::Current);
+        panel.task_manager.add_task("Review", TaskSection::Current);
+        press(&mut panel, KeyCode::Char('R'));
+        assert!(panel.reset_overlay().is_some());
+        press(&mut panel, KeyCode::Char('y'));
+        assert!(panel.reset_overlay().is_none());
+        assert_eq!(panel.task_manager.section_len(TaskSection::Current), 0);
+        assert_eq!(panel.task_manager.section_len(TaskSection::Backlog), 2);
+        assert_eq!(
+            panel.take_message().as_deref(),
+            Some("Moved 2 tasks back to Backlog")
+        );
+    }
+
+    #[test]
+    fn test_auto_promote() {
+        let mut panel = TasksPanel::default();
+        panel.task_manager.add_task("Plan", TaskSection::Backlog);
+        panel.task_manager.add_task("Review", TaskSection::Backlog);
+        panel.task_manager.add_task("Write", TaskSection::Current);
+        panel.task_manager.add_task("Edit", TaskSection::Current);
+
+        // Off by default
+        assert_eq!(panel.complete_current_task(), None);
+        assert_eq!(panel.task_manager.section_len(TaskSection::Backlog), 2);
+
+        // Only once Current is empty
+        panel.config.auto_promote = true;
+        assert_eq!(panel.complete_current_task().as_deref(), Some("Plan"));
+        assert_eq!(panel.task_manager.section_len(TaskSection::Current), 1);
+        assert_eq!(panel.task_manager.section_len(TaskSection::Backlog), 1);
+    }
+
+    #[test]
+    fn test_count_badge() {
+        let mut panel = TasksPanel::default();
+        for text in ["Plan #work", "Shop", "Review #work"] {
+            panel.task_manager.add_task(text, TaskSection::Backlog);
+        }
+        panel.task_manager.add_task("Write", TaskSection::Current);
+        assert_eq!(panel.count_badge(TaskSection::Backlog), "3");
+        assert_eq!(panel.count_badge(TaskSection::Current), "1");
+
+        // Only the tasks shown count, except against the WIP limit
+        panel.filter.tag = Some("work".to_string());
+        panel.config.wip_limit = Some(3);
+        assert_eq!(panel.count_badge(TaskSection::Backlog), "2");
+        assert_eq!(panel.count_badge(TaskSection::Current), "1/3");
+        assert_eq!(panel.count_badge(TaskSection::Completed), "0");
+    }
+
+    #[test]
+    fn test_modified_marker() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        std::fs::write(&path, "- [ ] Plan\n")?;
+        let (mut panel, _) = TasksPanel::from_file(Some(path), None);
+        assert_eq!(panel.title(), " Tasks ");
+
+        panel.add_task("Write", TaskSection::Backlog);
+        assert_eq!(panel.title(), " Tasks* ");
+        panel.apply_sync(&[])?;
+        assert_eq!(panel.title(), " Tasks ");
+
+        // Taking a change back leaves nothing to sync
+        panel.add_task("Review", TaskSection::Backlog);
+        panel.task_manager.delete_task(TaskSection::Backlog, 2);
+        assert_eq!(panel.title(), " Tasks ");
+
+        // Without a file there is nothing to sync to
+        let mut panel = TasksPanel::default();
+        panel.add_task("Write", TaskSection::Backlog);
+        assert_eq!(panel.title(), " Tasks ");
+        Ok(())
+    }
+
+    /// Run auto-sync as though it were due, until the background read of the file is back
+    fn auto_sync_now(panel: &mut TasksPanel, focused: bool) -> Result<bool, String> {
+        panel.last_auto_sync = Instant::now()
+            .checked_sub(Duration::from_secs(1))
+            .unwrap_or_else(Instant::now);
+        panel.auto_sync(focused)?;
+        for _ in 0..200 {
+            thread::sleep(Duration::from_millis(10));
+            let found = panel.auto_sync(focused)?;
+            if panel.auto_sync_read.is_none() {
+                return Ok(found);
+            }
+        }
+        Err("The task file was never read".to_string())
+    }
+
+    #[test]
+    fn test_auto_sync_waits_for_modes_and_focus() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        std::fs::write(&path, "- [ ] Plan\n")?;
+        let (mut panel, _) = TasksPanel::from_file(Some(path.clone()), None);
+        panel.set_config(TasksConfig {
+            auto_sync_secs: 1,
+            ..TasksConfig::default()
+        });
+        panel.add_task("Both", TaskSection::Backlog);
+        std::fs::write(&path, "- [ ] Plan\n- [x] Both\n")?;
+
+        // Selecting tasks holds their indices, so the sync waits
+        panel.select_anchor = Some(0);
+        assert!(!auto_sync_now(&mut panel, true)?);
+        assert_eq!(panel.task_manager.completed().len(), 0);
+
+        // Found while another panel has focus, the conflict waits for `s`
+        panel.select_anchor = None;
+        assert!(auto_sync_now(&mut panel, false)?);
+        assert!(panel.sync_overlay.is_none());
+
+        // Nothing changed since, so nothing is written
+        panel.task_manager.add_task("Review", TaskSection::Backlog);
+        panel.apply_sync(&[])?;
+        let modified = std::fs::metadata(&path)?.modified()?;
+        assert!(!auto_sync_now(&mut panel, true)?);
+        assert_eq!(std::fs::metadata(&path)?.modified()?, modified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_day_archives_tasks_completed_while_asking() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        std::fs::write(&path, "- [x] Plan\n")?;
+        let (mut panel, _) = TasksPanel::from_file(Some(path), None);
+        panel.set_config(TasksConfig {
+            archive_after_days: 0,
+            archive_on_reset: true,
+            ..TasksConfig::default()
+        });
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+        press(&mut panel, KeyCode::Char('R'));
+        panel.task_manager.add_task("Write", TaskSection::Completed);
+        press(&mut panel, KeyCode::Char('y'));
+        assert_eq!(panel.task_manager.section_len(TaskSection::Completed), 0);
+        assert_eq!(
+            panel.take_message().as_deref(),
+            Some("Moved 0 tasks back to Backlog and archived 2")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_duplicates_asks_first() {
+        let mut panel = TasksPanel::default();
+        panel
+            .task_manager
+            .add_task("Plan week", TaskSection::Backlog);
+        panel
+            .task_manager
+            .add_task("Plan week!", TaskSection::Backlog);
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+        press(&mut panel, KeyCode::Char('M'));
+        press(&mut panel, KeyCode::Enter);
+        assert!(panel.confirm_overlay().is_some());
+        press(&mut panel, KeyCode::Char('n'));
+        assert_eq!(panel.task_manager.section_len(TaskSection::Backlog), 2);
+
+        press(&mut panel, KeyCode::Char('M'));
+        press(&mut panel, KeyCode::Enter);
+        press(&mut panel, KeyCode::Char('y'));
+        assert_eq!(panel.task_manager.section_len(TaskSection::Backlog), 1);
+        assert_eq!(
+            panel.take_message().as_deref(),
+            Some("No more duplicate tasks")
+        );
+    }
+
+    #[test]
+    fn test_restore_deleted_with_ctrl_z() {
+        let mut panel = TasksPanel::default();
+        panel.task_manager.add_task("Plan", TaskSection::Backlog);
+        panel.task_manager.delete_task(TaskSection::Backlog, 0);
+        assert!(panel.task_manager.backlog().is_empty());
+
+        panel.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('z'),
+            KeyModifiers::CONTROL,
+        )));
+        assert!(!panel.fold_pending);
+        assert_eq!(panel.task_manager.backlog()[0].text, "Plan");
+    }
+
+    #[test]
+    fn test_triage_stale() {
+        let mut panel = TasksPanel::default();
+        let today = Local::now().date_naive();
+        for (text, age) in [("Old", 45), ("New", 3)] {
+            panel
+                .task_manager
+                .add_task(text, TaskSection::Backlog)
+                .created_on = Some(today - Days::new(age));
+        }
+        panel
+            .task_manager
+            .add_task("Ancient", TaskSection::Current)
+            .created_on = Some(today - Days::new(90));
+        panel
+            .task_manager
+            .add_task("Undated", TaskSection::Backlog)
+            .created_on = None;
+        panel.focus.section = TaskSection::Current;
+
+        let press = |panel: &mut TasksPanel| {
+            KeyMap::handle(
+                panel,
+                &Event::Key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE)),
+            );
+        };
+        press(&mut panel);
+        assert_eq!(panel.focus.section, TaskSection::Backlog);
+        assert_eq!(panel.rows(TaskSection::Backlog), [0]);
+        assert!(panel.rows(TaskSection::Current).is_empty());
+
+        press(&mut panel);
+        assert_eq!(panel.rows(TaskSection::Backlog), [0, 1, 2]);
+
+        // Nothing is stale with staleness off
+        panel.config.stale_after_days = 0;
+        press(&mut panel);
+        assert_eq!(panel.filter.stale_before, None);
+        assert!(panel.pending_message.is_some());
+    }
+
+    #[test]
+    fn test_section_heights() {
+        assert_eq!(section_heights(20, [false; 3]), [7, 7, 6]);
+        // A folded Backlog keeps its title and border
+        assert_eq!(section_heights(20, [true, false, false]), [2, 9, 9]);
+        assert_eq!(section_heights(20, [true, false, true]), [2, 17, 1]);
+    }
+
+    #[test]
+    fn test_fold_section() {
+        let mut panel = TasksPanel::default();
+        panel.task_manager.add_task("Plan", TaskSection::Backlog);
+        panel.task_manager.add_task("Review", TaskSection::Backlog);
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+
+        press(&mut panel, KeyCode::Char('j'));
+        press(&mut panel, KeyCode::Char('z'));
+        press(&mut panel, KeyCode::Char('a'));
+        assert!(panel.collapsed[0]);
+        // Nothing hidden can be acted on
+        assert_eq!(panel.focused_index(), None);
+        press(&mut panel, KeyCode::Char('x'));
+        assert_eq!(panel.task_manager.backlog().len(), 2);
+
+        // Any other key after z leaves it folded
+        press(&mut panel, KeyCode::Char('z'));
+        press(&mut panel, KeyCode::Char('k'));
+        assert!(panel.collapsed[0]);
+
+        press(&mut panel, KeyCode::Char('z'));
+        press(&mut panel, KeyCode::Char('a'));
+        assert!(!panel.collapsed[0]);
+        assert_eq!(panel.focused_index(), Some(0));
+    }
+
+    #[test]
+    fn test_jump_to_number() {
+        let mut panel = TasksPanel::default();
+        for i in 1..=12 {
+            panel
+                .task_manager
+                .add_task(format!("Task {i}"), TaskSection::Backlog);
+        }
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+        };
+
+        press(&mut panel, KeyCode::Char(':'));
+        assert!(panel.is_searching());
+        for c in "40".chars() {
+            press(&mut panel, KeyCode::Char(c));
+        }
+        press(&mut panel, KeyCode::Backspace);
+        press(&mut panel, KeyCode::Enter);
+        assert_eq!(panel.focus.index, 3);
+
+        // Digits are left for focusing panels unless line numbers are shown
+        assert!(!press(&mut panel, KeyCode::Char('4')));
+        panel.config.line_numbers = true;
+        press(&mut panel, KeyCode::Char('7'));
+        press(&mut panel, KeyCode::Char('g'));
+        assert_eq!(panel.focus.index, 6);
+
+        // Only a count already started takes the digits that focus panels
+        assert!(!press(&mut panel, KeyCode::Char('1')));
+        press(&mut panel, KeyCode::Char('4'));
+        assert!(press(&mut panel, KeyCode::Char('1')));
+        press(&mut panel, KeyCode::Char('g'));
+        assert_eq!(panel.focus.index, 11);
+
+        // Past the end goes to the last task, and another key drops the count
+        for c in "99g".chars() {
+            press(&mut panel, KeyCode::Char(c));
+        }
+        assert_eq!(panel.focus.index, 11);
+        press(&mut panel, KeyCode::Char('2'));
+        press(&mut panel, KeyCode::Char('k'));
+        press(&mut panel, KeyCode::Char('g'));
+        assert_eq!(panel.focus.index, 0);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut panel = TasksPanel::default();
+        for text in ["Write report", "Plan week", "Review report"] {
+            panel.task_manager.add_task(text, TaskSection::Backlog);
+        }
+        panel
+            .task_manager
+            .add_task("Send REPORT", TaskSection::Completed);
+        let press = |panel: &mut TasksPanel, code| {
+            panel.handle(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+
+        // Filters live while typing, and keys are text rather than shortcuts
+        press(&mut panel, KeyCode::Char('/'));
+        for c in "repq".chars() {
+            press(&mut panel, KeyCode::Char(c));
+        }
+        assert_eq!(panel.row_count(TaskSection::Backlog), 0);
+        press(&mut panel, KeyCode::Backspace);
+        press(&mut panel, KeyCode::Enter);
+        assert!(!panel.is_searching());
+        assert_eq!(panel.filter.query.as_deref(), Some("rep"));
+        assert_eq!(panel.row_count(TaskSection::Backlog), 2);
+
+        // n and N step through the matches across sections, wrapping around
+        press(&mut panel, KeyCode::Char('n'));
+        assert_eq!(panel.focused_index(), Some(2));
+        press(&mut panel, KeyCode::Char('n'));
+        assert_eq!(panel.focus.section, TaskSection::Completed);
+        press(&mut panel, KeyCode::Char('n'));
+        assert_eq!(panel.focus.section, TaskSection::Backlog);
+        assert_eq!(panel.focused_index(), Some(0));
+        press(&mut panel, KeyCode::Char('N'));
+        assert_eq!(panel.focus.section, TaskSection::Completed);
+
+        // Esc clears the search
+        press(&mut panel, KeyCode::Esc);
+        assert!(!panel.filter.is_active());
+        assert_eq!(panel.row_count(TaskSection::Backlog), 3);
+    }
+
+    #[test]
+    fn test_edit_note() {
+        let mut panel = TasksPanel::default();
+        panel
+            .task_manager
+            .add_task("Fix parser", TaskSection::Backlog);
+        let press = |panel: &mut TasksPanel, code, modifiers| {
+            panel.handle(&Event::Key(KeyEvent::new(code, modifiers)));
+        };
+
+        press(&mut panel, KeyCode::Char('E'), KeyModifiers::SHIFT);
+        assert!(panel.note_overlay().is_some());
+        for c in "Fails  ".chars() {
+            press(&mut panel, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        press(&mut panel, KeyCode::Enter, KeyModifiers::NONE);
+        press(&mut panel, KeyCode::Enter, KeyModifiers::NONE);
+        press(&mut panel, KeyCode::Char('x'), KeyModifiers::NONE);
+        press(&mut panel, KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert!(panel.note_overlay().is_none());
+        // Blank lines and trailing spaces are dropped, as they would be from the file
+        assert_eq!(
+            panel.task_manager.section(TaskSection::Backlog)[0].note,
+            "Fails\nx"
+        );
+    }
+
     #[test]
     fn test_section_navigation() {
         let mut panel = TasksPanel::default();
@@ -745,6 +3203,7 @@ mod tests {
     #[test]
     fn test_delete_task_from_any_section() {
         let mut panel = TasksPanel::default();
+        panel.config.confirm_delete = false;
         panel
             .task_manager
             .add_task("Backlog 1".to_string(), TaskSection::Backlog);
@@ -788,4 +3247,18 @@ mod tests {
         assert_eq!(panel.task_manager.completed()[0].text, "Completed 2");
         assert_eq!(panel.focus.index, 0);
     }
+
+    #[test]
+    fn test_sync_without_file_when_disabled() {
+        let mut panel = TasksPanel::default();
+        panel.disable_file();
+        panel.handle(&Event::Key(KeyEvent::new(
+            KeyCode::Char('s'),
+            KeyModifiers::NONE,
+        )));
+
+        assert!(panel.sync_overlay.is_none());
+        assert!(panel.take_error().is_some());
+        assert!(!panel.task_manager.has_file_path());
+    }
 }
@@ -1,23 +1,100 @@
+use std::f64::consts::TAU;
+use std::time::Duration;
+
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{
+        canvas::{Canvas, Points},
+        Block, Borders, LineGauge, Paragraph, Wrap,
+    },
     Frame,
 };
 
 use super::util::panel_block;
+use crate::config::DisplayConfig;
+use crate::history::{timeline, SessionRecord};
 use crate::task::Task;
-use crate::timer::{SessionType, Timer};
+use crate::theme::Theme;
+use crate::timer::{SessionType, Timer, TimerMode};
 
 /// Timer panel displaying countdown, session type, and current task
 #[derive(Default)]
 pub struct TimerPanel {
     /// Animation frame counter
     tick_count: u32,
+    display: DisplayConfig,
+    view: TimerView,
+    digits: DigitCache,
+}
+
+/// What the block digits show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Readout {
+    Time {
+        minutes: u64,
+        seconds: u64,
+    },
+    /// Seconds alone, for the final minute
+    Seconds(u64),
+    /// Whole seconds and tenths, for short sessions
+    Tenths {
+        seconds: u64,
+        tenths: u32,
+    },
+}
+
+/// Block digit rows for the time last drawn, rebuilt only when the shown time changes
+#[derive(Default)]
+struct DigitCache {
+    key: Option<Readout>,
+    lines: Vec<String>,
+}
+
+impl DigitCache {
+    fn lines(&mut self, readout: Readout) -> &[String] {
+        if self.key != Some(readout) {
+            self.key = Some(readout);
+            self.lines = match readout {
+                Readout::Time { minutes, seconds } => render_time(minutes, seconds),
+                Readout::Seconds(seconds) => render_seconds(seconds),
+                Readout::Tenths { seconds, tenths } => render_tenths(seconds, tenths),
+            };
+        }
+        &self.lines
+    }
+}
+
+/// State the timer panel draws, gathered from the app each frame
+pub struct TimerStatus<'a> {
+    pub timer: &'a Timer,
+    pub active_task: Option<&'a Task>,
+    /// Today's finished sessions, drawn as a strip under the session label
+    pub today: &'a [SessionRecord],
+}
+
+/// How the remaining time is drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimerView {
+    /// Block digits
+    #[default]
+    Digits,
+    /// Kitchen-timer pie shrinking as time runs out
+    Pie,
+}
+
+impl TimerView {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Digits => "Digits",
+            Self::Pie => "Pie",
+        }
+    }
 }
 
-const TIMER_MIN_HEIGHT: u16 = 11; // digits + wave + blank + label + blank
+pub const TIMER_MIN_HEIGHT: u16 = 11; // digits + wave + blank + label + blank
 const BOTTOM_BORDER: u16 = 1; // Borders::TOP
 const BOTTOM_PAD: u16 = 2; // 1 row above + 1 row below text
 /// Minimum width needed to display block digits with 1 char padding on each side
@@ -25,6 +102,16 @@ const BOTTOM_PAD: u16 = 2; // 1 row above + 1 row below text
 pub const TIMER_MIN_WIDTH: u16 = 38;
 const DIGIT_HEIGHT: usize = 5;
 const DIGIT_SPACING: u16 = 2;
+/// Widest the strip of today's sessions is drawn
+const TIMELINE_MAX_WIDTH: u16 = 48;
+/// Animation frames per second, set by the event loop's 100 ms poll
+const FRAMES_PER_SECOND: u32 = 10;
+/// 4-7-8 breathing: in for 4 seconds, hold for 7, out for 8
+const BREATH_PHASES: [(&str, u32); 3] = [("Breathe in", 4), ("Hold", 7), ("Breathe out", 8)];
+/// Sessions shorter than this show tenths of a second when enabled
+const TENTHS_BELOW: Duration = Duration::from_mins(2);
+/// Rows of the breathing section: border, padding and the bar
+const BREATH_HEIGHT: u16 = BOTTOM_BORDER + BOTTOM_PAD + 1;
 
 const DIGITS: [[&str; 5]; 10] = [
     ["██████", "██  ██", "██  ██", "██  ██", "██████"],
@@ -41,23 +128,59 @@ const DIGITS: [[&str; 5]; 10] = [
 
 const COLON: [&str; 5] = ["  ", "██", "  ", "██", "  "];
 
+/// Half-height digits sitting on the baseline of the block digits, for tenths of a second
+const SMALL_DIGITS: [[&str; 5]; 10] = [
+    ["   ", "   ", "▄▄▄", "█ █", "█▄█"],
+    ["   ", "   ", " ▄ ", " █ ", " █ "],
+    ["   ", "   ", "▄▄▄", "▄▄█", "█▄▄"],
+    ["   ", "   ", "▄▄▄", "▄▄█", "▄▄█"],
+    ["   ", "   ", "▄ ▄", "█▄█", "  █"],
+    ["   ", "   ", "▄▄▄", "█▄▄", "▄▄█"],
+    ["   ", "   ", "▄▄▄", "█▄▄", "█▄█"],
+    ["   ", "   ", "▄▄▄", "  █", "  █"],
+    ["   ", "   ", "▄▄▄", "█▄█", "█▄█"],
+    ["   ", "   ", "▄▄▄", "█▄█", "▄▄█"],
+];
+
+const DECIMAL_POINT: [&str; 5] = [" ", " ", " ", " ", "▄"];
+
 impl TimerPanel {
     pub fn render(
-        &self,
+        &mut self,
         frame: &mut Frame,
         area: Rect,
         focused: bool,
-        timer: &Timer,
-        active_task: Option<&Task>,
+        status: &TimerStatus,
+        theme: &Theme,
     ) {
-        let block = panel_block(" Timer ", focused);
+        let TimerStatus {
+            timer,
+            active_task,
+            today,
+        } = *status;
+        let block = panel_block(" Timer ", focused, theme);
 
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
-        // In break mode, no bottom section — timer gets everything
+        // In break mode, the bottom section paces breathing while the break runs and there is
+        // room for it; otherwise the timer gets everything
         if timer.session_type() != SessionType::Work {
-            self.render_timer_display(frame, inner, timer);
+            if self.display.breathing
+                && timer.is_running()
+                && inner.height >= TIMER_MIN_HEIGHT + BREATH_HEIGHT
+            {
+                let chunks = Layout::vertical([
+                    Constraint::Length(inner.height - BREATH_HEIGHT),
+                    Constraint::Length(BREATH_HEIGHT),
+                ])
+                .split(inner);
+                self.render_timer_display(frame, chunks[0], timer, today, theme);
+                let color = theme.session_color(timer.session_type());
+                self.render_breathing(frame, chunks[1], color);
+            } else {
+                self.render_timer_display(frame, inner, timer, today, theme);
+            }
             return;
         }
 
@@ -77,7 +200,7 @@ impl TimerPanel {
         // Need at least TIMER_MIN_HEIGHT for timer + bottom_total for bottom
         if h < TIMER_MIN_HEIGHT + bottom_total {
             // Not enough room — timer gets everything
-            self.render_timer_display(frame, inner, timer);
+            self.render_timer_display(frame, inner, timer, today, theme);
         } else {
             let timer_h = h - bottom_total;
             let chunks = Layout::vertical([
@@ -85,58 +208,171 @@ impl TimerPanel {
                 Constraint::Length(bottom_total),
             ])
             .split(inner);
-            self.render_timer_display(frame, chunks[0], timer);
+            self.render_timer_display(frame, chunks[0], timer, today, theme);
             Self::render_current_task(frame, chunks[1], active_task);
         }
     }
 
-    /// Increment the frame counter for the wave animation
+    pub fn set_display(&mut self, display: DisplayConfig) {
+        self.display = display;
+    }
+
+    /// Switch between digits and the pie, returning the new view
+    pub const fn toggle_view(&mut self) -> TimerView {
+        self.view = match self.view {
+            TimerView::Digits => TimerView::Pie,
+            TimerView::Pie => TimerView::Digits,
+        };
+        self.view
+    }
+
+    /// Timer on a single line, for terminals too small for the panel
+    pub fn render_compact(frame: &mut Frame, area: Rect, timer: &Timer, theme: &Theme) {
+        let color = if timer.is_overtime() {
+            Color::Yellow
+        } else {
+            theme.session_color(timer.session_type())
+        };
+        let line = Paragraph::new(Span::styled(
+            compact_line(timer),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center);
+        frame.render_widget(line, area);
+    }
+
+    /// Increment the frame counter for the wave and breathing animations
     pub fn next_animation_frame(&mut self) {
         self.tick_count = self.tick_count.wrapping_add(1);
     }
 
-    fn render_timer_display(&self, frame: &mut Frame, area: Rect, timer: &Timer) {
-        let time_lines = render_time(timer.minutes(), timer.seconds());
-        let session_color = session_color(timer.session_type());
+    fn readout(&self, timer: &Timer, urgent: bool) -> Readout {
+        // Counting down to the next session shows the seconds until it starts
+        if let Some(left) = timer.grace_remaining() {
+            let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+            return if secs < 60 {
+                Readout::Seconds(secs)
+            } else {
+                Readout::Time {
+                    minutes: secs / 60,
+                    seconds: secs % 60,
+                }
+            };
+        }
+        // Overtime counts up from zero in place of the remaining time
+        if timer.is_overtime() {
+            let secs = timer.overtime().as_secs();
+            Readout::Time {
+                minutes: secs / 60,
+                seconds: secs % 60,
+            }
+        } else if self.display.tenths && timer.total() < TENTHS_BELOW {
+            Readout::Tenths {
+                seconds: timer.remaining().as_secs(),
+                tenths: timer.tenths(),
+            }
+        } else if urgent {
+            Readout::Seconds(timer.seconds())
+        } else {
+            Readout::Time {
+                minutes: timer.minutes(),
+                seconds: timer.seconds(),
+            }
+        }
+    }
 
-        let wave = if timer.is_running() {
-            render_wave(Some(wave_position(self.tick_count)))
+    fn render_timer_display(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        timer: &Timer,
+        today: &[SessionRecord],
+        theme: &Theme,
+    ) {
+        let grace = timer.grace_remaining().is_some();
+        let session_color = if grace {
+            theme.get_ready
+        } else {
+            theme.session_color(timer.session_type())
+        };
+        // A flowtime work session counts up the whole way, so is not drawn as overtime
+        let flow = timer.is_overtime() && timer.mode() == TimerMode::Flowtime;
+        let overtime = timer.is_overtime() && !flow;
+        let urgent = self.display.final_minute_emphasis
+            && timer.minutes() == 0
+            && !timer.is_idle()
+            && !timer.is_overtime();
+        let digit_color = if overtime {
+            Color::Yellow
+        } else if urgent || grace {
+            session_color
         } else {
-            render_wave(None)
+            Color::White
+        };
+        let digit_style = Style::default()
+            .fg(digit_color)
+            .add_modifier(Modifier::BOLD);
+
+        let wave = if timer.is_running() || timer.is_overtime() || grace {
+            // Skipping every other dot doubles the wave speed
+            let frame = if urgent {
+                self.tick_count.wrapping_mul(2)
+            } else {
+                self.tick_count
+            };
+            WAVE_FRAMES[wave_position(frame)]
+        } else {
+            WAVE_IDLE
         };
 
-        let session_str = match timer.session_type() {
-            SessionType::Work => "WORK",
-            SessionType::ShortBreak => "SHORT BREAK",
-            SessionType::LongBreak => "LONG BREAK",
+        let session_str = if grace {
+            "GET READY"
+        } else {
+            session_label(timer.session_type(), overtime, flow)
         };
 
-        // Fixed top: blank + 5 digit lines + blank = 7 lines
+        // Fixed top: blank + 5 digit lines + end time (blank when there is none) = 7 lines
+        let view = self.view;
+        let time_lines = self.digits.lines(self.readout(timer, urgent));
         let mut digits: Vec<Line> = vec![Line::from("")];
         for line in time_lines {
-            digits.push(Line::from(Span::styled(
-                line,
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            )));
+            digits.push(Line::from(Span::styled(line.as_str(), digit_style)));
         }
-        digits.push(Line::from(""));
-
-        // Bottom part: wave + blank + label = 3 lines, centered in remaining space
-        let below: Vec<Line> = vec![
+        digits.push(timer.projected_end().map_or_else(Line::default, |end| {
+            Line::from(Span::styled(
+                format!("ends at {}", end.format("%H:%M")),
+                Style::default().fg(Color::DarkGray),
+            ))
+        }));
+
+        // Bottom part: wave + blank + label = 3 lines, centered in remaining space below the
+        // progress gauge when it is drawn
+        let mut label = vec![Span::styled(
+            session_str,
+            Style::default().fg(session_color),
+        )];
+        let sequence = timer.sequence();
+        if usize::from(area.width) >= session_str.len() + sequence.len() + 6 {
+            label.push(Span::raw("  "));
+            label.extend(render_sequence(&sequence, timer.step(), theme));
+        }
+        let mut below: Vec<Line> = vec![
             Line::from(Span::styled(wave, Style::default().fg(session_color))),
             Line::from(""),
-            Line::from(Span::styled(
-                session_str,
-                Style::default().fg(session_color),
-            )),
+            Line::from(label),
         ];
 
         let remaining_h = area
             .height
             .saturating_sub(DIGIT_HEIGHT as u16 + DIGIT_SPACING);
 
+        // Blank + today's strip, when there is room for it
+        let timeline_width = area.width.saturating_sub(4).min(TIMELINE_MAX_WIDTH);
+        if remaining_h >= 5 && !today.is_empty() {
+            below.push(Line::from(""));
+            below.push(render_timeline(today, timeline_width, theme));
+        }
+
         if remaining_h >= 3 {
             // Split: digits at top, wave+label centered in remaining space
             let chunks = Layout::vertical([
@@ -145,11 +381,19 @@ impl TimerPanel {
             ])
             .split(area);
 
-            let digits_para = Paragraph::new(digits).alignment(Alignment::Center);
-            frame.render_widget(digits_para, chunks[0]);
+            render_face(frame, chunks[0], view, digits, timer, session_color);
+
+            let gauge_fits = remaining_h > below.len() as u16 + 1;
+            let below_area = if self.display.progress_gauge && gauge_fits && !timer.is_overtime() {
+                let ratio = timer.fraction_elapsed();
+                render_gauge(frame, chunks[1], timeline_width, ratio, session_color)
+            } else {
+                chunks[1]
+            };
+            let remaining_h = below_area.height;
 
-            // Center the 3 lines of wave+label within the remaining area
-            let pad_top = (remaining_h.saturating_sub(3)) / 2;
+            // Center the wave+label within the area left under the gauge
+            let pad_top = (remaining_h.saturating_sub(below.len() as u16)) / 2;
             let mut below_content: Vec<Line> = Vec::new();
             for _ in 0..pad_top {
                 below_content.push(Line::from(""));
@@ -157,14 +401,41 @@ impl TimerPanel {
             below_content.extend(below);
 
             let below_para = Paragraph::new(below_content).alignment(Alignment::Center);
-            frame.render_widget(below_para, chunks[1]);
+            frame.render_widget(below_para, below_area);
         } else {
             // Not enough room — just render digits
-            let digits_para = Paragraph::new(digits).alignment(Alignment::Center);
-            frame.render_widget(digits_para, area);
+            render_face(frame, area, view, digits, timer, session_color);
         }
     }
 
+    fn render_breathing(&self, frame: &mut Frame, area: Rect, color: Color) {
+        let (phase, seconds_left, fullness) = breath_at(self.tick_count);
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(format!(" {phase} · {seconds_left} "));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let max_width = f64::from(inner.width.saturating_sub(4));
+        // Fullness is between 0.0 and 1.0, so the width is never negative
+        #[allow(clippy::cast_sign_loss)]
+        let width = (max_width * fullness).round() as usize;
+        let bar = Paragraph::new(Span::styled(
+            "━".repeat(width.max(1)),
+            Style::default().fg(color),
+        ))
+        .alignment(Alignment::Center);
+        frame.render_widget(
+            bar,
+            Rect {
+                y: inner.y + 1,
+                height: 1,
+                ..inner
+            },
+        );
+    }
+
     fn render_current_task(frame: &mut Frame, area: Rect, active_task: Option<&Task>) {
         let block = Block::default()
             .borders(Borders::TOP)
@@ -211,12 +482,45 @@ impl TimerPanel {
     }
 }
 
-const fn session_color(session_type: SessionType) -> Color {
-    match session_type {
-        SessionType::Work => Color::Red,
-        SessionType::ShortBreak => Color::Green,
-        SessionType::LongBreak => Color::Blue,
+/// Draw the digits, or the pie in their place
+fn render_face(
+    frame: &mut Frame,
+    area: Rect,
+    view: TimerView,
+    digits: Vec<Line>,
+    timer: &Timer,
+    color: Color,
+) {
+    if view == TimerView::Digits {
+        frame.render_widget(Paragraph::new(digits).alignment(Alignment::Center), area);
+        return;
     }
+
+    // Braille cells are 2x4 dots and about twice as tall as wide, so 2 columns per row is round
+    let height = (DIGIT_HEIGHT as u16).min(area.height.saturating_sub(2));
+    let width = (height * 2).min(area.width);
+    let pie_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let (filled, empty) = pie_points(width, height, timer.fraction_remaining());
+    let canvas = Canvas::default()
+        .marker(Marker::Braille)
+        .x_bounds([-1.0, 1.0])
+        .y_bounds([-1.0, 1.0])
+        .paint(move |ctx| {
+            ctx.draw(&Points {
+                coords: &empty,
+                color: Color::DarkGray,
+            });
+            ctx.draw(&Points {
+                coords: &filled,
+                color,
+            });
+        });
+    frame.render_widget(canvas, pie_area);
 }
 
 /// Count how many lines the text will occupy when word-wrapped at given width
@@ -273,31 +577,180 @@ fn render_time(minutes: u64, seconds: u64) -> Vec<String> {
         .collect()
 }
 
-fn render_wave(position: Option<usize>) -> String {
-    const LARGE: char = '●';
-    const SMALL: char = '·';
-    const DOT_SPACING: &str = " ";
-
-    position.map_or_else(
-        || {
-            [SMALL; 5]
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(DOT_SPACING)
-        },
-        |pos| {
-            (0..5)
-                .map(|i| if i == pos { LARGE } else { SMALL })
-                .collect::<Vec<_>>()
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(DOT_SPACING)
-        },
+type Dots = Vec<(f64, f64)>;
+
+/// Braille dot centers inside the unit circle, split into the remaining wedge (clockwise
+/// from 12 o'clock) and the elapsed rest
+fn pie_points(width: u16, height: u16, fraction: f64) -> (Dots, Dots) {
+    let (dots_x, dots_y) = (u32::from(width) * 2, u32::from(height) * 4);
+    let mut filled = Vec::new();
+    let mut empty = Vec::new();
+    for ix in 0..dots_x {
+        for iy in 0..dots_y {
+            // Dot center mapped onto -1.0..1.0
+            let x = (f64::from(ix) + 0.5).mul_add(2.0 / f64::from(dots_x), -1.0);
+            let y = (f64::from(iy) + 0.5).mul_add(2.0 / f64::from(dots_y), -1.0);
+            if x.hypot(y) > 1.0 {
+                continue;
+            }
+            let turn = x.atan2(y).rem_euclid(TAU) / TAU;
+            if turn < fraction {
+                filled.push((x, y));
+            } else {
+                empty.push((x, y));
+            }
+        }
+    }
+    (filled, empty)
+}
+
+/// Today's sessions in order: work and breaks in their session colors, gaps dimmed
+fn render_timeline(today: &[SessionRecord], width: u16, theme: &Theme) -> Line<'static> {
+    let spans: Vec<Span> = timeline(today, width)
+        .into_iter()
+        .map(|slot| {
+            slot.map_or_else(
+                || Span::styled("▁", Style::default().fg(Color::DarkGray)),
+                |session| Span::styled("▆", Style::default().fg(theme.session_color(session))),
+            )
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// Place in the session cycle: finished steps filled, the current one ringed, the rest dimmed
+fn render_sequence(sequence: &[SessionType], step: usize, theme: &Theme) -> Vec<Span<'static>> {
+    sequence
+        .iter()
+        .enumerate()
+        .map(|(i, &session)| {
+            let color = theme.session_color(session);
+            match i.cmp(&step) {
+                std::cmp::Ordering::Less => Span::styled("●", Style::default().fg(color)),
+                std::cmp::Ordering::Equal => Span::styled("◉", Style::default().fg(color)),
+                std::cmp::Ordering::Greater => {
+                    Span::styled("○", Style::default().fg(Color::DarkGray))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Seconds alone as two block digits, for the final minute
+fn render_seconds(seconds: u64) -> Vec<String> {
+    let d1 = digit_lines((seconds / 10) as u8);
+    let d2 = digit_lines((seconds % 10) as u8);
+    let spacing = " ".repeat(DIGIT_SPACING as usize);
+
+    (0..DIGIT_HEIGHT)
+        .map(|i| format!("{}{}{}", d1[i], spacing, d2[i]))
+        .collect()
+}
+
+/// Remaining seconds in block digits with tenths in small ones, for sessions under
+/// `TENTHS_BELOW`
+fn render_tenths(seconds: u64, tenths: u32) -> Vec<String> {
+    let digits: Vec<[&str; 5]> = seconds
+        .to_string()
+        .bytes()
+        .map(|b| digit_lines(b - b'0'))
+        .collect();
+    let small = SMALL_DIGITS[(tenths % 10) as usize];
+    let spacing = " ".repeat(DIGIT_SPACING as usize);
+
+    (0..DIGIT_HEIGHT)
+        .map(|i| {
+            let whole: Vec<&str> = digits.iter().map(|d| d[i]).collect();
+            format!("{} {} {}", whole.join(&spacing), DECIMAL_POINT[i], small[i])
+        })
+        .collect()
+}
+
+/// Wave dots with the large dot at each position, and all small while not running
+const WAVE_FRAMES: [&str; 5] = [
+    "● · · · ·",
+    "· ● · · ·",
+    "· · ● · ·",
+    "· · · ● ·",
+    "· · · · ●",
+];
+const WAVE_IDLE: &str = "· · · · ·";
+
+/// Session name under the digits
+const fn session_label(session: SessionType, overtime: bool, flow: bool) -> &'static str {
+    match session {
+        SessionType::Work if overtime => "WORK · OVERTIME",
+        SessionType::Work if flow => "FLOW",
+        SessionType::Work => "WORK",
+        SessionType::ShortBreak => "SHORT BREAK",
+        SessionType::LongBreak => "LONG BREAK",
+    }
+}
+
+/// Time, session and state in plain text, e.g. `25:00 WORK ▶`
+fn compact_line(timer: &Timer) -> String {
+    let time = if timer.is_overtime() {
+        let secs = timer.overtime().as_secs();
+        format!("+{:02}:{:02}", secs / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", timer.minutes(), timer.seconds())
+    };
+    let state = if timer.is_paused() {
+        "⏸"
+    } else if timer.is_idle() {
+        "■"
+    } else {
+        "▶"
+    };
+    format!(
+        "{time} {} {state}",
+        timer.session_type().label().to_uppercase()
     )
 }
 
+/// Share of the session elapsed as a thin bar with a percentage, centered on the top row of
+/// `area`; returns the rows below it
+fn render_gauge(frame: &mut Frame, area: Rect, width: u16, ratio: f64, color: Color) -> Rect {
+    let width = width.clamp(1, area.width.max(1));
+    let gauge_area = Rect::new(area.x + (area.width - width) / 2, area.y, width, 1);
+    let gauge = LineGauge::default()
+        .filled_style(Style::default().fg(color))
+        .unfilled_style(Style::default().fg(Color::DarkGray))
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(Span::styled(
+            format!("{:>3.0}%", ratio * 100.0),
+            Style::default().fg(Color::DarkGray),
+        ));
+    frame.render_widget(gauge, gauge_area);
+    Rect {
+        y: area.y + 1,
+        height: area.height.saturating_sub(1),
+        ..area
+    }
+}
+
+/// Breathing phase at an animation frame, the whole seconds left in it, and how full the
+/// lungs are, from 0.0 to 1.0
+fn breath_at(tick_count: u32) -> (&'static str, u32, f64) {
+    let cycle: u32 = BREATH_PHASES.iter().map(|(_, secs)| secs).sum();
+    let mut frame = tick_count % (cycle * FRAMES_PER_SECOND);
+    for (i, &(phase, secs)) in BREATH_PHASES.iter().enumerate() {
+        let frames = secs * FRAMES_PER_SECOND;
+        if frame < frames {
+            let seconds_left = (frames - frame).div_ceil(FRAMES_PER_SECOND);
+            let progress = f64::from(frame) / f64::from(frames);
+            let fullness = match i {
+                0 => progress,
+                1 => 1.0,
+                _ => 1.0 - progress,
+            };
+            return (phase, seconds_left, fullness);
+        }
+        frame -= frames;
+    }
+    (BREATH_PHASES[0].0, BREATH_PHASES[0].1, 0.0)
+}
+
 /// Calculate wave position from tick count (bounces back and forth)
 const fn wave_position(tick_count: u32) -> usize {
     let tick = (tick_count % 8) as usize;
@@ -307,3 +760,59 @@ const fn wave_position(tick_count: u32) -> usize {
         8 - tick
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_line() {
+        let mut timer = Timer::default();
+        assert_eq!(compact_line(&timer), "25:00 WORK ■");
+        timer.start();
+        assert_eq!(compact_line(&timer), "25:00 WORK ▶");
+        timer.pause();
+        // Pausing counts the moment it ran, so only check the state
+        assert!(compact_line(&timer).ends_with(" WORK ⏸"));
+        timer.reset();
+        timer.skip_session();
+        assert_eq!(compact_line(&timer), "05:00 SHORT BREAK ■");
+    }
+
+    #[test]
+    fn test_render_tenths() {
+        assert_eq!(
+            render_tenths(7, 3),
+            [
+                "██████      ",
+                "    ██      ",
+                "    ██   ▄▄▄",
+                "    ██   ▄▄█",
+                "    ██ ▄ ▄▄█",
+            ]
+        );
+        assert_eq!(render_tenths(105, 0)[0].chars().count(), 6 * 3 + 2 * 2 + 6);
+    }
+
+    #[test]
+    fn test_breath_at() {
+        assert_eq!(breath_at(0), ("Breathe in", 4, 0.0));
+        assert_eq!(breath_at(20), ("Breathe in", 2, 0.5));
+        assert_eq!(breath_at(40), ("Hold", 7, 1.0));
+        assert_eq!(breath_at(150), ("Breathe out", 4, 0.5));
+        // Loops every 19 seconds
+        assert_eq!(breath_at(190), breath_at(0));
+    }
+
+    #[test]
+    fn test_pie_points() {
+        let (filled, empty) = pie_points(10, 5, 0.25);
+        assert!(filled.iter().chain(&empty).all(|(x, y)| x.hypot(*y) <= 1.0));
+        // A quarter left is the wedge clockwise from 12 o'clock to 3, with y up
+        assert_eq!(filled.len() * 4, filled.len() + empty.len());
+        assert!(filled.iter().all(|(x, y)| *x > 0.0 && *y > 0.0));
+
+        assert!(pie_points(10, 5, 1.0).1.is_empty());
+        assert!(pie_points(10, 5, 0.0).0.is_empty());
+    }
+}
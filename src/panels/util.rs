@@ -3,9 +3,13 @@ use ratatui::{
     widgets::{Block, Borders},
 };
 
-pub fn panel_block(title: &str, focused: bool) -> Block<'_> {
+use unicode_width::UnicodeWidthStr;
+
+use crate::theme::Theme;
+
+pub fn panel_block<'a>(title: &'a str, focused: bool, theme: &Theme) -> Block<'a> {
     let color = if focused {
-        Color::Cyan
+        theme.accent
     } else {
         Color::DarkGray
     };
@@ -19,4 +23,46 @@ pub fn panel_block(title: &str, focused: bool) -> Block<'_> {
 pub enum PanelId {
     Timer,
     Tasks,
+    History,
+}
+
+impl PanelId {
+    /// Panel focused by a number key, numbered left to right
+    pub const fn from_number(n: char) -> Option<Self> {
+        match n {
+            '1' => Some(Self::Timer),
+            '2' => Some(Self::Tasks),
+            '3' => Some(Self::History),
+            _ => None,
+        }
+    }
+}
+
+/// Rows kept visible between the selection and the edge of a scrolling list
+pub const SCROLL_PADDING: usize = 2;
+
+/// Shorten text at a word boundary to fit `max_width` terminal columns, ending in `...`
+pub fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width < 3 {
+        return ".".repeat(max_width);
+    }
+    let limit = max_width - 3; // room for "..."
+    let mut result = String::new();
+    for word in text.split_whitespace() {
+        if result.is_empty() {
+            if word.width() > limit {
+                return "...".to_string();
+            }
+            result = word.to_string();
+        } else if result.width() + 1 + word.width() <= limit {
+            result.push(' ');
+            result.push_str(word);
+        } else {
+            break;
+        }
+    }
+    format!("{result}...")
 }
@@ -0,0 +1,206 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::timer::{SessionType, Timer};
+
+/// The session in progress, saved so it can be picked up again if the app dies.
+///
+/// Only changes when the session is started, paused or adjusted, not on every tick.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// `SessionType::name` of the session
+    pub session: String,
+    /// Planned length including the time already run, in seconds
+    pub length_secs: u64,
+    /// Time left while paused, in seconds
+    pub remaining_secs: u64,
+    /// Unix time a running session is due to end, None while paused
+    pub ends_at: Option<i64>,
+    pub task: Option<String>,
+}
+
+impl SessionSnapshot {
+    /// Snapshot of a running or paused session; None when idle or counting overtime
+    pub fn of(timer: &Timer, task: Option<&str>) -> Option<Self> {
+        if !(timer.is_running() || timer.is_paused()) {
+            return None;
+        }
+        let ends_at = if timer.is_running() {
+            Some(timer.projected_end()?.timestamp())
+        } else {
+            None
+        };
+        let remaining = timer.remaining().as_secs();
+        Some(Self {
+            session: timer.session_type().name().to_string(),
+            // Summed first, as the split between the two moves on every tick
+            length_secs: (timer.elapsed() + timer.remaining()).as_secs(),
+            remaining_secs: if ends_at.is_some() { 0 } else { remaining },
+            ends_at,
+            task: task.map(String::from),
+        })
+    }
+
+    pub fn session_type(&self) -> SessionType {
+        SessionType::from_name(&self.session).unwrap_or(SessionType::Work)
+    }
+
+    pub const fn length(&self) -> Duration {
+        Duration::from_secs(self.length_secs)
+    }
+
+    /// Time left at `now`, zero if a running session would already have finished
+    pub fn remaining(&self, now: DateTime<Local>) -> Duration {
+        let secs = self.ends_at.map_or(self.remaining_secs, |end| {
+            u64::try_from(end - now.timestamp()).unwrap_or(0)
+        });
+        Duration::from_secs(secs).min(self.length())
+    }
+
+    /// Whether `other` is the same session, allowing for the end drifting by a second
+    /// as it is worked out again from the clock on each tick
+    fn same_as(&self, other: &Self) -> bool {
+        let ends_close = match (self.ends_at, other.ends_at) {
+            (Some(a), Some(b)) => (a - b).abs() <= 1,
+            (a, b) => a == b,
+        };
+        ends_close
+            && Self {
+                ends_at: other.ends_at,
+                ..self.clone()
+            } == *other
+    }
+}
+
+/// `session.toml` in the cache directory, holding the session in progress.
+///
+/// It is removed when the session ends and when the app quits, so one found at startup
+/// belongs to a session the app died during.
+#[derive(Default)]
+pub struct SessionFile {
+    path: Option<PathBuf>,
+    saved: Option<SessionSnapshot>,
+}
+
+impl SessionFile {
+    /// Load the file, which holds the interrupted session if there is one
+    pub fn load(path: PathBuf) -> (Self, Option<String>) {
+        let result = fs::read_to_string(&path).and_then(|content| {
+            toml::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        });
+        let (saved, error) = match result {
+            Ok(snapshot) => (Some(snapshot), None),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (None, None),
+            Err(e) => (
+                None,
+                Some(format!("Failed to load interrupted session: {e}")),
+            ),
+        };
+        (
+            Self {
+                path: Some(path),
+                saved,
+            },
+            error,
+        )
+    }
+
+    /// Session last saved, which after loading is the interrupted one
+    pub const fn saved(&self) -> Option<&SessionSnapshot> {
+        self.saved.as_ref()
+    }
+
+    /// Save the session in progress, or remove the file when there is none
+    pub fn update(&mut self, snapshot: Option<SessionSnapshot>) -> Result<(), io::Error> {
+        let unchanged = match (&self.saved, &snapshot) {
+            (Some(saved), Some(snapshot)) => saved.same_as(snapshot),
+            (saved, snapshot) => saved == snapshot,
+        };
+        if unchanged {
+            return Ok(());
+        }
+        // Remember it even if writing fails, so a broken cache dir is reported once
+        self.saved.clone_from(&snapshot);
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        match snapshot {
+            Some(snapshot) => {
+                let content = toml::to_string(&snapshot)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(path, content)
+            }
+            None => match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_survives_restart() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("cache").join("session.toml");
+
+        let mut timer = Timer::default();
+        assert_eq!(SessionSnapshot::of(&timer, None), None);
+        timer.start();
+        let snapshot = SessionSnapshot::of(&timer, Some("Write report"));
+        assert!(snapshot.is_some());
+
+        let (mut file, error) = SessionFile::load(path.clone());
+        assert!(error.is_none());
+        assert!(file.saved().is_none());
+        file.update(snapshot.clone())?;
+
+        let (reloaded, _) = SessionFile::load(path.clone());
+        let saved = reloaded.saved().cloned();
+        assert_eq!(saved, snapshot);
+        let remaining = saved.map(|s| s.remaining(Local::now())).unwrap_or_default();
+        assert!(remaining > Duration::from_mins(24) && remaining <= Duration::from_mins(25));
+
+        file.update(None)?;
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unchanged_session_not_rewritten() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("session.toml");
+        let (mut file, _) = SessionFile::load(path.clone());
+
+        let mut timer = Timer::default();
+        timer.start();
+        let snapshot = SessionSnapshot::of(&timer, None);
+        file.update(snapshot.clone())?;
+        fs::remove_file(&path)?;
+
+        // The end is worked out from the clock, so it can land on the next second
+        let drifted = snapshot.map(|s| SessionSnapshot {
+            ends_at: s.ends_at.map(|end| end + 1),
+            ..s
+        });
+        file.update(drifted)?;
+        assert!(!path.exists());
+
+        timer.pause();
+        file.update(SessionSnapshot::of(&timer, None))?;
+        assert!(path.exists());
+        Ok(())
+    }
+}
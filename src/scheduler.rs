@@ -0,0 +1,104 @@
+use chrono::{Days, NaiveDateTime};
+
+use crate::config::ScheduleEntry;
+
+/// Starts sessions at set times of day, checked on every app tick
+#[derive(Default)]
+pub struct Scheduler {
+    /// Daily entries from the config
+    daily: Vec<ScheduleEntry>,
+    /// Entries added in the app, dropped once they come due
+    once: Vec<ScheduleEntry>,
+    last_check: Option<NaiveDateTime>,
+}
+
+impl Scheduler {
+    pub fn new(daily: Vec<ScheduleEntry>) -> Self {
+        Self {
+            daily,
+            ..Self::default()
+        }
+    }
+
+    pub fn set_daily(&mut self, daily: Vec<ScheduleEntry>) {
+        self.daily = daily;
+    }
+
+    /// Schedule a session for the next time the clock shows `entry.at`
+    pub fn add_once(&mut self, entry: ScheduleEntry) {
+        self.once.push(entry);
+    }
+
+    /// The entry that comes due first after `now`, with when
+    pub fn next(&self, now: NaiveDateTime) -> Option<(&ScheduleEntry, NaiveDateTime)> {
+        self.daily
+            .iter()
+            .chain(&self.once)
+            .filter_map(|entry| Some((entry, due_after(entry, now)?)))
+            .min_by_key(|(_, due)| *due)
+    }
+
+    /// Entries that came due since the last call; the first call only starts the clock
+    pub fn tick(&mut self, now: NaiveDateTime) -> Vec<ScheduleEntry> {
+        let Some(last) = self.last_check.replace(now) else {
+            return Vec::new();
+        };
+        let is_due = |entry: &ScheduleEntry| due_after(entry, last).is_some_and(|due| due <= now);
+        let mut due: Vec<ScheduleEntry> =
+            self.daily.iter().filter(|e| is_due(e)).cloned().collect();
+        let (fired, waiting): (Vec<_>, Vec<_>) = self.once.drain(..).partition(|e| is_due(e));
+        self.once = waiting;
+        due.extend(fired);
+        due
+    }
+}
+
+/// First time after `time` that the clock shows `entry.at`
+fn due_after(entry: &ScheduleEntry, time: NaiveDateTime) -> Option<NaiveDateTime> {
+    let today = time.date().and_time(entry.at);
+    if today > time {
+        Some(today)
+    } else {
+        today.checked_add_days(Days::new(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::SessionType;
+    use chrono::{NaiveDate, NaiveTime};
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, 1)
+            .and_then(|d| d.and_hms_opt(h, m, 0))
+            .unwrap_or_default()
+    }
+
+    fn entry(h: u32, m: u32) -> ScheduleEntry {
+        ScheduleEntry {
+            at: NaiveTime::from_hms_opt(h, m, 0).unwrap_or_default(),
+            session: SessionType::Work,
+            auto_start: false,
+        }
+    }
+
+    #[test]
+    fn test_entries_come_due() {
+        let mut scheduler = Scheduler::new(vec![entry(9, 0), entry(14, 0)]);
+        scheduler.add_once(entry(10, 30));
+        assert!(scheduler.tick(at(8, 59)).is_empty());
+        assert_eq!(scheduler.next(at(8, 59)).map(|n| n.1), Some(at(9, 0)));
+
+        assert_eq!(scheduler.tick(at(9, 0)), [entry(9, 0)]);
+        assert!(scheduler.tick(at(9, 1)).is_empty());
+        assert_eq!(scheduler.next(at(9, 1)).map(|n| n.1), Some(at(10, 30)));
+
+        // A gap in ticks still catches everything due during it, one-off entries only once
+        assert_eq!(scheduler.tick(at(15, 0)), [entry(14, 0), entry(10, 30)]);
+        assert_eq!(
+            scheduler.next(at(15, 0)).map(|n| n.1),
+            at(9, 0).checked_add_days(Days::new(1))
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use mlua::{Function, Lua, Table};
+
+use super::{ScriptAction, ScriptEvent, ScriptState};
+use crate::config::config_dir;
+use crate::task::TaskSection;
+
+/// Hook and key-binding registries live on the Lua side; Rust only calls `_emit` and `_key`.
+const PRELUDE: &str = r"
+local hooks, binds = {}, {}
+pomo = pomo or {}
+
+function pomo.on(event, fn)
+    hooks[event] = hooks[event] or {}
+    table.insert(hooks[event], fn)
+end
+
+function pomo.bind(key, fn)
+    binds[key] = fn
+end
+
+function pomo._emit(event, ...)
+    for _, fn in ipairs(hooks[event] or {}) do
+        fn(...)
+    end
+end
+
+function pomo._key(key)
+    local fn = binds[key]
+    if fn then
+        fn()
+        return true
+    end
+    return false
+end
+";
+
+/// Embedded Lua runtime running the user's `init.lua`
+pub struct ScriptEngine {
+    lua: Lua,
+}
+
+impl ScriptEngine {
+    /// Load `init.lua` from the config directory, returning None if there is no script
+    pub fn load() -> Result<Option<Self>, String> {
+        let Some(path) = config_dir().map(|dir| dir.join("init.lua")) else {
+            return Ok(None);
+        };
+        match Self::from_file(&path) {
+            Ok(engine) => Ok(Some(engine)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to load init.lua: {e}")),
+        }
+    }
+
+    fn from_file(path: &Path) -> Result<Self, io::Error> {
+        let source = fs::read_to_string(path)?;
+        Self::from_source(&source).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn from_source(source: &str) -> Result<Self, String> {
+        let lua = Lua::new();
+        lua.set_app_data(Vec::<ScriptAction>::new());
+        install_api(&lua).map_err(|e| e.to_string())?;
+        lua.load(PRELUDE)
+            .set_name("prelude")
+            .exec()
+            .map_err(|e| e.to_string())?;
+        lua.load(source)
+            .set_name("init.lua")
+            .exec()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { lua })
+    }
+
+    /// Run all hooks registered for the event
+    pub fn emit(
+        &self,
+        event: &ScriptEvent,
+        state: &ScriptState,
+    ) -> Result<Vec<ScriptAction>, String> {
+        self.call(state, |pomo| {
+            let emit: Function = pomo.get("_emit")?;
+            match event {
+                ScriptEvent::SessionCompleted(session) => {
+                    emit.call::<()>((event.name(), session.name()))
+                }
+                ScriptEvent::TaskAdded(text) => emit.call::<()>((event.name(), text.as_str())),
+            }
+        })
+    }
+
+    /// Run the action bound to the key, returning None if the script does not bind it
+    pub fn handle_key(
+        &self,
+        key: char,
+        state: &ScriptState,
+    ) -> Result<Option<Vec<ScriptAction>>, String> {
+        let mut bound = false;
+        let actions = self.call(state, |pomo| {
+            let handle: Function = pomo.get("_key")?;
+            bound = handle.call::<bool>(key.to_string())?;
+            Ok(())
+        })?;
+        Ok(bound.then_some(actions))
+    }
+
+    /// Publish the state snapshot, run `f`, and drain the actions it queued
+    fn call(
+        &self,
+        state: &ScriptState,
+        f: impl FnOnce(&Table) -> mlua::Result<()>,
+    ) -> Result<Vec<ScriptAction>, String> {
+        let result = (|| {
+            let pomo: Table = self.lua.globals().get("pomo")?;
+            pomo.set("state", state_table(&self.lua, state)?)?;
+            f(&pomo)
+        })();
+
+        let actions = self
+            .lua
+            .app_data_mut::<Vec<ScriptAction>>()
+            .map(|mut queue| std::mem::take(&mut *queue))
+            .unwrap_or_default();
+
+        result
+            .map(|()| actions)
+            .map_err(|e| format!("Script error: {e}"))
+    }
+}
+
+fn state_table(lua: &Lua, state: &ScriptState) -> mlua::Result<Table> {
+    let table = lua.create_table()?;
+    table.set("session", state.session.name())?;
+    table.set("running", state.running)?;
+    table.set("remaining", state.remaining_secs)?;
+    table.set("active_task", state.active_task.clone())?;
+    Ok(table)
+}
+
+/// Register the Rust-backed functions on the global `pomo` table
+fn install_api(lua: &Lua) -> mlua::Result<()> {
+    let pomo = lua.create_table()?;
+
+    let queue = |lua: &Lua, action: ScriptAction| {
+        if let Some(mut actions) = lua.app_data_mut::<Vec<ScriptAction>>() {
+            actions.push(action);
+        }
+    };
+
+    pomo.set(
+        "start",
+        lua.create_function(move |lua, ()| {
+            queue(lua, ScriptAction::StartTimer);
+            Ok(())
+        })?,
+    )?;
+    pomo.set(
+        "pause",
+        lua.create_function(move |lua, ()| {
+            queue(lua, ScriptAction::PauseTimer);
+            Ok(())
+        })?,
+    )?;
+    pomo.set(
+        "reset",
+        lua.create_function(move |lua, ()| {
+            queue(lua, ScriptAction::ResetTimer);
+            Ok(())
+        })?,
+    )?;
+    pomo.set(
+        "add_task",
+        lua.create_function(move |lua, (text, section): (String, Option<String>)| {
+            let section = match section.as_deref() {
+                None | Some("backlog") => TaskSection::Backlog,
+                Some("current") => TaskSection::Current,
+                Some(other) => {
+                    return Err(mlua::Error::runtime(format!(
+                        "unknown section '{other}' (expected 'backlog' or 'current')"
+                    )))
+                }
+            };
+            queue(lua, ScriptAction::AddTask(text, section));
+            Ok(())
+        })?,
+    )?;
+    pomo.set(
+        "notify",
+        lua.create_function(move |lua, message: String| {
+            queue(lua, ScriptAction::Notify(message));
+            Ok(())
+        })?,
+    )?;
+
+    lua.globals().set("pomo", pomo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::SessionType;
+
+    fn idle_state() -> ScriptState {
+        ScriptState {
+            session: SessionType::Work,
+            running: false,
+            remaining_secs: 1500,
+            active_task: Some("Write docs".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_hook_queues_actions() -> Result<(), String> {
+        let engine = ScriptEngine::from_source(
+            r#"
+            pomo.on("session_completed", function(session)
+                if session == "work" then
+                    pomo.notify("Done: " .. pomo.state.active_task)
+                    pomo.start()
+                end
+            end)
+            "#,
+        )?;
+
+        let actions = engine.emit(
+            &ScriptEvent::SessionCompleted(SessionType::Work),
+            &idle_state(),
+        )?;
+        assert_eq!(
+            actions,
+            vec![
+                ScriptAction::Notify("Done: Write docs".to_string()),
+                ScriptAction::StartTimer,
+            ]
+        );
+
+        let actions = engine.emit(
+            &ScriptEvent::SessionCompleted(SessionType::ShortBreak),
+            &idle_state(),
+        )?;
+        assert!(actions.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_binding() -> Result<(), String> {
+        let engine = ScriptEngine::from_source(
+            r#"pomo.bind("g", function() pomo.add_task("Stretch", "current") end)"#,
+        )?;
+
+        assert_eq!(
+            engine.handle_key('g', &idle_state())?,
+            Some(vec![ScriptAction::AddTask(
+                "Stretch".to_string(),
+                TaskSection::Current
+            )])
+        );
+        assert_eq!(engine.handle_key('h', &idle_state())?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_errors_are_reported() -> Result<(), String> {
+        assert!(ScriptEngine::from_source("this is not lua").is_err());
+
+        let engine = ScriptEngine::from_source(
+            r#"pomo.bind("g", function() pomo.add_task("x", "nowhere") end)"#,
+        )?;
+        assert!(engine.handle_key('g', &idle_state()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! User scripts (`~/.config/pomo-tui/init.lua`) reacting to app events and binding custom keys.
+//!
+//! Scripts never touch app state directly: they read a [`ScriptState`] snapshot and queue
+//! [`ScriptAction`]s, which the app applies once the script returns.
+
+// Without the `lua` feature the stub engine never produces events or actions
+#![cfg_attr(not(feature = "lua"), allow(dead_code))]
+
+#[cfg(feature = "lua")]
+mod lua;
+
+#[cfg(feature = "lua")]
+pub use lua::ScriptEngine;
+
+use crate::task::TaskSection;
+use crate::timer::SessionType;
+
+/// Events scripts can subscribe to with `pomo.on(name, fn)`
+#[derive(Debug, Clone)]
+pub enum ScriptEvent {
+    SessionCompleted(SessionType),
+    TaskAdded(String),
+}
+
+impl ScriptEvent {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::SessionCompleted(_) => "session_completed",
+            Self::TaskAdded(_) => "task_added",
+        }
+    }
+}
+
+/// Read-only view of the app exposed to scripts as `pomo.state`
+#[derive(Debug, Clone)]
+pub struct ScriptState {
+    pub session: SessionType,
+    pub running: bool,
+    pub remaining_secs: u64,
+    pub active_task: Option<String>,
+}
+
+/// A change requested by a script, applied by the app after the script returns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptAction {
+    StartTimer,
+    PauseTimer,
+    ResetTimer,
+    AddTask(String, TaskSection),
+    Notify(String),
+}
+
+/// Stand-in engine when built without the `lua` feature; never loads any scripts
+#[cfg(not(feature = "lua"))]
+pub struct ScriptEngine;
+
+// Signatures mirror the Lua engine, including its fallible returns
+#[cfg(not(feature = "lua"))]
+#[allow(clippy::unnecessary_wraps)]
+impl ScriptEngine {
+    pub const fn load() -> Result<Option<Self>, String> {
+        Ok(None)
+    }
+
+    pub fn emit(
+        &self,
+        _event: &ScriptEvent,
+        _state: &ScriptState,
+    ) -> Result<Vec<ScriptAction>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn handle_key(
+        &self,
+        _key: char,
+        _state: &ScriptState,
+    ) -> Result<Option<Vec<ScriptAction>>, String> {
+        Ok(None)
+    }
+}
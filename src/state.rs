@@ -0,0 +1,76 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ui::LayoutPreset;
+
+/// Layout preferences kept between runs in `state.toml` in the cache directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub tasks_visible: bool,
+    pub layout: LayoutPreset,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            tasks_visible: true,
+            layout: LayoutPreset::default(),
+        }
+    }
+}
+
+impl UiState {
+    /// Load the state file, falling back to defaults if it is missing
+    pub fn load(path: &Path) -> (Self, Option<String>) {
+        let result = fs::read_to_string(path).and_then(|content| {
+            toml::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        });
+        match result {
+            Ok(state) => (state, None),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Self::default(), None),
+            Err(e) => (Self::default(), Some(format!("Failed to load state: {e}"))),
+        }
+    }
+
+    pub fn save(self, path: &Path) -> Result<(), io::Error> {
+        let content =
+            toml::to_string(&self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("cache").join("state.toml");
+
+        let (state, error) = UiState::load(&path);
+        assert_eq!(state, UiState::default());
+        assert!(error.is_none());
+
+        let state = UiState {
+            tasks_visible: false,
+            layout: LayoutPreset::Stacked,
+        };
+        state.save(&path)?;
+        assert_eq!(UiState::load(&path).0, state);
+
+        fs::write(&path, "layout = \"diagonal\"")?;
+        let (state, error) = UiState::load(&path);
+        assert_eq!(state, UiState::default());
+        assert!(error.is_some());
+        Ok(())
+    }
+}
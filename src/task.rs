@@ -1,3 +1,9 @@
+use std::cmp::{Ordering, Reverse};
+use std::ops::Range;
+use std::time::Duration;
+
+use chrono::{Datelike, Days, Local, NaiveDate, Weekday};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaskSection {
     Backlog,
@@ -5,14 +11,598 @@ pub enum TaskSection {
     Completed,
 }
 
+/// How urgent a task is, ordered from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    /// Marker given in the text: `!!`, `(A)` or `!high` is high, `!`, `(B)` or `!med` medium
+    /// and `(C)` or `!low` low
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "!" | "(B)" | "!med" | "!medium" => Some(Self::Medium),
+            "(A)" | "!high" => Some(Self::High),
+            "(C)" | "!low" => Some(Self::Low),
+            _ if word.len() > 1 && word.chars().all(|c| c == '!') => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// How often a task comes back once completed, given as `every:day`, `every:week` or a
+/// weekday such as `every:mon`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    On(Weekday),
+}
+
+impl Recurrence {
+    fn parse(word: &str) -> Option<Self> {
+        let value = word.strip_prefix("every:")?;
+        match value.to_lowercase().as_str() {
+            "day" | "daily" => Some(Self::Daily),
+            "week" | "weekly" => Some(Self::Weekly),
+            _ => value.parse().ok().map(Self::On),
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Daily => "every day".to_string(),
+            Self::Weekly => "every week".to_string(),
+            Self::On(weekday) => format!("every {weekday}"),
+        }
+    }
+
+    /// The first day after `date` the task falls due again
+    fn next_after(self, date: NaiveDate) -> Option<NaiveDate> {
+        let days = match self {
+            Self::Daily => 1,
+            Self::Weekly => 7,
+            Self::On(weekday) => {
+                let ahead = (7 + weekday.num_days_from_monday()
+                    - date.weekday().num_days_from_monday())
+                    % 7;
+                if ahead == 0 {
+                    7
+                } else {
+                    ahead
+                }
+            }
+        };
+        date.checked_add_days(Days::new(days.into()))
+    }
+}
+
 /// A single task with a text description
 #[derive(Debug, Clone)]
 pub struct Task {
     pub text: String,
+    /// Work sessions recorded against this task
+    pub pomodoros: u32,
+    /// Parsed from a marker in the text, which is kept so the file round-trips
+    pub priority: Option<Priority>,
+    /// `#tag` words in the text, without the `#`
+    pub tags: Vec<String>,
+    /// Parsed from an `@2024-06-01` or `due:` word in the text
+    pub due: Option<NaiveDate>,
+    /// Pomodoros the task is expected to take, given as `~3`
+    pub estimate: Option<u32>,
+    /// Parsed from an `every:` word in the text
+    pub recurrence: Option<Recurrence>,
+    /// Free-form lines kept under the task in the file
+    pub note: String,
+    /// Day the task was completed, kept in the file after the checkbox line
+    pub completed_on: Option<NaiveDate>,
+    /// Day the task was added, kept in the file like `completed_on`; unknown for tasks added
+    /// before it was recorded
+    pub created_on: Option<NaiveDate>,
+    /// The `## ` header the task is under in the file
+    pub project: Option<String>,
 }
 
 impl Task {
-    pub const fn new(text: String) -> Self {
-        Self { text }
+    pub fn new(text: String) -> Self {
+        let priority = text.split_whitespace().find_map(Priority::parse);
+        let tags = text
+            .split_whitespace()
+            .filter_map(parse_tag)
+            .map(String::from)
+            .collect();
+        let today = Local::now().date_naive();
+        let due = text
+            .split_whitespace()
+            .find_map(|word| parse_due(word, today));
+        let estimate = text
+            .split_whitespace()
+            .find_map(|word| word.strip_prefix('~')?.parse().ok())
+            .filter(|&estimate| estimate > 0);
+        let recurrence = text.split_whitespace().find_map(Recurrence::parse);
+        Self {
+            text,
+            pomodoros: 0,
+            priority,
+            tags,
+            due,
+            estimate,
+            recurrence,
+            note: String::new(),
+            completed_on: None,
+            created_on: None,
+            project: None,
+        }
+    }
+
+    /// Whether the task was added before `cutoff`; tasks of unknown age never are
+    pub fn is_stale(&self, cutoff: NaiveDate) -> bool {
+        self.created_on.is_some_and(|day| day < cutoff)
+    }
+
+    /// Replace the text, picking up any new priority marker
+    pub fn set_text(&mut self, text: String) {
+        *self = Self {
+            pomodoros: self.pomodoros,
+            note: std::mem::take(&mut self.note),
+            completed_on: self.completed_on,
+            created_on: self.created_on,
+            project: self.project.take(),
+            ..Self::new(text)
+        };
+    }
+
+    /// Text compared to find duplicates: its words in lowercase without punctuation, leaving
+    /// out tags and priority markers
+    pub fn duplicate_key(&self) -> String {
+        self.text
+            .split_whitespace()
+            .filter(|word| parse_tag(word).is_none() && Priority::parse(word).is_none())
+            .map(|word| {
+                word.trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Web links in the text, without any punctuation that ends the sentence around them
+    pub fn urls(&self) -> Vec<&str> {
+        self.text
+            .split_whitespace()
+            .filter(|word| word.starts_with("https://") || word.starts_with("http://"))
+            .map(|word| word.trim_end_matches(['.', ',', ';', ':', ')', '!', '?']))
+            .filter(|url| !url.ends_with("//"))
+            .collect()
+    }
+
+    /// Whether the due date has passed by `today`
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        self.due.is_some_and(|due| due < today)
+    }
+
+    /// A fresh copy of a recurring task being completed `today`, due on its next day after
+    /// today, or after its due date if that is later
+    pub fn next_occurrence(&self, today: NaiveDate) -> Option<Self> {
+        let from = self.due.map_or(today, |due| due.max(today));
+        let due = self.recurrence?.next_after(from)?;
+        // Swap the date in place, as `due` is taken from the first such word
+        let mut replaced = false;
+        let text: String = self
+            .text
+            .split_inclusive(char::is_whitespace)
+            .map(|piece| {
+                let word = piece.trim_end();
+                if !replaced && parse_due(word, today).is_some() {
+                    replaced = true;
+                    format!("due:{due}{}", &piece[word.len()..])
+                } else {
+                    piece.to_string()
+                }
+            })
+            .collect();
+        let text = if replaced {
+            text
+        } else {
+            format!("{} due:{due}", text.trim_end())
+        };
+        Some(Self {
+            note: self.note.clone(),
+            created_on: Some(today),
+            project: self.project.clone(),
+            ..Self::new(text)
+        })
+    }
+
+    /// Work session length given in the text as `~50m`, `~1h` or `~1h30m`
+    pub fn duration(&self) -> Option<Duration> {
+        self.text
+            .split_whitespace()
+            .rev()
+            .find_map(|word| parse_duration(word.strip_prefix('~')?))
+    }
+}
+
+/// Order a section's tasks are listed in, leaving their order in the file as it is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortMode {
+    /// As arranged by hand
+    #[default]
+    Manual,
+    Alphabetical,
+    /// Longest-standing first, with tasks of unknown age before the rest
+    OldestFirst,
+    /// Most urgent first, then those due soonest
+    Priority,
+}
+
+impl SortMode {
+    /// The mode after this one, cycling back to manual
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Manual => Self::Alphabetical,
+            Self::Alphabetical => Self::OldestFirst,
+            Self::OldestFirst => Self::Priority,
+            Self::Priority => Self::Manual,
+        }
+    }
+
+    /// Shown after the section title, or None in manual order
+    pub const fn label(self) -> Option<&'static str> {
+        match self {
+            Self::Manual => None,
+            Self::Alphabetical => Some("by name"),
+            Self::OldestFirst => Some("oldest first"),
+            Self::Priority => Some("by priority"),
+        }
+    }
+
+    /// Compare two tasks, leaving equals to keep their manual order
+    pub fn compare(self, a: &Task, b: &Task) -> Ordering {
+        match self {
+            Self::Manual => Ordering::Equal,
+            Self::Alphabetical => a.text.to_lowercase().cmp(&b.text.to_lowercase()),
+            Self::OldestFirst => a.created_on.cmp(&b.created_on),
+            Self::Priority => {
+                let key = |task: &Task| (Reverse(task.priority), task.due.is_none(), task.due);
+                key(a).cmp(&key(b))
+            }
+        }
+    }
+}
+
+/// Which tasks the lists show, leaving the rest in place but hidden
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskFilter {
+    /// Only tasks in this project
+    pub project: Option<String>,
+    /// Only tasks with this tag
+    pub tag: Option<String>,
+    /// Only tasks whose text contains this, ignoring case
+    pub query: Option<String>,
+    /// Only Backlog tasks added before this day
+    pub stale_before: Option<NaiveDate>,
+}
+
+impl TaskFilter {
+    pub const fn is_active(&self) -> bool {
+        self.project.is_some()
+            || self.tag.is_some()
+            || self.query.is_some()
+            || self.stale_before.is_some()
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        self.project
+            .as_ref()
+            .is_none_or(|project| task.project.as_ref() == Some(project))
+            && self.tag.as_ref().is_none_or(|tag| task.tags.contains(tag))
+            && self
+                .query
+                .as_ref()
+                .is_none_or(|query| !find_ignore_case(&task.text, query).is_empty())
+            && self.stale_before.is_none_or(|cutoff| task.is_stale(cutoff))
+    }
+}
+
+/// Byte ranges of `text` matching `query`, ignoring case
+pub fn find_ignore_case(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut lowered = Vec::new();
+        let mut end = i;
+        while lowered.len() < query.len() && end < chars.len() {
+            lowered.extend(chars[end].1.to_lowercase());
+            end += 1;
+        }
+        if lowered == query {
+            let end_byte = chars.get(end).map_or(text.len(), |&(byte, _)| byte);
+            matches.push(chars[i].0..end_byte);
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
+
+/// Date given by an `@2024-06-01` word, or a `due:` word holding a date, `today`,
+/// `tomorrow` or a weekday, which means the next one from `today` on
+fn parse_due(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Some(date) = word.strip_prefix('@') {
+        return NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+    }
+    let value = word.strip_prefix("due:")?;
+    match value.to_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        _ => {
+            if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                return Some(date);
+            }
+            let weekday: Weekday = value.parse().ok()?;
+            let ahead =
+                (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+            today.checked_add_days(Days::new(ahead.into()))
+        }
+    }
+}
+
+/// Rewrite relative `due:` words, e.g. `due:tomorrow`, to the date they mean from `today`,
+/// so the task stays due on that day once saved
+pub fn resolve_due_dates(text: &str, today: NaiveDate) -> String {
+    text.split_inclusive(char::is_whitespace)
+        .map(|piece| {
+            let word = piece.trim_end();
+            match parse_due(word, today) {
+                Some(date) if word.starts_with("due:") => {
+                    format!("due:{date}{}", &piece[word.len()..])
+                }
+                _ => piece.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Name of a `#tag` word, made of letters, digits, `-` and `_`
+fn parse_tag(word: &str) -> Option<&str> {
+    let tag = word.strip_prefix('#')?;
+    let valid = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    (!tag.is_empty() && tag.chars().all(valid)).then_some(tag)
+}
+
+fn parse_duration(text: &str) -> Option<Duration> {
+    let (hours, rest): (u64, &str) = match text.split_once('h') {
+        Some((hours, rest)) => (hours.parse().ok()?, rest),
+        None => (0, text),
+    };
+    let minutes = match rest {
+        "" => 0,
+        rest => rest.strip_suffix('m')?.parse().ok()?,
+    };
+    let minutes = hours.checked_mul(60)?.checked_add(minutes)?;
+    let duration = Duration::from_secs(minutes.checked_mul(60)?);
+    (!duration.is_zero()).then_some(duration)
+}
+
+/// Make text safe to draw in a single list row: tabs become spaces, while ANSI escape
+/// sequences and other control characters are dropped
+pub fn sanitize(text: &str) -> String {
+    let mut clean = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\t' => clean.push(' '),
+            // CSI sequence, e.g. `ESC [ 31 m`, runs up to a final byte in `@`..=`~`
+            '\u{1b}' if chars.peek() == Some(&'[') => {
+                chars.next();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            c if c.is_control() => {}
+            c => clean.push(c),
+        }
+    }
+    clean
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration() {
+        let duration = |text: &str| Task::new(text.to_string()).duration();
+        assert_eq!(duration("Write report ~50m"), Some(Duration::from_mins(50)));
+        assert_eq!(duration("~1h Deep work"), Some(Duration::from_hours(1)));
+        assert_eq!(duration("Review ~1h30m"), Some(Duration::from_mins(90)));
+        assert_eq!(duration("Plain task"), None);
+        assert_eq!(duration("Approx ~5 things"), None);
+        assert_eq!(duration("Nothing ~0m"), None);
+    }
+
+    #[test]
+    fn test_duration_overflow() {
+        let duration = |text: &str| Task::new(text.to_string()).duration();
+        assert_eq!(duration("Forever ~18446744073709551615h"), None);
+        assert_eq!(duration("Forever ~307445734561825861m"), None);
+    }
+
+    #[test]
+    fn test_priority() {
+        let priority = |text: &str| Task::new(text.to_string()).priority;
+        assert_eq!(priority("Fix prod !!"), Some(Priority::High));
+        assert_eq!(priority("(A) Call the bank"), Some(Priority::High));
+        assert_eq!(priority("Reply to Sam !"), Some(Priority::Medium));
+        assert_eq!(priority("(C) Tidy desk"), Some(Priority::Low));
+        assert_eq!(priority("Fix login !high"), Some(Priority::High));
+        assert_eq!(priority("Tidy desk !low"), Some(Priority::Low));
+        assert_eq!(priority("Plain task"), None);
+        assert_eq!(priority("Wow! Exciting"), None);
+
+        let mut task = Task::new("Plan".to_string());
+        task.pomodoros = 2;
+        task.set_text("Plan !!".to_string());
+        assert_eq!(task.priority, Some(Priority::High));
+        assert_eq!(task.pomodoros, 2);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let estimate = |text: &str| Task::new(text.to_string()).estimate;
+        assert_eq!(estimate("Fix login bug ~3"), Some(3));
+        assert_eq!(estimate("Deep work ~50m"), None);
+        assert_eq!(estimate("Nothing ~0"), None);
+        assert_eq!(estimate("Plain task"), None);
+    }
+
+    #[test]
+    fn test_tags() {
+        let tags = |text: &str| Task::new(text.to_string()).tags;
+        assert_eq!(tags("Write report #work #q3-review"), ["work", "q3-review"]);
+        assert_eq!(tags("#home Fix tap"), ["home"]);
+        assert!(tags("Plain # task").is_empty());
+        assert!(tags("Issue #12, then lunch").is_empty());
+    }
+
+    #[test]
+    fn test_due_dates() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd_opt(2024, 6, 5).unwrap_or_default();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        assert_eq!(parse_due("@2024-06-01", today), date(2024, 6, 1));
+        assert_eq!(parse_due("due:2024-07-01", today), date(2024, 7, 1));
+        assert_eq!(parse_due("due:today", today), Some(today));
+        assert_eq!(parse_due("due:Tomorrow", today), date(2024, 6, 6));
+        assert_eq!(parse_due("due:fri", today), date(2024, 6, 7));
+        assert_eq!(parse_due("due:monday", today), date(2024, 6, 10));
+        assert_eq!(parse_due("due:wed", today), Some(today));
+        assert_eq!(parse_due("due:soon", today), None);
+        assert_eq!(parse_due("me@example.com", today), None);
+
+        assert_eq!(
+            resolve_due_dates("Ship due:tomorrow  #work @2024-06-09", today),
+            "Ship due:2024-06-06  #work @2024-06-09"
+        );
+
+        let task = Task::new("Renew passport @2024-06-01".to_string());
+        assert_eq!(task.due, date(2024, 6, 1));
+        assert!(task.is_overdue(today));
+        assert!(!task.is_overdue(date(2024, 6, 1).unwrap_or_default()));
+    }
+
+    #[test]
+    fn test_recurrence() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd_opt(2024, 6, 5).unwrap_or_default();
+        let next = |text: &str| {
+            Task::new(text.to_string())
+                .next_occurrence(today)
+                .map(|task| task.text)
+        };
+        assert_eq!(
+            next("Stand-up every:day"),
+            Some("Stand-up every:day due:2024-06-06".to_string())
+        );
+        assert_eq!(
+            next("Review every:Mon @2024-06-03 #work"),
+            Some("Review every:Mon due:2024-06-10 #work".to_string())
+        );
+        assert_eq!(
+            next("Review every:wed due:2024-06-05"),
+            Some("Review every:wed due:2024-06-12".to_string())
+        );
+        // Completed early, so the next one follows the due date rather than today
+        assert_eq!(
+            next("Report every:week due:2024-06-07"),
+            Some("Report every:week due:2024-06-14".to_string())
+        );
+        assert_eq!(next("Plain due:2024-06-07"), None);
+        assert_eq!(next("Sometimes every:now"), None);
+
+        let task = Task::new("Water plants every:fri".to_string());
+        assert_eq!(task.recurrence, Some(Recurrence::On(Weekday::Fri)));
+        assert_eq!(
+            task.recurrence.map(Recurrence::label).as_deref(),
+            Some("every Fri")
+        );
+    }
+
+    #[test]
+    fn test_duplicate_key() {
+        let key = |text: &str| Task::new(text.to_string()).duplicate_key();
+        assert_eq!(key("Fix the parser"), key("fix the parser."));
+        assert_eq!(
+            key("Fix the parser #work !!"),
+            key("  Fix  the parser #home")
+        );
+        assert_ne!(key("Fix the parser"), key("Fix the parsers"));
+        assert_eq!(key("#work !"), "");
+    }
+
+    #[test]
+    fn test_urls() {
+        let urls = |text: &str| {
+            Task::new(text.to_string())
+                .urls()
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            urls("Review PR https://github.com/o/r/pull/7, then http://example.com/a."),
+            ["https://github.com/o/r/pull/7", "http://example.com/a"]
+        );
+        assert_eq!(urls("(see https://example.com)"), ["https://example.com"]);
+        assert!(urls("Plain task about https:// and www.example.com").is_empty());
+    }
+
+    #[test]
+    fn test_find_ignore_case() {
+        assert_eq!(
+            find_ignore_case("Write REPORT, report", "report"),
+            [6..12, 14..20]
+        );
+        assert_eq!(find_ignore_case("Café visit, café", "CAFÉ"), [0..5, 13..18]);
+        assert!(find_ignore_case("Plan", "").is_empty());
+        assert!(find_ignore_case("Plan", "plans").is_empty());
+
+        let filter = TaskFilter {
+            query: Some("rep".to_string()),
+            ..TaskFilter::default()
+        };
+        assert!(filter.matches(&Task::new("Write report".to_string())));
+        assert!(!filter.matches(&Task::new("Plan".to_string())));
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("Plain task"), "Plain task");
+        assert_eq!(sanitize("Tab\tseparated"), "Tab separated");
+        assert_eq!(sanitize("\u{1b}[31mRed\u{1b}[0m text"), "Red text");
+        assert_eq!(sanitize("Bell\u{7} and\r return"), "Bell and return");
+        assert_eq!(sanitize("Wide 🍕 emoji"), "Wide 🍕 emoji");
     }
 }
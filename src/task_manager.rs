@@ -1,11 +1,16 @@
-use std::env;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::fileio::TaskFile;
-use crate::overlays::{SyncItem, SyncResolution};
-use crate::task::{Task, TaskSection};
+use chrono::{DateTime, Days, Local, NaiveDate};
+
+use crate::backup::Backups;
+use crate::config::cache_dir;
+use crate::fileio::{ParsedTasks, TaskFile};
+use crate::overlays::{SyncItem, SyncOrigin, SyncResolution};
+use crate::task::{resolve_due_dates, sanitize, SortMode, Task, TaskFilter, TaskSection};
+use crate::trash::Trash;
 
 /// Manages tasks across three sections (backlog, current, completed) with optional file sync.
 pub struct TaskManager {
@@ -13,36 +18,86 @@ pub struct TaskManager {
     backlog: Vec<Task>,
     current: Vec<Task>,
     completed: Vec<Task>,
+    /// Whether in-app edits have not been synced to the file yet
+    dirty: bool,
+    last_sync: Option<DateTime<Local>>,
+    /// Where deleted tasks go, so they can be restored
+    trash: Trash,
+    /// Why tasks last failed to go to the trash, if they did since this was last asked
+    trash_error: Option<io::Error>,
+    /// Where copies of the task file go before it is written
+    backups: Backups,
+    /// Folder of lock files telling instances with the same task file apart
+    locks: Option<PathBuf>,
+    /// Tasks as the app and the file last agreed on them, when the file was loaded or last
+    /// synced or saved, to tell changes made in the app from changes made to the file since
+    synced: ParsedTasks,
+    /// Whether completion days are written to the file
+    completion_dates: bool,
+    /// Whether the days tasks were added are written to the file
+    creation_dates: bool,
 }
 
 impl TaskManager {
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             file: None,
             backlog: Vec::new(),
             current: Vec::new(),
             completed: Vec::new(),
+            dirty: false,
+            last_sync: None,
+            trash: Trash::new(),
+            trash_error: None,
+            backups: Backups::new(),
+            locks: None,
+            synced: ParsedTasks::default(),
+            completion_dates: false,
+            creation_dates: false,
         }
     }
 
-    pub fn load(path: PathBuf) -> Result<Self, io::Error> {
-        let (file, parsed) = TaskFile::load(path)?;
+    /// Load the task file at `path`, locking it through a lock file in `locks` before
+    /// anything is written, so another instance with it open leaves it read-only here
+    pub fn load(path: PathBuf, locks: Option<PathBuf>) -> Result<Self, io::Error> {
+        let (mut file, mut parsed) = TaskFile::load(path)?;
+        if let Some(ref dir) = locks {
+            file.lock_in(dir.clone());
+        }
+        if resolve_file_due_dates(&mut file) {
+            parsed = file.read_tasks()?;
+        }
         Ok(Self {
             file: Some(file),
-            backlog: parsed.incomplete.into_iter().map(Task::new).collect(),
+            backlog: parsed
+                .incomplete
+                .iter()
+                .cloned()
+                .map(|t| parsed.task(t))
+                .collect(),
             current: Vec::new(),
-            completed: parsed.complete.into_iter().map(Task::new).collect(),
+            completed: parsed
+                .complete
+                .iter()
+                .cloned()
+                .map(|t| parsed.task(t))
+                .collect(),
+            dirty: false,
+            last_sync: None,
+            trash: Trash::new(),
+            trash_error: None,
+            backups: Backups::new(),
+            locks,
+            synced: parsed,
+            completion_dates: false,
+            creation_dates: false,
         })
     }
 
     /// Create and set the default task file at `~/.cache/pomo-tui/tasks.md`
     pub fn create_default_file(&mut self) -> Result<(), io::Error> {
-        // Resolve home directory
-        let home = env::var("HOME")
-            .or_else(|_| env::var("USERPROFILE"))
-            .map_err(|_| io::Error::new(io::ErrorKind::NotFound, "Home directory not found"))?;
-
-        let cache_dir = PathBuf::from(home).join(".cache").join("pomo-tui");
+        let cache_dir = cache_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Home directory not found"))?;
         let file_path = cache_dir.join("tasks.md");
 
         // Create directory structure if it doesn't exist
@@ -54,105 +109,126 @@ impl TaskManager {
         }
 
         // Load the task file
-        let (file, parsed) = TaskFile::load(file_path)?;
+        let (mut file, mut parsed) = TaskFile::load(file_path)?;
+        if let Some(ref dir) = self.locks {
+            file.lock_in(dir.clone());
+        }
+        file.set_backups(self.backups.clone());
+        if resolve_file_due_dates(&mut file) {
+            parsed = file.read_tasks()?;
+        }
 
         // Set the file and merge any tasks from the file into current state
         self.file = Some(file);
-        for text in parsed.incomplete {
-            if !self.backlog.iter().any(|t| t.text == text)
-                && !self.current.iter().any(|t| t.text == text)
+        for text in &parsed.incomplete {
+            if !self.backlog.iter().any(|t| &t.text == text)
+                && !self.current.iter().any(|t| &t.text == text)
             {
-                self.backlog.push(Task::new(text));
+                self.backlog.push(parsed.task(text.clone()));
             }
         }
-        for text in parsed.complete {
-            if !self.completed.iter().any(|t| t.text == text) {
-                self.completed.push(Task::new(text));
+        for text in &parsed.complete {
+            if !self.completed.iter().any(|t| &t.text == text) {
+                self.completed.push(parsed.task(text.clone()));
             }
         }
+        self.synced = parsed;
 
         Ok(())
     }
 
-    /// Compute diff between app state and file, returning sync items needing resolution
+    /// Compute diff between app state and file, returning sync items needing resolution.
+    /// Each is resolved the way the side that changed since the app last read or wrote the
+    /// file went; where both changed, towards keeping the task and completing it.
     pub fn compute_sync_items(&self) -> Result<Vec<SyncItem>, io::Error> {
         let Some(ref file) = self.file else {
             return Ok(Vec::new());
         };
 
         let file_tasks = file.read_tasks()?;
+        let baseline = &self.synced;
 
-        let app_incomplete: Vec<String> = self
+        // Lists keep the item order stable; sets make each membership check O(1)
+        let app_incomplete: Vec<&str> = self
             .backlog
             .iter()
             .chain(self.current.iter())
-            .map(|t| t.text.clone())
+            .map(|t| t.text.as_str())
             .collect();
-        let app_complete: Vec<String> = self.completed.iter().map(|t| t.text.clone()).collect();
+        let app_complete: Vec<&str> = self.completed.iter().map(|t| t.text.as_str()).collect();
+        let app_incomplete_set: HashSet<&str> = app_incomplete.iter().copied().collect();
+        let app_complete_set: HashSet<&str> = app_complete.iter().copied().collect();
+        let file_incomplete: HashSet<&str> =
+            file_tasks.incomplete.iter().map(String::as_str).collect();
+        let file_complete: HashSet<&str> = file_tasks.complete.iter().map(String::as_str).collect();
+        let was_incomplete: HashSet<&str> =
+            baseline.incomplete.iter().map(String::as_str).collect();
+        let was_complete: HashSet<&str> = baseline.complete.iter().map(String::as_str).collect();
+        let in_app =
+            |text: &str| app_incomplete_set.contains(text) || app_complete_set.contains(text);
+        let in_file = |text: &str| file_incomplete.contains(text) || file_complete.contains(text);
+
+        let side = |incomplete: &HashSet<&str>, complete: &HashSet<&str>, text: &str| {
+            if incomplete.contains(text) {
+                Some(SyncResolution::Incomplete)
+            } else if complete.contains(text) {
+                Some(SyncResolution::Complete)
+            } else {
+                None
+            }
+        };
 
         let mut items = Vec::new();
+        let mut push = |text: &str, resolution: SyncResolution| {
+            let origin = SyncOrigin {
+                app: side(&app_incomplete_set, &app_complete_set, text),
+                file: side(&file_incomplete, &file_complete, text),
+                baseline: side(&was_incomplete, &was_complete, text),
+            };
+            items.push(SyncItem {
+                text: text.to_string(),
+                resolution: origin.merged().unwrap_or(resolution),
+                origin,
+            });
+        };
 
         // New incomplete tasks in file, not in app
         for text in &file_tasks.incomplete {
-            if !app_incomplete.contains(text) && !app_complete.contains(text) {
-                items.push(SyncItem {
-                    text: text.clone(),
-                    resolution: SyncResolution::Incomplete,
-                });
+            if !in_app(text) {
+                push(text, SyncResolution::Incomplete);
             }
         }
 
         // New complete tasks in file, not in app
         for text in &file_tasks.complete {
-            if !app_incomplete.contains(text) && !app_complete.contains(text) {
-                items.push(SyncItem {
-                    text: text.clone(),
-                    resolution: SyncResolution::Complete,
-                });
+            if !in_app(text) {
+                push(text, SyncResolution::Complete);
             }
         }
 
         // App incomplete but complete in file
-        for text in &app_incomplete {
-            if file_tasks.complete.contains(text) {
-                items.push(SyncItem {
-                    text: text.clone(),
-                    resolution: SyncResolution::Complete,
-                });
+        for &text in &app_incomplete {
+            if file_complete.contains(text) {
+                push(text, SyncResolution::Complete);
             }
         }
 
         // App complete but incomplete in file
-        for text in &app_complete {
-            if file_tasks.incomplete.contains(text) {
-                items.push(SyncItem {
-                    text: text.clone(),
-                    resolution: SyncResolution::Complete,
-                });
+        for &text in &app_complete {
+            if file_incomplete.contains(text) {
+                push(text, SyncResolution::Complete);
             }
         }
 
-        let all_file_tasks: Vec<&String> = file_tasks
-            .incomplete
-            .iter()
-            .chain(file_tasks.complete.iter())
-            .collect();
-
         // App-only tasks not in file
-        for text in &app_incomplete {
-            if !all_file_tasks.contains(&text) {
-                items.push(SyncItem {
-                    text: text.clone(),
-                    resolution: SyncResolution::Incomplete,
-                });
+        for &text in &app_incomplete {
+            if !in_file(text) {
+                push(text, SyncResolution::Incomplete);
             }
         }
-        for text in &app_complete {
-            if !all_file_tasks.contains(&text) {
-                items.push(SyncItem {
-                    text: text.clone(),
-                    resolution: SyncResolution::Complete,
-                });
+        for &text in &app_complete {
+            if !in_file(text) {
+                push(text, SyncResolution::Complete);
             }
         }
 
@@ -161,64 +237,396 @@ impl TaskManager {
 
     /// Apply sync resolutions to both app state and task file
     pub fn apply_sync(&mut self, items: &[SyncItem]) -> Result<(), io::Error> {
-        // Apply to app state
+        // Apply to app state, dropping tasks that move sides before adding any that are missing
+        let resolutions: HashMap<&str, SyncResolution> = items
+            .iter()
+            .map(|item| (item.text.as_str(), item.resolution))
+            .collect();
+        let keeps = |task: &Task, side: SyncResolution| {
+            resolutions
+                .get(task.text.as_str())
+                .is_none_or(|&resolution| resolution == side)
+        };
+        self.backlog
+            .retain(|t| keeps(t, SyncResolution::Incomplete));
+        self.current
+            .retain(|t| keeps(t, SyncResolution::Incomplete));
+        self.completed
+            .retain(|t| keeps(t, SyncResolution::Complete));
+
+        let mut incomplete: HashSet<String> = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .map(|t| t.text.clone())
+            .collect();
+        let mut complete: HashSet<String> = self.completed.iter().map(|t| t.text.clone()).collect();
+        // Tasks coming from the file bring their notes and days
+        let parsed = match self.file {
+            Some(ref file) => Some(file.read_tasks()?),
+            None => None,
+        };
+        let task = |text: &String| Task {
+            note: parsed
+                .as_ref()
+                .and_then(|p| p.notes.get(text).cloned())
+                .unwrap_or_default(),
+            completed_on: parsed
+                .as_ref()
+                .and_then(|p| p.completed_on.get(text).copied()),
+            created_on: parsed
+                .as_ref()
+                .and_then(|p| p.created_on.get(text).copied()),
+            project: parsed.as_ref().and_then(|p| p.projects.get(text).cloned()),
+            ..Task::new(text.clone())
+        };
         for item in items {
-            match item.resolution {
-                SyncResolution::Incomplete => {
-                    self.completed.retain(|t| t.text != item.text);
-                    if !self.backlog.iter().any(|t| t.text == item.text)
-                        && !self.current.iter().any(|t| t.text == item.text)
-                    {
-                        self.backlog.push(Task::new(item.text.clone()));
-                    }
-                }
-                SyncResolution::Complete => {
-                    self.backlog.retain(|t| t.text != item.text);
-                    self.current.retain(|t| t.text != item.text);
-                    if !self.completed.iter().any(|t| t.text == item.text) {
-                        self.completed.push(Task::new(item.text.clone()));
-                    }
+            match resolutions.get(item.text.as_str()) {
+                Some(SyncResolution::Incomplete) if incomplete.insert(item.text.clone()) => {
+                    self.backlog.push(task(&item.text));
                 }
-                SyncResolution::Remove => {
-                    self.backlog.retain(|t| t.text != item.text);
-                    self.current.retain(|t| t.text != item.text);
-                    self.completed.retain(|t| t.text != item.text);
+                Some(SyncResolution::Complete) if complete.insert(item.text.clone()) => {
+                    self.completed.push(task(&item.text));
                 }
+                _ => {}
             }
         }
 
-        // Write to file
+        self.write_file(items)
+    }
+
+    /// Pin relative `due:` words added to the task file since it was read to the dates they
+    /// mean today, so the tasks don't move to a later day each time the file is read
+    pub fn resolve_due_dates(&mut self) {
         if let Some(ref mut file) = self.file {
-            file.write_sync(items)?;
+            resolve_file_due_dates(file);
         }
+    }
 
-        Ok(())
+    /// Whether the app changed since it last read or wrote the file, or `on_disk`, the file
+    /// as read in the background, has different tasks than it did then
+    pub fn needs_sync(&self, on_disk: &ParsedTasks) -> bool {
+        let tasks = |list: &[String]| list.iter().cloned().collect::<HashSet<String>>();
+        self.dirty
+            || tasks(&on_disk.incomplete) != tasks(&self.synced.incomplete)
+            || tasks(&on_disk.complete) != tasks(&self.synced.complete)
     }
 
-    pub fn add_task(&mut self, text: String, section: TaskSection) {
-        self.section_tasks(section).push(Task::new(text));
+    /// Apply the sync items only one side changed, returning the conflicts left to ask about
+    pub fn auto_sync(&mut self) -> Result<Vec<SyncItem>, io::Error> {
+        self.resolve_due_dates();
+        let (quiet, conflicts): (Vec<SyncItem>, Vec<SyncItem>) = self
+            .compute_sync_items()?
+            .into_iter()
+            .partition(|item| item.origin.merged().is_some());
+        if !quiet.is_empty() {
+            self.apply_sync(&quiet)?;
+        }
+        // The sides don't agree on the conflicts yet, so they keep their old baseline
+        for item in &conflicts {
+            self.set_synced(&item.text, item.origin.baseline);
+        }
+        // The file still differs until the conflicts are resolved
+        self.dirty |= !conflicts.is_empty();
+        Ok(conflicts)
     }
 
-    pub fn backlog(&self) -> &[Task] {
-        &self.backlog
+    /// Put `text` back on `side` of the synced snapshot, or out of it if None
+    fn set_synced(&mut self, text: &str, side: Option<SyncResolution>) {
+        self.synced.incomplete.retain(|t| t != text);
+        self.synced.complete.retain(|t| t != text);
+        match side {
+            Some(SyncResolution::Incomplete) => self.synced.incomplete.push(text.to_string()),
+            Some(SyncResolution::Complete) => self.synced.complete.push(text.to_string()),
+            Some(SyncResolution::Remove) | None => {}
+        }
     }
 
-    pub fn current(&self) -> &[Task] {
-        &self.current
+    /// Write the tasks as they are in the app to the file, without asking: tasks added,
+    /// completed or deleted in the app are changed in the file, while tasks added to or
+    /// deleted from the file since the app last read or wrote it are left for a sync
+    pub fn save(&mut self) -> Result<(), io::Error> {
+        let items = self.save_items()?;
+        self.write_file(&items)
     }
 
-    pub fn completed(&self) -> &[Task] {
-        &self.completed
+    /// Resolutions writing the app's tasks over the file, see `save`
+    fn save_items(&self) -> Result<Vec<SyncItem>, io::Error> {
+        let Some(ref file) = self.file else {
+            return Ok(Vec::new());
+        };
+        let on_disk = file.read_tasks()?;
+        let baseline = &self.synced;
+        let sides = |parsed: &ParsedTasks| -> HashMap<String, SyncResolution> {
+            parsed
+                .incomplete
+                .iter()
+                .map(|text| (text.clone(), SyncResolution::Incomplete))
+                .chain(
+                    parsed
+                        .complete
+                        .iter()
+                        .map(|text| (text.clone(), SyncResolution::Complete)),
+                )
+                .collect()
+        };
+        let (on_disk, baseline) = (sides(&on_disk), sides(baseline));
+
+        let mut items = Vec::new();
+        let mut in_app = HashSet::new();
+        let app_tasks = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .map(|t| (t, SyncResolution::Incomplete))
+            .chain(self.completed.iter().map(|t| (t, SyncResolution::Complete)));
+        for (task, resolution) in app_tasks {
+            in_app.insert(task.text.as_str());
+            // Leave out tasks deleted from the file since the app read them there
+            if on_disk.contains_key(&task.text) || !baseline.contains_key(&task.text) {
+                items.push(SyncItem {
+                    text: task.text.clone(),
+                    resolution,
+                    origin: SyncOrigin {
+                        app: Some(resolution),
+                        file: on_disk.get(&task.text).copied(),
+                        baseline: baseline.get(&task.text).copied(),
+                    },
+                });
+            }
+        }
+        // Deleted in the app, unlike tasks new to the file
+        for (text, &side) in on_disk
+            .iter()
+            .filter(|(text, _)| baseline.contains_key(*text))
+        {
+            if !in_app.contains(text.as_str()) {
+                items.push(SyncItem {
+                    text: text.clone(),
+                    resolution: SyncResolution::Remove,
+                    origin: SyncOrigin {
+                        app: None,
+                        file: Some(side),
+                        baseline: baseline.get(text).copied(),
+                    },
+                });
+            }
+        }
+        Ok(items)
+    }
+
+    /// Write `items` and every task's details to the file, which is then in sync
+    fn write_file(&mut self, items: &[SyncItem]) -> Result<(), io::Error> {
+        if let Some(ref mut file) = self.file {
+            file.write_sync(items)?;
+        }
+        self.write_pomodoros()?;
+        self.write_notes()?;
+        self.write_completion_days()?;
+        self.write_creation_days()?;
+        self.write_projects()?;
+        self.dirty = false;
+        self.last_sync = Some(Local::now());
+        self.synced = self.snapshot();
+        Ok(())
+    }
+
+    /// The app's tasks as the file would record them
+    fn snapshot(&self) -> ParsedTasks {
+        let mut snapshot = ParsedTasks::default();
+        let tasks = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .map(|t| (t, false))
+            .chain(self.completed.iter().map(|t| (t, true)));
+        for (task, complete) in tasks {
+            let text = task.text.clone();
+            if task.pomodoros > 0 {
+                snapshot.pomodoros.insert(text.clone(), task.pomodoros);
+            }
+            if !task.note.is_empty() {
+                snapshot.notes.insert(text.clone(), task.note.clone());
+            }
+            if let Some(day) = task.completed_on {
+                snapshot.completed_on.insert(text.clone(), day);
+            }
+            if let Some(day) = task.created_on {
+                snapshot.created_on.insert(text.clone(), day);
+            }
+            if let Some(ref project) = task.project {
+                snapshot.projects.insert(text.clone(), project.clone());
+            }
+            if complete {
+                snapshot.complete.push(text);
+            } else {
+                snapshot.incomplete.push(text);
+            }
+        }
+        snapshot
+    }
+
+    /// Add a task to the end of `section`, returning it for any further details; `text` can
+    /// be a `String` or a `&str`
+    pub fn add_task(&mut self, text: impl Into<String>, section: TaskSection) -> &mut Task {
+        let today = Local::now().date_naive();
+        let text = resolve_due_dates(&sanitize(&text.into()), today);
+        self.dirty = true;
+        let tasks = self.section_tasks(section);
+        tasks.push(Task {
+            created_on: Some(today),
+            ..Task::new(text)
+        });
+        let last = tasks.len() - 1;
+        &mut tasks[last]
     }
 
     pub const fn has_file_path(&self) -> bool {
         self.file.is_some()
     }
 
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file.as_ref().map(TaskFile::path)
+    }
+
+    pub const fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Tasks added, removed or changed in the app since the file was last read or written,
+    /// which quitting now would lose
+    pub fn unsynced_changes(&self) -> usize {
+        if self.file.is_none() || !self.dirty {
+            return 0;
+        }
+        let baseline = &self.synced;
+        let mut unmatched: HashMap<&str, bool> = baseline
+            .incomplete
+            .iter()
+            .map(|text| (text.as_str(), false))
+            .chain(baseline.complete.iter().map(|text| (text.as_str(), true)))
+            .collect();
+        let app_tasks = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .map(|t| (t, false))
+            .chain(self.completed.iter().map(|t| (t, true)));
+        let mut changes = 0;
+        for (task, complete) in app_tasks {
+            let unchanged = unmatched.remove(task.text.as_str()) == Some(complete) && {
+                let saved = baseline.task(task.text.clone());
+                task.pomodoros == saved.pomodoros
+                    && task.note == saved.note
+                    && task.completed_on == saved.completed_on
+                    && task.created_on == saved.created_on
+                    && task.project == saved.project
+            };
+            if !unchanged {
+                changes += 1;
+            }
+        }
+        // Deleted in the app
+        changes + unmatched.len()
+    }
+
+    /// Whether another instance has the task file open, so it is not written from here
+    pub fn is_read_only(&self) -> bool {
+        self.file.as_ref().is_some_and(TaskFile::is_read_only)
+    }
+
+    /// Write the task file from here even though another instance has it open
+    pub fn ignore_lock(&mut self) {
+        if let Some(ref mut file) = self.file {
+            file.ignore_lock();
+        }
+    }
+
+    pub const fn last_sync(&self) -> Option<DateTime<Local>> {
+        self.last_sync
+    }
+
     pub fn active_task(&self) -> Option<&Task> {
         self.current.first()
     }
 
+    pub fn section(&self, section: TaskSection) -> &[Task] {
+        match section {
+            TaskSection::Backlog => &self.backlog,
+            TaskSection::Current => &self.current,
+            TaskSection::Completed => &self.completed,
+        }
+    }
+
+    /// Indices of the tasks in `section` that `filter` shows
+    pub fn filtered(&self, section: TaskSection, filter: &TaskFilter) -> Vec<usize> {
+        // Only Backlog tasks go stale
+        if filter.stale_before.is_some() && section != TaskSection::Backlog {
+            return Vec::new();
+        }
+        self.section(section)
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| filter.matches(task))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Indices of the tasks in `section` that `filter` shows, listed in `sort` order
+    pub fn sorted(&self, section: TaskSection, filter: &TaskFilter, sort: SortMode) -> Vec<usize> {
+        let tasks = self.section(section);
+        let mut indices = self.filtered(section, filter);
+        indices.sort_by(|&a, &b| sort.compare(&tasks[a], &tasks[b]));
+        indices
+    }
+
+    /// Every project a task is in, in the order they first come up
+    pub fn projects(&self) -> Vec<String> {
+        let mut projects: Vec<String> = Vec::new();
+        for task in self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .chain(&self.completed)
+        {
+            if let Some(ref project) = task.project {
+                if !projects.contains(project) {
+                    projects.push(project.clone());
+                }
+            }
+        }
+        projects
+    }
+
+    /// Every tag used by a task in any section, sorted
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .chain(&self.completed)
+            .flat_map(|task| task.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Pomodoros recorded and estimated across the tasks in `section` with an estimate, or
+    /// None if no task has one
+    pub fn estimate_totals(&self, section: TaskSection) -> Option<(u32, u32)> {
+        estimate_totals(self.section(section))
+    }
+
+    /// Like `estimate_totals`, across the tasks completed on `day`
+    pub fn estimate_totals_on(&self, day: NaiveDate) -> Option<(u32, u32)> {
+        estimate_totals(
+            self.completed
+                .iter()
+                .filter(|task| task.completed_on == Some(day)),
+        )
+    }
+
     pub const fn section_len(&self, section: TaskSection) -> usize {
         match section {
             TaskSection::Backlog => self.backlog.len(),
@@ -235,74 +643,548 @@ impl TaskManager {
         }
     }
 
-    pub fn reorder_down(&mut self, section: TaskSection, index: usize) {
+    /// Swap the task at `index` with the next one `filter` shows, so hidden tasks stay put
+    pub fn reorder_down(&mut self, section: TaskSection, index: usize, filter: &TaskFilter) {
         let tasks = self.section_tasks(section);
-        if index + 1 < tasks.len() {
-            tasks.swap(index, index + 1);
+        let next = (index + 1..tasks.len()).find(|&i| filter.matches(&tasks[i]));
+        if let Some(next) = next {
+            tasks.swap(index, next);
         }
     }
 
-    pub fn reorder_up(&mut self, section: TaskSection, index: usize) {
-        if index > 0 {
-            let tasks = self.section_tasks(section);
-            tasks.swap(index, index - 1);
+    /// Swap the task at `index` with the previous one `filter` shows
+    pub fn reorder_up(&mut self, section: TaskSection, index: usize, filter: &TaskFilter) {
+        let tasks = self.section_tasks(section);
+        let prev = (0..index.min(tasks.len()))
+            .rev()
+            .find(|&i| filter.matches(&tasks[i]));
+        if let Some(prev) = prev {
+            tasks.swap(index, prev);
         }
     }
 
+    /// Remove the tasks at `indices` in `section`, returned in list order; indices out of
+    /// range are skipped
+    fn take_tasks(&mut self, section: TaskSection, indices: &[usize]) -> Vec<Task> {
+        let tasks = self.section_tasks(section);
+        let (taken, kept): (Vec<_>, Vec<_>) = std::mem::take(tasks)
+            .into_iter()
+            .enumerate()
+            .partition(|(i, _)| indices.contains(i));
+        *tasks = kept.into_iter().map(|(_, task)| task).collect();
+        taken.into_iter().map(|(_, task)| task).collect()
+    }
+
     /// Move task at index in section to other section (backlog ↔ current).
     pub fn cycle_task_section(&mut self, section: TaskSection, index: usize) {
-        match section {
-            TaskSection::Backlog => {
-                if index < self.backlog.len() {
-                    let task = self.backlog.remove(index);
-                    self.current.push(task);
+        self.cycle_tasks_section(section, &[index]);
+    }
+
+    /// Move the tasks at `indices` in section to the other section (backlog ↔ current),
+    /// keeping their order
+    pub fn cycle_tasks_section(&mut self, section: TaskSection, indices: &[usize]) {
+        let target = match section {
+            TaskSection::Backlog => TaskSection::Current,
+            TaskSection::Current => TaskSection::Backlog,
+            TaskSection::Completed => return,
+        };
+        self.move_tasks(section, indices, target);
+    }
+
+    /// Toggle completion status of focused task (current → completed, or completed → backlog)
+    pub fn toggle_completion(&mut self, section: TaskSection, index: usize) {
+        self.toggle_completions(section, &[index]);
+    }
+
+    /// Toggle completion status of the tasks at `indices` in section, keeping their order
+    pub fn toggle_completions(&mut self, section: TaskSection, indices: &[usize]) {
+        let target = match section {
+            TaskSection::Current => TaskSection::Completed,
+            TaskSection::Completed => TaskSection::Backlog,
+            TaskSection::Backlog => return,
+        };
+        self.move_tasks(section, indices, target);
+    }
+
+    /// Move task at index in section to the end of `target`
+    pub fn move_task(&mut self, section: TaskSection, index: usize, target: TaskSection) {
+        self.move_tasks(section, &[index], target);
+    }
+
+    /// Move the tasks at `indices` in section to the end of `target`, keeping their order
+    pub fn move_tasks(&mut self, section: TaskSection, indices: &[usize], target: TaskSection) {
+        if section == target {
+            return;
+        }
+        let mut moved = self.take_tasks(section, indices);
+        // The file only knows complete and incomplete, so backlog ↔ current has nothing to sync
+        if !moved.is_empty()
+            && (section == TaskSection::Completed) != (target == TaskSection::Completed)
+        {
+            self.dirty = true;
+        }
+        let completed_on = (target == TaskSection::Completed).then(|| Local::now().date_naive());
+        for task in &mut moved {
+            task.completed_on = completed_on;
+        }
+        if target == TaskSection::Completed {
+            self.recur(&moved);
+        }
+        self.section_tasks(target).extend(moved);
+    }
+
+    /// Move the Backlog task at `index` to the top of Current, making it the active task
+    pub fn promote_task(&mut self, index: usize) {
+        if let Some(task) = self.take_tasks(TaskSection::Backlog, &[index]).pop() {
+            self.current.insert(0, task);
+        }
+    }
+
+    /// Move the Current task at `index` to the top, making it the active task
+    pub fn activate_task(&mut self, index: usize) {
+        if index < self.current.len() {
+            let task = self.current.remove(index);
+            self.current.insert(0, task);
+        }
+    }
+
+    /// Record a work session against the current task, saving the tally to the task file;
+    /// while the file is read-only the tally waits for the next sync
+    pub fn record_pomodoro(&mut self) -> Result<(), io::Error> {
+        let read_only = self.is_read_only();
+        if let Some(task) = self.current.first_mut() {
+            task.pomodoros += 1;
+            if read_only {
+                self.dirty = true;
+                return Ok(());
+            }
+            // Written straight to the file, so both sides agree on it
+            if self.synced.incomplete.contains(&task.text) {
+                self.synced
+                    .pomodoros
+                    .insert(task.text.clone(), task.pomodoros);
+            }
+        }
+        self.write_pomodoros()
+    }
+
+    fn write_pomodoros(&mut self) -> Result<(), io::Error> {
+        let Some(ref mut file) = self.file else {
+            return Ok(());
+        };
+        let tallies: Vec<(&str, u32)> = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .chain(&self.completed)
+            .filter(|t| t.pomodoros > 0)
+            .map(|t| (t.text.as_str(), t.pomodoros))
+            .collect();
+        file.write_pomodoros(&tallies)
+    }
+
+    fn write_completion_days(&mut self) -> Result<(), io::Error> {
+        let Some(file) = self.file.as_mut().filter(|_| self.completion_dates) else {
+            return Ok(());
+        };
+        let days: Vec<(&str, NaiveDate)> = self
+            .completed
+            .iter()
+            .filter_map(|t| Some((t.text.as_str(), t.completed_on?)))
+            .collect();
+        file.write_completion_days(&days)
+    }
+
+    fn write_projects(&mut self) -> Result<(), io::Error> {
+        let Some(ref mut file) = self.file else {
+            return Ok(());
+        };
+        let projects: Vec<(&str, &str)> = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .chain(&self.completed)
+            .filter_map(|t| Some((t.text.as_str(), t.project.as_deref()?)))
+            .collect();
+        file.write_projects(&projects)
+    }
+
+    fn write_creation_days(&mut self) -> Result<(), io::Error> {
+        let Some(file) = self.file.as_mut().filter(|_| self.creation_dates) else {
+            return Ok(());
+        };
+        let days: Vec<(&str, NaiveDate)> = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .chain(&self.completed)
+            .filter_map(|t| Some((t.text.as_str(), t.created_on?)))
+            .collect();
+        file.write_creation_days(&days)
+    }
+
+    fn write_notes(&mut self) -> Result<(), io::Error> {
+        let Some(ref mut file) = self.file else {
+            return Ok(());
+        };
+        let notes: Vec<(&str, &str)> = self
+            .backlog
+            .iter()
+            .chain(&self.current)
+            .chain(&self.completed)
+            .filter(|t| !t.note.is_empty())
+            .map(|t| (t.text.as_str(), t.note.as_str()))
+            .collect();
+        file.write_notes(&notes)
+    }
+
+    /// Replace the note of the task at `index` in `section`, saving it under the task in the
+    /// file if the task is there yet; blank lines are dropped
+    pub fn set_note(
+        &mut self,
+        section: TaskSection,
+        index: usize,
+        note: &str,
+    ) -> Result<(), io::Error> {
+        let note = note
+            .lines()
+            .map(|line| sanitize(line).trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Some(task) = self.section_tasks(section).get_mut(index) else {
+            return Ok(());
+        };
+        task.note.clone_from(&note);
+        let text = task.text.clone();
+        // Read-only, so the note waits for the next sync
+        if self.is_read_only() {
+            self.dirty = true;
+            return Ok(());
+        }
+        // Written straight to the file, so both sides agree on it
+        let in_file =
+            self.synced.incomplete.contains(&text) || self.synced.complete.contains(&text);
+        if in_file && note.is_empty() {
+            self.synced.notes.remove(&text);
+        } else if in_file {
+            self.synced.notes.insert(text.clone(), note.clone());
+        }
+        self.file
+            .as_mut()
+            .map_or(Ok(()), |file| file.write_notes(&[(&text, &note)]))
+    }
+
+    /// Add the next occurrence of any recurring tasks among those being completed to the
+    /// backlog, unless it is there already from completing the task before
+    fn recur(&mut self, completed: &[Task]) {
+        let today = Local::now().date_naive();
+        for next in completed
+            .iter()
+            .filter_map(|task| task.next_occurrence(today))
+        {
+            let spawned = self
+                .backlog
+                .iter()
+                .chain(&self.current)
+                .any(|task| task.text == next.text);
+            if !spawned {
+                self.backlog.push(next);
+            }
+        }
+    }
+
+    /// Groups of tasks that look like the same task, by `Task::duplicate_key`, in the order
+    /// Current, Backlog, Completed so the first of each is the one to keep
+    pub fn duplicates(&self) -> Vec<Vec<(TaskSection, usize)>> {
+        let mut groups: Vec<Vec<(TaskSection, usize)>> = Vec::new();
+        let mut by_key: HashMap<String, usize> = HashMap::new();
+        for section in [
+            TaskSection::Current,
+            TaskSection::Backlog,
+            TaskSection::Completed,
+        ] {
+            for (index, task) in self.section(section).iter().enumerate() {
+                let key = task.duplicate_key();
+                if key.is_empty() {
+                    continue;
                 }
+                let group = *by_key.entry(key).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[group].push((section, index));
             }
-            TaskSection::Current => {
-                if index < self.current.len() {
-                    let task = self.current.remove(index);
-                    self.backlog.push(task);
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Fold the other tasks of `group` into its first, which gains their pomodoros, tags and
+    /// notes, moving the others to the trash as they were
+    pub fn merge_tasks(&mut self, group: &[(TaskSection, usize)]) {
+        let Some((&(section, index), rest)) = group.split_first() else {
+            return;
+        };
+        if self.section(section).get(index).is_none() {
+            return;
+        }
+        let mut merged = Vec::new();
+        let mut index = index;
+        for target in [
+            TaskSection::Backlog,
+            TaskSection::Current,
+            TaskSection::Completed,
+        ] {
+            let indices: Vec<usize> = rest
+                .iter()
+                .filter(|&&(s, _)| s == target)
+                .map(|&(_, i)| i)
+                .collect();
+            if target == section {
+                // The kept task shifts up past any taken from above it
+                index -= indices.iter().filter(|&&i| i < index).count();
+            }
+            merged.extend(
+                self.take_tasks(target, &indices)
+                    .into_iter()
+                    .map(|task| (target, task)),
+            );
+        }
+        if merged.is_empty() {
+            return;
+        }
+
+        let task = &mut self.section_tasks(section)[index];
+        let mut text = task.text.clone();
+        for (_, other) in &merged {
+            task.pomodoros += other.pomodoros;
+            for tag in &other.tags {
+                if !task.tags.contains(tag) && !text.contains(&format!("#{tag}")) {
+                    text = format!("{text} #{tag}");
                 }
             }
-            TaskSection::Completed => {}
+            if !other.note.is_empty() && !task.note.contains(&other.note) {
+                task.note = [task.note.as_str(), other.note.as_str()]
+                    .into_iter()
+                    .filter(|note| !note.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        }
+        task.set_text(text);
+        self.dirty = true;
+        self.trash_error = self.trash.add_from(merged).err();
+    }
+
+    /// Indices of completed tasks done more than `days` ago, or all of them for 0; tasks
+    /// without a completion day are left alone unless archiving all
+    pub fn archivable(&self, days: u32) -> Vec<usize> {
+        let cutoff = Local::now()
+            .date_naive()
+            .checked_sub_days(Days::new(days.into()));
+        self.completed
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| days == 0 || t.completed_on.is_some_and(|day| Some(day) < cutoff))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Move the completed tasks at `indices` out to the archive file beside the task file
+    pub fn archive_tasks(&mut self, indices: &[usize]) -> Result<(), io::Error> {
+        let Some(ref mut file) = self.file else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No task file to archive from",
+            ));
+        };
+        let tasks: Vec<Task> = indices
+            .iter()
+            .filter_map(|&i| self.completed.get(i).cloned())
+            .collect();
+        file.archive(&tasks)?;
+        self.take_tasks(TaskSection::Completed, indices);
+        for task in &tasks {
+            self.set_synced(&task.text, None);
+        }
+        Ok(())
+    }
+
+    /// Start the day afresh: archive the tasks at `archive` in Completed, if any, then move
+    /// every Current task back to the top of Backlog, keeping their order. Nothing moves if
+    /// archiving fails, so the day is never left half reset
+    pub fn reset_day(&mut self, archive: &[usize]) -> Result<(), io::Error> {
+        if !archive.is_empty() {
+            self.archive_tasks(archive)?;
+        }
+        let current = std::mem::take(&mut self.current);
+        self.backlog.splice(0..0, current);
+        Ok(())
+    }
+
+    /// Write the day tasks are completed in the app to the file
+    pub const fn set_completion_dates(&mut self, enabled: bool) {
+        self.completion_dates = enabled;
+    }
+
+    /// Write the day tasks are added in the app to the file
+    pub const fn set_creation_dates(&mut self, enabled: bool) {
+        self.creation_dates = enabled;
+    }
+
+    /// Where `archive_tasks` writes to
+    pub fn archive_path(&self) -> Option<PathBuf> {
+        self.file.as_ref().map(TaskFile::archive_path)
+    }
+
+    /// Complete the current task (the first task in the current section)
+    pub fn complete_current_task(&mut self) {
+        if !self.current.is_empty() {
+            let mut task = self.current.remove(0);
+            task.completed_on = Some(Local::now().date_naive());
+            self.recur(std::slice::from_ref(&task));
+            self.completed.push(task);
+            self.dirty = true;
+        }
+    }
+
+    /// Replace the text of the task at `index` in `section`, keeping its pomodoros
+    pub fn edit_task(&mut self, section: TaskSection, index: usize, text: &str) {
+        let text = resolve_due_dates(&sanitize(text), Local::now().date_naive());
+        if let Some(task) = self.section_tasks(section).get_mut(index) {
+            if task.text != text {
+                task.set_text(text);
+                self.dirty = true;
+            }
+        }
+    }
+
+    pub fn delete_task(&mut self, section: TaskSection, index: usize) {
+        self.delete_tasks(section, &[index]);
+    }
+
+    /// Move the tasks at `indices` in section to the trash
+    pub fn delete_tasks(&mut self, section: TaskSection, indices: &[usize]) {
+        let deleted = self.take_tasks(section, indices);
+        if deleted.is_empty() {
+            return;
+        }
+        self.dirty = true;
+        self.trash_error = self.trash.add(section, deleted).err();
+    }
+
+    /// Why the last tasks deleted or merged away failed to go to the trash, if they did
+    /// since this was last asked; they are gone from the app either way
+    pub const fn take_trash_error(&mut self) -> Option<io::Error> {
+        self.trash_error.take()
+    }
+
+    /// Put the tasks of the last delete back at the end of their sections, returning how many
+    pub fn restore_deleted(&mut self) -> Result<usize, io::Error> {
+        let restored = self.trash.restore_last()?;
+        if !restored.is_empty() {
+            self.dirty = true;
         }
+        let count = restored.len();
+        for entry in restored {
+            self.section_tasks(entry.section).push(entry.task);
+        }
+        Ok(count)
+    }
+
+    pub fn set_trash(&mut self, trash: Trash) {
+        self.trash = trash;
+    }
+
+    /// Lock the default task file through `locks` once it is created
+    pub fn set_locks(&mut self, locks: Option<PathBuf>) {
+        self.locks = locks;
+    }
+
+    pub fn set_backups(&mut self, backups: Backups) {
+        if let Some(ref mut file) = self.file {
+            file.set_backups(backups.clone());
+        }
+        self.backups = backups;
+    }
+
+    /// Why the last backup failed, if it did since this was last asked; the write went ahead
+    pub fn take_backup_error(&mut self) -> Option<io::Error> {
+        self.file.as_mut().and_then(TaskFile::take_backup_error)
+    }
+
+    /// Copies of the task file kept before writes, newest first
+    pub fn backups(&self) -> Vec<PathBuf> {
+        self.file
+            .as_ref()
+            .map(|file| file.backups().list(file.path()))
+            .unwrap_or_default()
+    }
+
+    /// Put the task file back as it was in `backup`, after backing up what it holds now,
+    /// and reload the tasks from it; Current empties into Backlog as on startup
+    pub fn restore_backup(&mut self, backup: &Path) -> Result<(), io::Error> {
+        let Some(ref mut file) = self.file else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No task file to restore",
+            ));
+        };
+        file.replace(&fs::read_to_string(backup)?)?;
+        let parsed = file.read_tasks()?;
+        self.backlog = parsed
+            .incomplete
+            .iter()
+            .cloned()
+            .map(|t| parsed.task(t))
+            .collect();
+        self.current.clear();
+        self.completed = parsed
+            .complete
+            .iter()
+            .cloned()
+            .map(|t| parsed.task(t))
+            .collect();
+        self.synced = parsed;
+        self.dirty = false;
+        Ok(())
     }
+}
 
-    /// Toggle completion status of focused task (current → completed, or completed → backlog)
-    pub fn toggle_completion(&mut self, section: TaskSection, index: usize) {
-        match section {
-            TaskSection::Current => {
-                if index < self.current.len() {
-                    let task = self.current.remove(index);
-                    self.completed.push(task);
-                }
-            }
-            TaskSection::Completed => {
-                if index < self.completed.len() {
-                    let task = self.completed.remove(index);
-                    self.backlog.push(task);
-                }
-            }
-            TaskSection::Backlog => {}
-        }
+/// Rewrite relative `due:` words in `file` to today's dates, returning whether it changed;
+/// a file that can't be written keeps its words, which are then read afresh each time
+fn resolve_file_due_dates(file: &mut TaskFile) -> bool {
+    !file.is_read_only()
+        && file
+            .resolve_due_dates(Local::now().date_naive())
+            .unwrap_or(false)
+}
+
+/// Direct section access for tests; the app goes through `section`
+#[cfg(test)]
+impl TaskManager {
+    pub fn backlog(&self) -> &[Task] {
+        &self.backlog
     }
 
-    /// Complete the current task (the first task in the current section)
-    pub fn complete_current_task(&mut self) {
-        if !self.current.is_empty() {
-            let task = self.current.remove(0);
-            self.completed.push(task);
-        }
+    pub fn current(&self) -> &[Task] {
+        &self.current
     }
 
-    pub fn delete_task(&mut self, section: TaskSection, index: usize) {
-        let tasks = self.section_tasks(section);
-        if index < tasks.len() {
-            tasks.remove(index);
-        }
+    pub fn completed(&self) -> &[Task] {
+        &self.completed
     }
 }
 
+/// Pomodoros recorded and estimated across the `tasks` with an estimate
+fn estimate_totals<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Option<(u32, u32)> {
+    tasks
+        .into_iter()
+        .filter_map(|task| Some((task.pomodoros, task.estimate?)))
+        .reduce(|(actual, estimate), (a, e)| (actual + a, estimate + e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,6 +1214,44 @@ mod tests {
         assert_eq!(tm.completed()[0].text, "Task 3");
     }
 
+    #[test]
+    fn test_estimate_totals() {
+        let mut tm = TaskManager::new();
+        assert_eq!(tm.estimate_totals(TaskSection::Completed), None);
+        tm.add_task("Fix login ~3", TaskSection::Completed);
+        tm.add_task("Write docs ~1", TaskSection::Completed);
+        tm.add_task("No estimate", TaskSection::Completed);
+        tm.completed[0].pomodoros = 4;
+        tm.completed[2].pomodoros = 5;
+        assert_eq!(tm.estimate_totals(TaskSection::Completed), Some((4, 4)));
+    }
+
+    #[test]
+    fn test_estimate_totals_on() {
+        let mut tm = TaskManager::new();
+        let today = Local::now().date_naive();
+        tm.add_task("Fix login ~3", TaskSection::Completed);
+        tm.add_task("Write docs ~1", TaskSection::Completed);
+        tm.completed[0].pomodoros = 4;
+        tm.completed[0].completed_on = Some(today);
+        tm.completed[1].completed_on = today.checked_sub_days(Days::new(1));
+        assert_eq!(tm.estimate_totals_on(today), Some((4, 3)));
+        assert_eq!(tm.estimate_totals_on(today - Days::new(2)), None);
+    }
+
+    #[test]
+    fn test_relative_due_date_is_fixed() {
+        let mut tm = TaskManager::new();
+        let today = Local::now().date_naive();
+        tm.add_task("Ship due:today", TaskSection::Backlog);
+        assert_eq!(tm.backlog()[0].text, format!("Ship due:{today}"));
+        assert_eq!(tm.backlog()[0].due, Some(today));
+
+        tm.edit_task(TaskSection::Backlog, 0, "Ship due:tomorrow");
+        assert_eq!(tm.backlog()[0].due, today.succ_opt());
+        assert!(!tm.backlog()[0].text.contains("tomorrow"));
+    }
+
     #[test]
     fn test_toggle_section() {
         let mut tm = TaskManager::new();
@@ -379,6 +1299,233 @@ mod tests {
         assert_eq!(tm.backlog()[0].text, "Task 1");
     }
 
+    #[test]
+    fn test_batch_operations() {
+        let mut tm = TaskManager::new();
+        for i in 0..5 {
+            tm.add_task(format!("Task {i}"), TaskSection::Backlog);
+        }
+
+        // Order is kept and out of range indices are skipped
+        tm.cycle_tasks_section(TaskSection::Backlog, &[3, 1, 9]);
+        let texts = |tasks: &[Task]| tasks.iter().map(|t| t.text.clone()).collect::<Vec<_>>();
+        assert_eq!(texts(tm.current()), ["Task 1", "Task 3"]);
+        assert_eq!(texts(tm.backlog()), ["Task 0", "Task 2", "Task 4"]);
+
+        tm.dirty = false;
+        tm.toggle_completions(TaskSection::Backlog, &[0]);
+        assert!(!tm.is_dirty());
+        tm.toggle_completions(TaskSection::Current, &[0, 1]);
+        assert_eq!(texts(tm.completed()), ["Task 1", "Task 3"]);
+        assert!(tm.is_dirty());
+
+        tm.delete_tasks(TaskSection::Backlog, &[0, 2]);
+        assert_eq!(texts(tm.backlog()), ["Task 2"]);
+        assert!(tm.current().is_empty());
+    }
+
+    #[test]
+    fn test_recurring_task_comes_back() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Stand-up every:day", TaskSection::Current);
+        tm.add_task("One-off", TaskSection::Current);
+        tm.toggle_completions(TaskSection::Current, &[0, 1]);
+        assert_eq!(tm.section_len(TaskSection::Completed), 2);
+        assert_eq!(tm.section_len(TaskSection::Backlog), 1);
+        let next = &tm.section(TaskSection::Backlog)[0];
+        assert!(next.text.starts_with("Stand-up every:day due:"));
+        assert_eq!(next.pomodoros, 0);
+
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Current);
+        tm.complete_current_task();
+        assert_eq!(tm.section_len(TaskSection::Backlog), 1);
+        assert_eq!(tm.section_len(TaskSection::Completed), 3);
+    }
+
+    #[test]
+    fn test_recompleting_does_not_recur_twice() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Stand-up every:day", TaskSection::Current);
+        tm.complete_current_task();
+        tm.move_task(TaskSection::Completed, 0, TaskSection::Current);
+        tm.complete_current_task();
+
+        assert_eq!(tm.section_len(TaskSection::Backlog), 1);
+        assert_eq!(tm.section_len(TaskSection::Completed), 1);
+    }
+
+    #[test]
+    fn test_archivable() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Old", TaskSection::Completed);
+        tm.add_task("Done today", TaskSection::Current);
+        tm.add_task("Dated", TaskSection::Completed);
+        tm.toggle_completion(TaskSection::Current, 0);
+        tm.completed[1].completed_on = Local::now().date_naive().checked_sub_days(Days::new(3));
+        assert_eq!(
+            tm.completed[2].completed_on,
+            Some(Local::now().date_naive())
+        );
+
+        // Tasks without a day are only archived with the rest
+        assert_eq!(tm.archivable(2), [1]);
+        assert!(tm.archivable(3).is_empty());
+        assert_eq!(tm.archivable(0), [0, 1, 2]);
+        assert!(tm.archive_tasks(&[0]).is_err());
+    }
+
+    #[test]
+    fn test_reset_day() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Plan", TaskSection::Backlog);
+        tm.add_task("Write", TaskSection::Current);
+        tm.add_task("Review", TaskSection::Current);
+        tm.add_task("Done", TaskSection::Completed);
+
+        assert!(tm.reset_day(&[]).is_ok());
+        let backlog: Vec<&str> = tm.backlog().iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(backlog, ["Write", "Review", "Plan"]);
+        assert!(tm.current().is_empty());
+        assert_eq!(tm.section_len(TaskSection::Completed), 1);
+
+        // Archiving needs a task file
+        tm.add_task("Write", TaskSection::Current);
+        assert!(tm.reset_day(&[0]).is_err());
+        assert_eq!(tm.section_len(TaskSection::Current), 1);
+    }
+
+    #[test]
+    fn test_reset_day_archives_completed() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Write\n- [x] Plan\n- [x] Review\n")?;
+        let mut tm = TaskManager::load(path, None)?;
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Current);
+
+        tm.reset_day(&[1])?;
+        let archive = fs::read_to_string(temp_dir.path().join("tasks-archive.md"))?;
+        assert_eq!(archive, "- [x] Review\n");
+        let completed: Vec<&str> = tm.completed().iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(completed, ["Plan"]);
+        assert_eq!(tm.backlog()[0].text, "Write");
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_day_keeps_current_when_archive_fails() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Write\n- [x] Plan\n")?;
+        // A folder in the way can't be written over, even as root
+        fs::create_dir(temp_dir.path().join("tasks-archive.md"))?;
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Current);
+
+        assert!(tm.reset_day(&[0]).is_err());
+        assert_eq!(tm.current()[0].text, "Write");
+        assert!(tm.backlog().is_empty());
+        assert_eq!(tm.section_len(TaskSection::Completed), 1);
+        assert_eq!(fs::read_to_string(&path)?, "- [ ] Write\n- [x] Plan\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_task_to_any_section() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Task 1", TaskSection::Backlog);
+        tm.add_task("Task 2", TaskSection::Backlog);
+        tm.dirty = false;
+
+        tm.move_task(TaskSection::Backlog, 1, TaskSection::Current);
+        assert_eq!(tm.current()[0].text, "Task 2");
+        assert!(!tm.is_dirty());
+
+        // Straight from backlog to completed
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Completed);
+        assert_eq!(tm.completed()[0].text, "Task 1");
+        assert!(tm.is_dirty());
+
+        // Moving within the same section does nothing
+        tm.move_task(TaskSection::Completed, 0, TaskSection::Completed);
+        assert_eq!(tm.section_len(TaskSection::Completed), 1);
+    }
+
+    #[test]
+    fn test_promote_task() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Task 1", TaskSection::Backlog);
+        tm.add_task("Task 2", TaskSection::Backlog);
+        tm.add_task("Task 3", TaskSection::Current);
+
+        tm.promote_task(1);
+        assert_eq!(tm.active_task().map(|t| t.text.as_str()), Some("Task 2"));
+        assert_eq!(tm.section_len(TaskSection::Backlog), 1);
+        assert_eq!(tm.section_len(TaskSection::Current), 2);
+
+        tm.promote_task(5);
+        assert_eq!(tm.section_len(TaskSection::Backlog), 1);
+
+        tm.activate_task(1);
+        assert_eq!(tm.active_task().map(|t| t.text.as_str()), Some("Task 3"));
+        assert_eq!(tm.current()[1].text, "Task 2");
+    }
+
+    #[test]
+    fn test_filter_by_tag() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Plan #work", TaskSection::Backlog);
+        tm.add_task("Shop #home", TaskSection::Backlog);
+        tm.add_task("Review #work", TaskSection::Backlog);
+        tm.add_task("Ship #work #release", TaskSection::Completed);
+        assert_eq!(tm.tags(), ["home", "release", "work"]);
+
+        let filter = TaskFilter {
+            tag: Some("work".to_string()),
+            ..TaskFilter::default()
+        };
+        assert_eq!(tm.filtered(TaskSection::Backlog, &filter), [0, 2]);
+        assert_eq!(tm.filtered(TaskSection::Completed, &filter), [0]);
+        assert_eq!(
+            tm.filtered(TaskSection::Backlog, &TaskFilter::default()),
+            [0, 1, 2]
+        );
+        assert_eq!(tm.section_len(TaskSection::Backlog), 3);
+    }
+
+    #[test]
+    fn test_sorted_oldest_first() {
+        let mut tm = TaskManager::new();
+        for (text, age) in [
+            ("Plan #work", Some(1)),
+            ("Shop", Some(5)),
+            ("Review #work", None),
+        ] {
+            tm.add_task(text, TaskSection::Backlog);
+            if let Some(task) = tm.backlog.last_mut() {
+                task.created_on =
+                    age.and_then(|age| Local::now().date_naive().checked_sub_days(Days::new(age)));
+            }
+        }
+        let filter = TaskFilter::default();
+        // Tasks of unknown age come first
+        assert_eq!(
+            tm.sorted(TaskSection::Backlog, &filter, SortMode::OldestFirst),
+            [2, 1, 0]
+        );
+        let work = TaskFilter {
+            tag: Some("work".to_string()),
+            ..TaskFilter::default()
+        };
+        assert_eq!(
+            tm.sorted(TaskSection::Backlog, &work, SortMode::Alphabetical),
+            [0, 2]
+        );
+        assert_eq!(
+            tm.sorted(TaskSection::Backlog, &filter, SortMode::Manual),
+            [0, 1, 2]
+        );
+    }
+
     #[test]
     fn test_active_task() {
         let mut tm = TaskManager::new();
@@ -400,31 +1547,50 @@ mod tests {
     #[test]
     fn test_reorder_tasks() {
         let mut tm = TaskManager::new();
+        let no_filter = TaskFilter::default();
         tm.add_task("Task 1".to_string(), TaskSection::Backlog);
         tm.add_task("Task 2".to_string(), TaskSection::Backlog);
         tm.add_task("Task 3".to_string(), TaskSection::Backlog);
 
         // Reorder down (swap 0 and 1)
-        tm.reorder_down(TaskSection::Backlog, 0);
+        tm.reorder_down(TaskSection::Backlog, 0, &no_filter);
         assert_eq!(tm.backlog()[0].text, "Task 2");
         assert_eq!(tm.backlog()[1].text, "Task 1");
         assert_eq!(tm.backlog()[2].text, "Task 3");
 
         // Reorder up (swap 1 and 2)
-        tm.reorder_up(TaskSection::Backlog, 2);
+        tm.reorder_up(TaskSection::Backlog, 2, &no_filter);
         assert_eq!(tm.backlog()[0].text, "Task 2");
         assert_eq!(tm.backlog()[1].text, "Task 3");
         assert_eq!(tm.backlog()[2].text, "Task 1");
 
         // Try to move first item up (should do nothing)
-        tm.reorder_up(TaskSection::Backlog, 0);
+        tm.reorder_up(TaskSection::Backlog, 0, &no_filter);
         assert_eq!(tm.backlog()[0].text, "Task 2");
 
         // Try to move last item down (should do nothing)
-        tm.reorder_down(TaskSection::Backlog, 2);
+        tm.reorder_down(TaskSection::Backlog, 2, &no_filter);
         assert_eq!(tm.backlog()[2].text, "Task 1");
     }
 
+    #[test]
+    fn test_reorder_skips_hidden_tasks() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Plan #work", TaskSection::Backlog);
+        tm.add_task("Shop", TaskSection::Backlog);
+        tm.add_task("Review #work", TaskSection::Backlog);
+        let filter = TaskFilter {
+            tag: Some("work".to_string()),
+            ..TaskFilter::default()
+        };
+
+        tm.reorder_down(TaskSection::Backlog, 0, &filter);
+        assert_eq!(tm.backlog()[0].text, "Review #work");
+        assert_eq!(tm.backlog()[1].text, "Shop");
+        tm.reorder_up(TaskSection::Backlog, 2, &filter);
+        assert_eq!(tm.backlog()[0].text, "Plan #work");
+    }
+
     #[test]
     fn test_delete_task_from_backlog() {
         let mut tm = TaskManager::new();
@@ -462,6 +1628,102 @@ mod tests {
         assert_eq!(tm.completed()[1].text, "Task 2");
     }
 
+    #[test]
+    fn test_edit_task() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Task 1", TaskSection::Current);
+        tm.current[0].pomodoros = 2;
+        tm.dirty = false;
+
+        tm.edit_task(TaskSection::Current, 0, "Task 1");
+        assert!(!tm.is_dirty());
+        tm.edit_task(TaskSection::Current, 0, "Renamed\ttask");
+        assert_eq!(tm.current()[0].text, "Renamed task");
+        assert_eq!(tm.current()[0].pomodoros, 2);
+        assert!(tm.is_dirty());
+
+        // Out of range is ignored
+        tm.edit_task(TaskSection::Backlog, 0, "Nothing");
+        assert!(tm.backlog().is_empty());
+    }
+
+    #[test]
+    fn test_merge_duplicates() {
+        let mut tm = TaskManager::new();
+        tm.add_task("Write report #work", TaskSection::Backlog);
+        tm.add_task("Plan week", TaskSection::Backlog);
+        tm.add_task("write report. #q3", TaskSection::Current);
+        tm.add_task("Write report", TaskSection::Completed);
+        tm.add_task("Plan week!", TaskSection::Backlog);
+        tm.backlog[0].pomodoros = 2;
+        tm.backlog[0].note = "Draft first".to_string();
+        tm.current[0].pomodoros = 1;
+
+        let groups = tm.duplicates();
+        assert_eq!(
+            groups,
+            [
+                vec![
+                    (TaskSection::Current, 0),
+                    (TaskSection::Backlog, 0),
+                    (TaskSection::Completed, 0)
+                ],
+                vec![(TaskSection::Backlog, 1), (TaskSection::Backlog, 2)],
+            ]
+        );
+
+        tm.dirty = false;
+        tm.merge_tasks(&groups[0]);
+        assert!(tm.is_dirty());
+        assert!(tm.completed().is_empty());
+        let merged = &tm.current()[0];
+        assert_eq!(merged.text, "write report. #q3 #work");
+        assert_eq!(merged.pomodoros, 3);
+        assert_eq!(merged.note, "Draft first");
+
+        // The kept task can sit below others in its own section
+        tm.merge_tasks(&[(TaskSection::Backlog, 1), (TaskSection::Backlog, 0)]);
+        assert_eq!(tm.section_len(TaskSection::Backlog), 1);
+        assert_eq!(tm.backlog()[0].text, "Plan week!");
+        assert!(tm.duplicates().is_empty());
+    }
+
+    #[test]
+    fn test_merged_tasks_go_to_trash() -> Result<(), io::Error> {
+        let mut tm = TaskManager::new();
+        tm.add_task("Plan week", TaskSection::Backlog);
+        tm.add_task("Plan week!", TaskSection::Completed);
+        tm.merge_tasks(&[(TaskSection::Backlog, 0), (TaskSection::Completed, 0)]);
+        assert!(tm.take_trash_error().is_none());
+        assert!(tm.completed().is_empty());
+
+        assert_eq!(tm.restore_deleted()?, 1);
+        assert_eq!(tm.completed()[0].text, "Plan week!");
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_deleted() -> Result<(), io::Error> {
+        let mut tm = TaskManager::new();
+        tm.add_task("Task 1", TaskSection::Current);
+        tm.add_task("Task 2", TaskSection::Backlog);
+        tm.add_task("Task 3", TaskSection::Backlog);
+        tm.current[0].pomodoros = 2;
+
+        tm.delete_task(TaskSection::Current, 0);
+        tm.delete_tasks(TaskSection::Backlog, &[0, 1]);
+        assert!(tm.backlog().is_empty());
+
+        // The last delete comes back first, whole
+        assert_eq!(tm.restore_deleted()?, 2);
+        assert_eq!(tm.section_len(TaskSection::Backlog), 2);
+        assert_eq!(tm.restore_deleted()?, 1);
+        assert_eq!(tm.current()[0].text, "Task 1");
+        assert_eq!(tm.current()[0].pomodoros, 2);
+        assert_eq!(tm.restore_deleted()?, 0);
+        Ok(())
+    }
+
     #[test]
     fn test_delete_task_invalid_index() {
         let mut tm = TaskManager::new();
@@ -480,5 +1742,316 @@ mod tests {
         // Try to delete from empty section (should do nothing)
         tm.delete_task(TaskSection::Backlog, 0);
         assert_eq!(tm.section_len(TaskSection::Backlog), 0);
+        assert!(!tm.is_dirty());
+    }
+
+    #[test]
+    fn test_sync_large_file() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        let lines: Vec<String> = (0..5000).map(|i| format!("- [ ] Task {i}")).collect();
+        fs::write(&path, lines.join("\n"))?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.cycle_task_section(TaskSection::Backlog, 3);
+        tm.toggle_completion(TaskSection::Current, 0);
+        tm.add_task("New in app".to_string(), TaskSection::Backlog);
+        fs::write(&path, lines.join("\n") + "\n- [x] New in file")?;
+
+        let items = tm.compute_sync_items()?;
+        let resolutions: Vec<(&str, SyncResolution)> = items
+            .iter()
+            .map(|item| (item.text.as_str(), item.resolution))
+            .collect();
+        assert_eq!(
+            resolutions,
+            [
+                ("New in file", SyncResolution::Complete),
+                ("Task 3", SyncResolution::Complete),
+                ("New in app", SyncResolution::Incomplete),
+            ]
+        );
+
+        tm.apply_sync(&items)?;
+        assert_eq!(tm.completed().len(), 2);
+        assert_eq!(tm.backlog().len(), 5000);
+        let content = fs::read_to_string(&path)?;
+        assert!(content.contains("- [x] Task 3\n"));
+        assert!(content.ends_with("- [x] New in file\n- [ ] New in app"));
+        assert!(tm.compute_sync_items()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_completion_dates() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Plan\n- [ ] Write\n")?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Completed);
+        tm.save()?;
+        assert_eq!(fs::read_to_string(&path)?, "- [x] Plan\n- [ ] Write\n");
+
+        tm.set_completion_dates(true);
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Completed);
+        tm.save()?;
+        let today = Local::now().date_naive();
+        assert_eq!(
+            fs::read_to_string(&path)?,
+            format!("- [x] Plan ✅ {today}\n- [x] Write ✅ {today}\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_only_writes_wait() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Plan\n")?;
+        let locks = tempfile::TempDir::new()?;
+
+        let _first = TaskManager::load(path.clone(), Some(locks.path().to_path_buf()))?;
+        let mut tm = TaskManager::load(path.clone(), Some(locks.path().to_path_buf()))?;
+        assert!(tm.is_read_only());
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Current);
+        tm.record_pomodoro()?;
+        tm.set_note(TaskSection::Current, 0, "Draft first")?;
+
+        assert_eq!(fs::read_to_string(&path)?, "- [ ] Plan\n");
+        assert!(tm.is_dirty());
+        assert_eq!(tm.section(TaskSection::Current)[0].pomodoros, 1);
+        // The lock is kept out of the task file's folder
+        assert_eq!(fs::read_dir(temp_dir.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_due_dates_pinned_in_file() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Call back due:tomorrow\n  Ask about it\n")?;
+
+        let tm = TaskManager::load(path.clone(), None)?;
+        let tomorrow = Local::now().date_naive().succ_opt().unwrap_or_default();
+        assert_eq!(
+            fs::read_to_string(&path)?,
+            format!("- [ ] Call back due:{tomorrow}\n  Ask about it\n")
+        );
+        assert_eq!(tm.section(TaskSection::Backlog)[0].due, Some(tomorrow));
+        assert_eq!(tm.unsynced_changes(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_creation_dates() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "")?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.add_task("Plan", TaskSection::Backlog);
+        tm.save()?;
+        assert_eq!(fs::read_to_string(&path)?, "- [ ] Plan");
+
+        tm.set_creation_dates(true);
+        tm.save()?;
+        let today = Local::now().date_naive();
+        assert_eq!(fs::read_to_string(&path)?, format!("- [ ] Plan ➕ {today}"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(
+            &path,
+            "- [ ] Plan\n- [ ] Write\n- [ ] Review\n- [ ] Gone outside\n",
+        )?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.delete_task(TaskSection::Backlog, 0);
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Completed);
+        tm.add_task("New in app", TaskSection::Backlog);
+        fs::write(
+            &path,
+            "- [ ] Plan\n- [ ] Write\n- [ ] Review\n- [ ] New in file\n",
+        )?;
+
+        tm.save()?;
+        assert!(!tm.is_dirty());
+        assert_eq!(
+            fs::read_to_string(&path)?,
+            "- [x] Write\n- [ ] Review\n- [ ] New in file\n- [ ] New in app\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_twice_keeps_file_changes() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] A\n- [ ] B\n")?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        fs::write(&path, "- [ ] A\n- [ ] X\n")?;
+        tm.add_task("N", TaskSection::Backlog);
+        tm.save()?;
+        tm.add_task("M", TaskSection::Backlog);
+        tm.save()?;
+
+        // X stays for a sync to bring in, and B stays deleted
+        let tasks = tm.file.as_ref().map(TaskFile::read_tasks).transpose()?;
+        let incomplete = tasks.map(|t| t.incomplete).unwrap_or_default();
+        assert_eq!(incomplete, ["A", "X", "N", "M"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_backup() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Plan\n- [ ] Write\n")?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.set_backups(Backups::in_dir(temp_dir.path().join("backups"), 5));
+        tm.delete_task(TaskSection::Backlog, 0);
+        tm.save()?;
+        assert_eq!(fs::read_to_string(&path)?, "- [ ] Write\n");
+
+        // The file as it was before the first write
+        let backups = tm.backups();
+        assert_eq!(backups.len(), 1);
+        tm.restore_backup(&backups[0])?;
+        assert_eq!(fs::read_to_string(&path)?, "- [ ] Plan\n- [ ] Write\n");
+        assert_eq!(tm.section_len(TaskSection::Backlog), 2);
+        assert!(!tm.is_dirty());
+
+        // What the restore replaced is kept too
+        assert_eq!(tm.backups().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsynced_changes() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Plan\n- [ ] Write\n- [ ] Review\n")?;
+
+        let mut tm = TaskManager::load(path, None)?;
+        assert_eq!(tm.unsynced_changes(), 0);
+        tm.delete_task(TaskSection::Backlog, 0);
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Completed);
+        tm.add_task("New", TaskSection::Backlog);
+        // Moving to Current changes nothing in the file
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Current);
+        assert_eq!(tm.unsynced_changes(), 3);
+
+        tm.save()?;
+        assert_eq!(tm.unsynced_changes(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_items_follow_changed_side() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Plan\n- [x] Write\n- [ ] Review\n")?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.move_task(TaskSection::Backlog, 1, TaskSection::Completed);
+        tm.delete_task(TaskSection::Backlog, 0);
+        fs::write(&path, "- [ ] Plan\n- [ ] Write\n- [ ] Review\n")?;
+
+        let resolutions: Vec<(String, SyncResolution)> = tm
+            .compute_sync_items()?
+            .into_iter()
+            .map(|item| (item.text, item.resolution))
+            .collect();
+        assert_eq!(
+            resolutions,
+            [
+                // Deleted in the app, not new to the file
+                ("Plan".to_string(), SyncResolution::Remove),
+                // Reopened in the file, where it used to be completed regardless
+                ("Write".to_string(), SyncResolution::Incomplete),
+                ("Review".to_string(), SyncResolution::Complete),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_sync_after_incidental_write() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] A\n")?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.move_task(TaskSection::Backlog, 0, TaskSection::Current);
+        fs::write(&path, "- [ ] A\n- [ ] X\n")?;
+        // Writes the whole file, X included, without syncing
+        tm.record_pomodoro()?;
+
+        let conflicts = tm.auto_sync()?;
+        assert!(conflicts.is_empty());
+        assert_eq!(fs::read_to_string(&path)?, "- [ ] A 🍅\n- [ ] X\n");
+        let texts: Vec<&str> = tm
+            .section(TaskSection::Backlog)
+            .iter()
+            .map(|t| t.text.as_str())
+            .collect();
+        assert_eq!(texts, ["X"]);
+        assert_eq!(tm.unsynced_changes(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_sync() -> Result<(), io::Error> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("tasks.md");
+        fs::write(&path, "- [ ] Plan\n- [ ] Write\n- [ ] Review\n")?;
+
+        let mut tm = TaskManager::load(path.clone(), None)?;
+        tm.delete_task(TaskSection::Backlog, 0);
+        tm.add_task("Both", TaskSection::Backlog);
+        fs::write(
+            &path,
+            "- [ ] Plan\n- [x] Write\n- [ ] Review\n- [ ] New in file\n- [x] Both\n",
+        )?;
+
+        // Added on both sides, but done on only one
+        let conflicts = tm.auto_sync()?;
+        let texts: Vec<&str> = conflicts.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(texts, ["Both"]);
+        assert!(tm.is_dirty());
+        let backlog: Vec<&str> = tm.backlog().iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(backlog, ["Review", "Both", "New in file"]);
+        assert_eq!(tm.completed()[0].text, "Write");
+        let content = fs::read_to_string(&path)?;
+        assert!(!content.contains("Plan"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dirty_until_synced() -> Result<(), io::Error> {
+        let mut tm = TaskManager::new();
+        tm.add_task("Task 1".to_string(), TaskSection::Backlog);
+        tm.add_task("Task 2".to_string(), TaskSection::Backlog);
+        assert!(tm.is_dirty());
+
+        tm.apply_sync(&[])?;
+        assert!(!tm.is_dirty());
+        assert!(tm.last_sync().is_some());
+
+        // Ordering and the current section are not stored in the file
+        tm.reorder_down(TaskSection::Backlog, 0, &TaskFilter::default());
+        tm.cycle_task_section(TaskSection::Backlog, 0);
+        assert!(!tm.is_dirty());
+
+        tm.complete_current_task();
+        assert!(tm.is_dirty());
+        Ok(())
     }
 }
@@ -0,0 +1,53 @@
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use serde::{de::Error, Deserialize, Deserializer};
+
+use crate::timer::SessionType;
+
+/// Colors used across panels and overlays, configured in the `[theme]` config section.
+///
+/// Colors accept any name or hex value understood by ratatui, e.g. `"cyan"` or `"#ff8800"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Focused borders, titles and cursors
+    #[serde(deserialize_with = "deserialize_color")]
+    pub accent: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub work: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub short_break: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub long_break: Color,
+    /// Countdown between sessions
+    #[serde(deserialize_with = "deserialize_color")]
+    pub get_ready: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent: Color::Cyan,
+            work: Color::Red,
+            short_break: Color::Green,
+            long_break: Color::Blue,
+            get_ready: Color::Magenta,
+        }
+    }
+}
+
+impl Theme {
+    pub const fn session_color(&self, session_type: SessionType) -> Color {
+        match session_type {
+            SessionType::Work => self.work,
+            SessionType::ShortBreak => self.short_break,
+            SessionType::LongBreak => self.long_break,
+        }
+    }
+}
+
+fn deserialize_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Color::from_str(&value).map_err(|_| D::Error::custom(format!("invalid color '{value}'")))
+}
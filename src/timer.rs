@@ -1,4 +1,9 @@
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Local, TimeDelta};
+use serde::Deserialize;
+
+use crate::config::Preset;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionType {
@@ -7,22 +12,168 @@ pub enum SessionType {
     LongBreak,
 }
 
+impl SessionType {
+    const ALL: [Self; 3] = [Self::Work, Self::ShortBreak, Self::LongBreak];
+
+    /// Identifier used in scripts and the history file
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Work => "work",
+            Self::ShortBreak => "short_break",
+            Self::LongBreak => "long_break",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Work => "Work",
+            Self::ShortBreak => "Short break",
+            Self::LongBreak => "Long break",
+        }
+    }
+}
+
+/// Whether work sessions count down a fixed length or up until stopped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimerMode {
+    #[default]
+    Pomodoro,
+    /// Open-ended work stopwatch, followed by a break proportional to the time worked
+    Flowtime,
+}
+
+impl TimerMode {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Pomodoro => "Pomodoro",
+            Self::Flowtime => "Flowtime",
+        }
+    }
+}
+
+/// Something that happened during a tick, for the app to alert on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerEvent {
+    /// Half of the session has run
+    Halfway,
+    /// The configured warning time before the end was reached
+    EndingSoon,
+    /// A work session reached zero and is counting overtime
+    OvertimeStarted,
+    /// The session finished and the timer moved on to the next one
+    Completed,
+    /// The machine slept this long while the session ran, which is now paused
+    Slept(Duration),
+    /// The get-ready countdown after a session ran out and the next session started
+    GraceOver,
+}
+
+/// What to do with a running session when the machine sleeps, set with `on_sleep` in `[timer]`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepPolicy {
+    /// Pause where the session was and ask whether to count the time asleep
+    #[default]
+    Ask,
+    /// Count the time asleep as if the session kept running
+    Count,
+}
+
+/// Work added each time a break is snoozed
+pub const SNOOZE_LENGTH: Duration = Duration::from_mins(5);
+/// Times one break can be snoozed
+pub const MAX_SNOOZES: u32 = 2;
+/// How long into a break it can still be snoozed
+const SNOOZE_WINDOW: Duration = Duration::from_mins(1);
+
+/// Where the upcoming break is in being put off with `Timer::snooze_break`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Deferral {
+    #[default]
+    None,
+    /// Working through a break snoozed this many times
+    Snoozed(u32),
+    /// Back at a break after snoozing it this many times
+    Due(u32),
+}
+
+/// Wall-clock time between ticks taken to mean the machine was asleep
+const SLEEP_GAP: Duration = Duration::from_mins(1);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimerState {
     Idle,
     Running,
     Paused,
+    /// Work session past zero, counting up until stopped
+    Overtime,
 }
 
+/// Four pomodoros with short breaks, then a long break
+const CLASSIC_SEQUENCE: [SessionType; 8] = [
+    SessionType::Work,
+    SessionType::ShortBreak,
+    SessionType::Work,
+    SessionType::ShortBreak,
+    SessionType::Work,
+    SessionType::ShortBreak,
+    SessionType::Work,
+    SessionType::LongBreak,
+];
+
 /// Pomodoro timer with work/break sessions and configurable durations
 pub struct Timer {
     state: TimerState,
+    mode: TimerMode,
     session_type: SessionType,
+    /// Time left, refreshed from `end` on every tick while running
     remaining: Duration,
     /// Completed work sessions
     sessions_completed: u32,
-    /// Time of last tick - None when paused/idle, Some when running
-    last_tick: Option<Instant>,
+    /// When the session will finish - None when paused/idle, Some when running
+    end: Option<Instant>,
+    /// Time spent running in the current session
+    elapsed: Duration,
+    /// Running time of the most recently completed session
+    last_elapsed: Duration,
+    /// When the current session was paused
+    pauses: Vec<DateTime<Local>>,
+    /// Pauses during the most recently completed session
+    last_pauses: Vec<DateTime<Local>>,
+    /// How many times faster than real time the countdown runs
+    speed: u32,
+    /// Longest a session can be made by adding minutes
+    max_duration: Duration,
+    /// Whether work sessions count up past zero instead of completing
+    overtime_enabled: bool,
+    /// Time counted past zero in the current session
+    overtime: Duration,
+    /// Flowtime breaks last this fraction, 1/N, of the work before them
+    flow_break_divisor: u32,
+    /// Break earned by the last flowtime work session
+    flow_break: Duration,
+    /// User-defined cycle of sessions, or empty for the classic four pomodoros and a long break
+    sequence: Vec<(SessionType, Duration)>,
+    /// Position of the current session in the cycle
+    step: usize,
+    /// Whether to report the halfway point
+    halfway_cue: bool,
+    /// Time before the end to report, zero for never
+    warn_before: Duration,
+    /// Work length set by the active task, in place of the configured one
+    task_duration: Option<Duration>,
+    sleep_policy: SleepPolicy,
+    /// Monotonic and wall-clock time of the last tick, to spot the machine sleeping
+    last_tick: Option<(Instant, SystemTime)>,
+    /// Countdown after each session before the next starts on its own, zero for none
+    grace_period: Duration,
+    /// When the next session starts, during the countdown after a completed one
+    grace_end: Option<Instant>,
+    deferral: Deferral,
 
     work_duration: Duration,
     short_break_duration: Duration,
@@ -31,16 +182,37 @@ pub struct Timer {
 
 impl Default for Timer {
     fn default() -> Self {
-        let work_duration = Duration::from_secs(25 * 60);
+        let work_duration = Duration::from_mins(25);
         Self {
             state: TimerState::Idle,
+            mode: TimerMode::Pomodoro,
             session_type: SessionType::Work,
             remaining: work_duration,
             sessions_completed: 0,
+            end: None,
+            elapsed: Duration::ZERO,
+            last_elapsed: Duration::ZERO,
+            pauses: Vec::new(),
+            last_pauses: Vec::new(),
+            speed: 1,
+            max_duration: Duration::from_hours(2),
+            overtime_enabled: false,
+            overtime: Duration::ZERO,
+            flow_break_divisor: 5,
+            flow_break: Duration::from_mins(5),
+            sequence: Vec::new(),
+            step: 0,
+            halfway_cue: false,
+            warn_before: Duration::ZERO,
+            task_duration: None,
+            sleep_policy: SleepPolicy::Ask,
             last_tick: None,
+            grace_period: Duration::ZERO,
+            grace_end: None,
+            deferral: Deferral::None,
             work_duration,
-            short_break_duration: Duration::from_secs(5 * 60),
-            long_break_duration: Duration::from_secs(15 * 60),
+            short_break_duration: Duration::from_mins(5),
+            long_break_duration: Duration::from_mins(15),
         }
     }
 }
@@ -50,6 +222,58 @@ impl Timer {
         self.session_type
     }
 
+    pub const fn sessions_completed(&self) -> u32 {
+        self.sessions_completed
+    }
+
+    pub const fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub const fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    pub const fn last_elapsed(&self) -> Duration {
+        self.last_elapsed
+    }
+
+    pub fn last_pauses(&self) -> u32 {
+        u32::try_from(self.last_pauses.len()).unwrap_or(u32::MAX)
+    }
+
+    /// When the most recently completed session was paused
+    pub fn last_paused_at(&self) -> &[DateTime<Local>] {
+        &self.last_pauses
+    }
+
+    /// When the current session was last paused
+    pub fn paused_at(&self) -> Option<DateTime<Local>> {
+        self.pauses.last().copied()
+    }
+
+    /// Length of the current session, including any time it has been stretched by
+    pub fn total(&self) -> Duration {
+        self.remaining + self.elapsed
+    }
+
+    /// Share of the current session still to run, from 1.0 at the start to 0.0 at the end
+    pub fn fraction_remaining(&self) -> f64 {
+        let total = self.total();
+        if total.is_zero() {
+            return 0.0;
+        }
+        self.remaining.as_secs_f64() / total.as_secs_f64()
+    }
+
+    /// Share of the current session run so far, from 0.0 at the start to 1.0 at the end
+    pub fn fraction_elapsed(&self) -> f64 {
+        if self.total().is_zero() {
+            return 0.0;
+        }
+        1.0 - self.fraction_remaining()
+    }
+
     pub fn is_idle(&self) -> bool {
         self.state == TimerState::Idle
     }
@@ -58,17 +282,89 @@ impl Timer {
         self.state == TimerState::Running
     }
 
+    pub fn is_paused(&self) -> bool {
+        self.state == TimerState::Paused
+    }
+
+    pub fn is_overtime(&self) -> bool {
+        self.state == TimerState::Overtime
+    }
+
+    pub const fn overtime(&self) -> Duration {
+        self.overtime
+    }
+
+    pub const fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Switch between pomodoro and flowtime between sessions
+    pub fn set_mode(&mut self, mode: TimerMode) {
+        if self.state == TimerState::Idle {
+            self.mode = mode;
+            self.remaining = self.duration_for_session(self.session_type);
+        }
+    }
+
+    /// Flowtime breaks last 1/`divisor` of the work before them
+    pub fn set_flow_break_divisor(&mut self, divisor: u32) {
+        self.flow_break_divisor = divisor.max(1);
+    }
+
     pub fn start(&mut self) {
-        if self.state != TimerState::Running {
-            self.state = TimerState::Running;
-            self.last_tick = Some(Instant::now());
+        if !matches!(self.state, TimerState::Idle | TimerState::Paused) {
+            return;
+        }
+        let now = Instant::now();
+        self.last_tick = None;
+        self.grace_end = None;
+        // A flowtime work session is all overtime, counting up from the start
+        if self.mode == TimerMode::Flowtime
+            && self.session_type == SessionType::Work
+            && self.remaining.is_zero()
+        {
+            self.state = TimerState::Overtime;
+            self.end = Some(now);
+            return;
         }
+        self.state = TimerState::Running;
+        self.end = Some(now + self.remaining / self.speed);
     }
 
     pub fn pause(&mut self) {
         if self.state == TimerState::Running {
+            self.sync_remaining(Instant::now());
             self.state = TimerState::Paused;
-            self.last_tick = None;
+            self.end = None;
+            self.pauses.push(Local::now());
+        }
+    }
+
+    /// Pick up a session from before a restart, paused with `elapsed` run and `remaining` left
+    pub fn restore(&mut self, session: SessionType, elapsed: Duration, remaining: Duration) {
+        if self.state == TimerState::Idle {
+            self.seek(session);
+            self.elapsed = elapsed;
+            self.remaining = remaining;
+            self.state = TimerState::Paused;
+        }
+    }
+
+    /// Give back up to `time` of the paused session, which was spent away rather than working
+    pub fn rewind(&mut self, time: Duration) {
+        if self.state == TimerState::Paused {
+            let time = time.min(self.elapsed);
+            self.elapsed -= time;
+            self.remaining += time;
+        }
+    }
+
+    /// Count `time` away from the paused session as if it had kept running
+    pub fn fast_forward(&mut self, time: Duration) {
+        if self.state == TimerState::Paused {
+            let time = (time * self.speed).min(self.remaining);
+            self.elapsed += time;
+            self.remaining -= time;
         }
     }
 
@@ -76,22 +372,79 @@ impl Timer {
         match self.state {
             TimerState::Idle | TimerState::Paused => self.start(),
             TimerState::Running => self.pause(),
+            TimerState::Overtime => {}
         }
     }
 
     pub fn reset(&mut self) {
         self.state = TimerState::Idle;
-        self.last_tick = None;
+        self.end = None;
+        self.grace_end = None;
+        self.deferral = Deferral::None;
+        self.elapsed = Duration::ZERO;
+        self.overtime = Duration::ZERO;
+        self.pauses.clear();
         self.remaining = self.duration_for_session(self.session_type);
     }
 
     pub fn set_session_type(&mut self, session_type: SessionType) {
         if self.state == TimerState::Idle {
-            self.session_type = session_type;
-            self.remaining = self.duration_for_session(session_type);
+            self.seek(session_type);
+        }
+    }
+
+    /// Move to the next step of the cycle with `session`, if it has one
+    fn seek(&mut self, session: SessionType) {
+        let len = self.sequence_len();
+        if let Some(step) = (0..len)
+            .map(|offset| (self.step + offset) % len)
+            .find(|&step| self.sequence_step(step).0 == session)
+        {
+            self.step = step;
+        }
+        self.session_type = session;
+        self.remaining = self.duration_for_session(session);
+        self.grace_end = None;
+        self.deferral = Deferral::None;
+    }
+
+    /// Replace the cycle of sessions; an empty one restores four pomodoros and a long break
+    pub fn set_sequence(&mut self, sequence: Vec<(SessionType, Duration)>) {
+        self.sequence = sequence;
+        self.step = 0;
+        if self.state == TimerState::Idle {
+            self.session_type = self.sequence_step(0).0;
+            self.remaining = self.duration_for_session(self.session_type);
+        }
+    }
+
+    /// Sessions of the cycle in order
+    pub fn sequence(&self) -> Vec<SessionType> {
+        (0..self.sequence_len())
+            .map(|step| self.sequence_step(step).0)
+            .collect()
+    }
+
+    /// Position of the current session in `sequence`
+    pub const fn step(&self) -> usize {
+        self.step
+    }
+
+    const fn sequence_len(&self) -> usize {
+        if self.sequence.is_empty() {
+            CLASSIC_SEQUENCE.len()
+        } else {
+            self.sequence.len()
         }
     }
 
+    fn sequence_step(&self, step: usize) -> (SessionType, Duration) {
+        self.sequence.get(step).copied().unwrap_or_else(|| {
+            let session = CLASSIC_SEQUENCE[step % CLASSIC_SEQUENCE.len()];
+            (session, self.fixed_duration(session))
+        })
+    }
+
     /// Cycle to the next session type (work → short break → long break → work)
     pub fn cycle_session_type(&mut self) {
         if self.state == TimerState::Idle {
@@ -100,64 +453,351 @@ impl Timer {
                 SessionType::ShortBreak => SessionType::LongBreak,
                 SessionType::LongBreak => SessionType::Work,
             };
-            self.session_type = next;
-            self.remaining = self.duration_for_session(next);
+            self.seek(next);
         }
     }
 
-    pub fn add_minute(&mut self) {
+    /// Skip the upcoming session without counting it, moving on to the one after
+    pub fn skip_session(&mut self) {
         if self.state == TimerState::Idle {
-            self.remaining += Duration::from_secs(60);
+            self.advance();
+            self.deferral = Deferral::None;
+        }
+    }
+
+    /// Put off a break that is up next or under a minute in, with `SNOOZE_LENGTH` more work
+    /// that doesn't count as a pomodoro. Returns how many times the break has now been
+    /// snoozed, or None if it can't be, at most `MAX_SNOOZES` times.
+    pub fn snooze_break(&mut self) -> Option<u32> {
+        let snoozes = match self.deferral {
+            Deferral::None => 0,
+            Deferral::Due(snoozes) => snoozes,
+            Deferral::Snoozed(_) => return None,
+        };
+        let just_started = match self.state {
+            TimerState::Idle => true,
+            TimerState::Running | TimerState::Paused => self.elapsed < SNOOZE_WINDOW,
+            TimerState::Overtime => false,
+        };
+        if self.session_type == SessionType::Work || !just_started || snoozes >= MAX_SNOOZES {
+            return None;
         }
+        self.reset();
+        self.extend_session(SessionType::Work, SNOOZE_LENGTH);
+        self.deferral = Deferral::Snoozed(snoozes + 1);
+        Some(snoozes + 1)
     }
 
-    pub fn subtract_minute(&mut self) {
-        if self.state == TimerState::Idle && self.remaining > Duration::from_secs(60) {
-            self.remaining -= Duration::from_secs(60);
+    /// Times the upcoming or current break has been snoozed
+    pub const fn snoozes(&self) -> u32 {
+        match self.deferral {
+            Deferral::None => 0,
+            Deferral::Snoozed(snoozes) | Deferral::Due(snoozes) => snoozes,
         }
     }
 
-    /// Decrement timer and return true if a session was completed
-    pub fn tick(&mut self) -> bool {
-        if self.state != TimerState::Running {
+    /// Resume a just-completed session for `extra` more time
+    pub fn extend_session(&mut self, session: SessionType, extra: Duration) {
+        if self.state == TimerState::Idle {
+            // Go back to the session's step, so the cycle carries on as if it had run longer
+            let previous = (self.step + self.sequence_len() - 1) % self.sequence_len();
+            if self.sequence_step(previous).0 == session {
+                self.step = previous;
+            }
+            self.session_type = session;
+            self.remaining = extra;
+            self.start();
+        }
+    }
+
+    /// Run the countdown `speed` times faster than real time from the next start, for demos
+    pub fn set_speed(&mut self, speed: u32) {
+        self.speed = speed.max(1);
+    }
+
+    /// Replace the session durations; an idle timer picks up the new length immediately
+    pub fn set_durations(&mut self, work: Duration, short_break: Duration, long_break: Duration) {
+        self.work_duration = work;
+        self.short_break_duration = short_break;
+        self.long_break_duration = long_break;
+        if self.state == TimerState::Idle {
+            self.remaining = self.duration_for_session(self.session_type);
+        }
+    }
+
+    /// Use the active task's work length, if it has one, for work sessions started from now
+    pub fn set_task_duration(&mut self, duration: Option<Duration>) {
+        let duration = duration.map(|d| d.min(self.max_duration));
+        if self.task_duration == duration {
+            return;
+        }
+        self.task_duration = duration;
+        if self.state == TimerState::Idle && self.session_type == SessionType::Work {
+            self.remaining = self.duration_for_session(SessionType::Work);
+        }
+    }
+
+    /// Switch to a preset's session lengths in the classic cycle, only while idle
+    pub fn apply_preset(&mut self, preset: &Preset) -> bool {
+        if self.state != TimerState::Idle {
             return false;
         }
+        let (work, short_break, long_break) = preset.durations();
+        self.set_durations(work, short_break, long_break);
+        self.set_sequence(Vec::new());
+        true
+    }
+
+    /// Let work sessions run past zero until `finish_overtime` is called
+    pub const fn set_overtime(&mut self, enabled: bool) {
+        self.overtime_enabled = enabled;
+    }
+
+    /// Stop counting overtime and complete the session, its running time including the overtime
+    pub fn finish_overtime(&mut self) {
+        if self.state == TimerState::Overtime {
+            self.sync_remaining(Instant::now());
+            self.complete_session();
+        }
+    }
 
-        if let Some(last) = self.last_tick {
-            let elapsed = last.elapsed();
-            self.last_tick = Some(Instant::now());
+    /// Wall-clock time the session would end if it ran on from now, shown as "ends at HH:MM"
+    /// under the digits; None when counting up or nothing is left
+    pub fn projected_end(&self) -> Option<DateTime<Local>> {
+        if self.state == TimerState::Overtime || self.remaining.is_zero() {
+            return None;
+        }
+        let remaining = TimeDelta::from_std(self.remaining / self.speed).ok()?;
+        Local::now().checked_add_signed(remaining)
+    }
 
-            if elapsed >= self.remaining {
-                self.remaining = Duration::ZERO;
-                self.complete_session();
-                return true;
+    /// Make the current session end at `target` and run it, returning false if that has passed.
+    ///
+    /// A paused session keeps its new length until resumed.
+    pub fn set_target_time(&mut self, target: DateTime<Local>) -> bool {
+        let Ok(remaining) = (target - Local::now()).to_std() else {
+            return false;
+        };
+        if self.state == TimerState::Overtime || remaining.is_zero() {
+            return false;
+        }
+        self.remaining = remaining;
+        match self.state {
+            TimerState::Idle => self.start(),
+            TimerState::Running => self.reschedule(Instant::now()),
+            TimerState::Paused | TimerState::Overtime => {}
+        }
+        true
+    }
+
+    /// Report `TimerEvent::Halfway` and, unless zero, `TimerEvent::EndingSoon` `warn_before`
+    /// the end of each session
+    pub const fn set_cues(&mut self, halfway: bool, warn_before: Duration) {
+        self.halfway_cue = halfway;
+        self.warn_before = warn_before;
+    }
+
+    pub const fn set_sleep_policy(&mut self, policy: SleepPolicy) {
+        self.sleep_policy = policy;
+    }
+
+    /// Count down `period` after each completed session, then start the next one
+    pub const fn set_grace_period(&mut self, period: Duration) {
+        self.grace_period = period;
+    }
+
+    /// Time until the next session starts on its own, during the countdown after a session
+    pub fn grace_remaining(&self) -> Option<Duration> {
+        self.grace_end
+            .map(|end| end.saturating_duration_since(Instant::now()) * self.speed)
+    }
+
+    /// Stop the countdown after a session, leaving the next one waiting to be started
+    pub const fn cancel_grace(&mut self) {
+        self.grace_end = None;
+    }
+
+    /// Cap on the session length `add_minutes` can reach
+    pub const fn set_max_duration(&mut self, max: Duration) {
+        self.max_duration = max;
+    }
+
+    /// Lengthen the session by `minutes`, up to the maximum; works while running too
+    pub fn add_minutes(&mut self, minutes: u64) {
+        if self.state == TimerState::Overtime {
+            return;
+        }
+        let now = Instant::now();
+        self.sync_remaining(now);
+        let total =
+            (self.elapsed + self.remaining + Duration::from_mins(minutes)).min(self.max_duration);
+        if total > self.elapsed + self.remaining {
+            self.remaining = total.saturating_sub(self.elapsed);
+            self.reschedule(now);
+        }
+    }
+
+    /// Shorten the session by `minutes`, leaving at least a minute to go
+    pub fn subtract_minutes(&mut self, minutes: u64) {
+        if self.state == TimerState::Overtime {
+            return;
+        }
+        let now = Instant::now();
+        self.sync_remaining(now);
+        let step = Duration::from_mins(minutes);
+        if self.remaining > step {
+            self.remaining -= step;
+        } else if self.remaining > Duration::from_mins(1) {
+            self.remaining = Duration::from_mins(1);
+        } else {
+            return;
+        }
+        self.reschedule(now);
+    }
+
+    /// Move the end instant of a running session to match `remaining`
+    fn reschedule(&mut self, now: Instant) {
+        if self.end.is_some() {
+            self.end = Some(now + self.remaining / self.speed);
+        }
+    }
+
+    /// Update the remaining time from the end instant, reporting a completed session or a cue
+    /// passed since the last tick.
+    ///
+    /// With overtime enabled a work session reaching zero moves to `TimerState::Overtime` instead.
+    pub fn tick(&mut self) -> Option<TimerEvent> {
+        if self.state == TimerState::Idle {
+            if self.grace_end.is_some_and(|end| Instant::now() >= end) {
+                self.start();
+                return Some(TimerEvent::GraceOver);
             }
-            self.remaining -= elapsed;
+            return None;
+        }
+        if !matches!(self.state, TimerState::Running | TimerState::Overtime) {
+            return None;
+        }
+
+        let now = Instant::now();
+        if let Some((gap, counted)) = self.sleep_gap(now, SystemTime::now()) {
+            if self.sleep_policy == SleepPolicy::Ask && self.state == TimerState::Running {
+                // Stop where the last tick left off, before the machine slept
+                self.state = TimerState::Paused;
+                self.end = None;
+                self.pauses.push(Local::now());
+                return Some(TimerEvent::Slept(gap));
+            }
+            // The monotonic clock may not run during suspend, so catch up to the wall clock
+            if let Some(end) = self
+                .end
+                .and_then(|end| end.checked_sub(gap.saturating_sub(counted)))
+            {
+                self.end = Some(end);
+            }
+        }
+        let before = self.remaining;
+        self.sync_remaining(now);
+        if self.state != TimerState::Running {
+            return None;
+        }
+        if self.remaining.is_zero() {
+            if self.overtime_enabled && self.session_type == SessionType::Work {
+                self.state = TimerState::Overtime;
+                self.sync_remaining(now);
+                return Some(TimerEvent::OvertimeStarted);
+            }
+            self.complete_session();
+            return Some(TimerEvent::Completed);
+        }
+
+        let passed = |mark: Duration| before > mark && self.remaining <= mark;
+        let total = self.elapsed + self.remaining;
+        if !self.warn_before.is_zero() && self.warn_before < total && passed(self.warn_before) {
+            Some(TimerEvent::EndingSoon)
+        } else if self.halfway_cue && passed(total / 2) {
+            Some(TimerEvent::Halfway)
+        } else {
+            None
+        }
+    }
+
+    /// Wall-clock and monotonic time since the last tick, if long enough to mean the machine slept
+    fn sleep_gap(&mut self, now: Instant, wall_now: SystemTime) -> Option<(Duration, Duration)> {
+        let (instant, wall) = self.last_tick.replace((now, wall_now))?;
+        let gap = wall_now.duration_since(wall).unwrap_or_default();
+        (gap >= SLEEP_GAP).then(|| (gap, now.saturating_duration_since(instant)))
+    }
+
+    /// Derive `remaining` from the end instant, so long gaps between ticks never accumulate drift
+    fn sync_remaining(&mut self, now: Instant) {
+        if let (TimerState::Overtime, Some(end)) = (self.state, self.end) {
+            let overtime = now.saturating_duration_since(end) * self.speed;
+            self.elapsed += overtime.saturating_sub(self.overtime);
+            self.overtime = overtime;
+        } else if let Some(end) = self.end {
+            let remaining = end.saturating_duration_since(now) * self.speed;
+            self.elapsed += self.remaining.saturating_sub(remaining);
+            self.remaining = remaining;
         }
-        false
     }
 
     /// Complete current session and transition to next session type
     fn complete_session(&mut self) {
+        let deferral = match (self.session_type, self.deferral) {
+            // Snoozing adds to the pomodoro before, rather than being one
+            (SessionType::Work, Deferral::Snoozed(snoozes)) => Deferral::Due(snoozes),
+            _ => Deferral::None,
+        };
         match self.session_type {
+            SessionType::Work if deferral != Deferral::None => {}
             SessionType::Work => {
-                self.sessions_completed += 1;
-                if self.sessions_completed.is_multiple_of(4) {
-                    self.session_type = SessionType::LongBreak;
-                } else {
-                    self.session_type = SessionType::ShortBreak;
+                if self.mode == TimerMode::Flowtime {
+                    self.flow_break =
+                        (self.elapsed / self.flow_break_divisor).max(Duration::from_mins(1));
                 }
+                self.sessions_completed += 1;
             }
-            SessionType::ShortBreak | SessionType::LongBreak => {
-                self.session_type = SessionType::Work;
-            }
+            SessionType::ShortBreak | SessionType::LongBreak => {}
         }
-        self.remaining = self.duration_for_session(self.session_type);
+        self.advance();
         self.state = TimerState::Idle;
-        self.last_tick = None;
+        self.end = None;
+        self.last_elapsed = std::mem::take(&mut self.elapsed);
+        self.last_pauses = std::mem::take(&mut self.pauses);
+        self.overtime = Duration::ZERO;
+        self.grace_end =
+            (!self.grace_period.is_zero()).then(|| Instant::now() + self.grace_period / self.speed);
+        self.deferral = deferral;
     }
 
-    const fn duration_for_session(&self, session: SessionType) -> Duration {
+    /// Move on to the next step of the cycle
+    fn advance(&mut self) {
+        self.step = (self.step + 1) % self.sequence_len();
+        self.session_type = self.sequence_step(self.step).0;
+        self.remaining = self.duration_for_session(self.session_type);
+    }
+
+    /// Length of `session` at the current step, or its configured length off the cycle
+    fn duration_for_session(&self, session: SessionType) -> Duration {
+        match (self.mode, session) {
+            (TimerMode::Flowtime, SessionType::Work) => Duration::ZERO,
+            (TimerMode::Flowtime, _) => self.flow_break,
+            (TimerMode::Pomodoro, SessionType::Work) => self
+                .task_duration
+                .unwrap_or_else(|| self.cycle_duration(session)),
+            (TimerMode::Pomodoro, _) => self.cycle_duration(session),
+        }
+    }
+
+    /// Length of `session` in the cycle, or the configured length if the cycle is elsewhere
+    fn cycle_duration(&self, session: SessionType) -> Duration {
+        match self.sequence_step(self.step) {
+            (step_session, duration) if step_session == session => duration,
+            _ => self.fixed_duration(session),
+        }
+    }
+
+    const fn fixed_duration(&self, session: SessionType) -> Duration {
         match session {
             SessionType::Work => self.work_duration,
             SessionType::ShortBreak => self.short_break_duration,
@@ -172,6 +812,11 @@ impl Timer {
     pub const fn seconds(&self) -> u64 {
         self.remaining.as_secs() % 60
     }
+
+    /// Tenths of a second remaining past the whole seconds
+    pub const fn tenths(&self) -> u32 {
+        self.remaining.subsec_millis() / 100
+    }
 }
 
 #[cfg(test)]
@@ -226,7 +871,26 @@ mod tests {
         timer.reset();
         assert_eq!(timer.state, TimerState::Idle);
         assert_eq!(timer.minutes(), 25);
-        assert_eq!(timer.remaining, Duration::from_secs(25 * 60));
+        assert_eq!(timer.remaining, Duration::from_mins(25));
+    }
+
+    #[test]
+    fn test_skip_and_extend_session() {
+        let mut timer = Timer::default();
+        timer.skip_session();
+        assert_eq!(timer.session_type, SessionType::ShortBreak);
+        assert_eq!(timer.sessions_completed, 0);
+        timer.skip_session();
+        assert_eq!(timer.session_type, SessionType::Work);
+
+        timer.extend_session(SessionType::LongBreak, Duration::from_mins(5));
+        assert!(timer.is_running());
+        assert_eq!(timer.session_type, SessionType::LongBreak);
+        assert_eq!(timer.minutes(), 5);
+
+        // Only applies between sessions
+        timer.skip_session();
+        assert_eq!(timer.session_type, SessionType::LongBreak);
     }
 
     #[test]
@@ -235,32 +899,263 @@ mod tests {
         assert_eq!(timer.minutes(), 25);
 
         // Add minute
-        timer.add_minute();
+        timer.add_minutes(1);
         assert_eq!(timer.minutes(), 26);
 
         // Subtract minute
-        timer.subtract_minute();
+        timer.subtract_minutes(1);
         assert_eq!(timer.minutes(), 25);
     }
 
     #[test]
     fn test_subtract_minute_minimum() {
         let mut timer = Timer {
-            remaining: Duration::from_secs(60),
+            remaining: Duration::from_mins(1),
             ..Default::default()
         };
-        timer.subtract_minute();
+        timer.subtract_minutes(1);
         // Should not go below 1 minute
-        assert_eq!(timer.remaining, Duration::from_secs(60));
+        assert_eq!(timer.remaining, Duration::from_mins(1));
     }
 
     #[test]
-    fn test_cannot_adjust_time_when_running() {
+    fn test_set_durations() {
         let mut timer = Timer::default();
+        timer.set_durations(
+            Duration::from_mins(50),
+            Duration::from_mins(10),
+            Duration::from_mins(30),
+        );
+        assert_eq!(timer.minutes(), 50);
+
+        // A running session keeps its remaining time
         timer.start();
-        let initial = timer.remaining;
-        timer.add_minute();
-        assert_eq!(timer.remaining, initial); // No change
+        timer.set_durations(
+            Duration::from_mins(40),
+            Duration::from_mins(10),
+            Duration::from_mins(30),
+        );
+        assert_eq!(timer.minutes(), 50);
+        assert_eq!(timer.work_duration, Duration::from_mins(40));
+    }
+
+    #[test]
+    fn test_adjust_time_when_running() {
+        let mut timer = Timer::default();
+        timer.start();
+        timer.add_minutes(1);
+        assert_eq!(timer.minutes(), 25);
+        assert!(timer
+            .end
+            .is_some_and(|end| end > Instant::now() + Duration::from_mins(25)));
+
+        timer.subtract_minutes(1);
+        timer.subtract_minutes(1);
+        assert_eq!(timer.minutes(), 23);
+    }
+
+    #[test]
+    fn test_overtime_counts_up_until_finished() {
+        let mut timer = Timer {
+            remaining: Duration::ZERO,
+            elapsed: Duration::from_mins(25),
+            ..Default::default()
+        };
+        timer.set_overtime(true);
+        timer.start();
+        timer.end = Instant::now().checked_sub(Duration::from_secs(90));
+
+        assert_eq!(timer.tick(), Some(TimerEvent::OvertimeStarted));
+        assert!(timer.is_overtime());
+        assert_eq!(timer.overtime().as_secs(), 90);
+        assert_eq!(timer.elapsed().as_secs(), 25 * 60 + 90);
+
+        // Space does not pause overtime, it has to be finished
+        timer.toggle();
+        assert!(timer.is_overtime());
+
+        timer.finish_overtime();
+        assert!(timer.is_idle());
+        assert_eq!(timer.session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.last_elapsed().as_secs(), 25 * 60 + 90);
+        assert_eq!(timer.overtime(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_flowtime_break_is_proportional() {
+        let mut timer = Timer::default();
+        timer.set_mode(TimerMode::Flowtime);
+        assert_eq!(timer.remaining, Duration::ZERO);
+
+        timer.start();
+        assert!(timer.is_overtime());
+        timer.end = Instant::now().checked_sub(Duration::from_mins(50));
+        timer.tick();
+        assert_eq!(timer.elapsed().as_secs() / 60, 50);
+
+        timer.finish_overtime();
+        assert_eq!(timer.session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.minutes(), 10);
+
+        // The break counts down as usual
+        timer.start();
+        assert!(timer.is_running());
+    }
+
+    #[test]
+    fn test_set_target_time() {
+        let mut timer = Timer::default();
+        assert!(!timer.set_target_time(Local::now() - Duration::from_mins(1)));
+        assert!(timer.is_idle());
+
+        assert!(timer.set_target_time(Local::now() + Duration::from_mins(90)));
+        assert!(timer.is_running());
+        assert_eq!(timer.minutes(), 89);
+    }
+
+    #[test]
+    fn test_projected_end() {
+        let mut timer = Timer::default();
+        let end = timer.projected_end().unwrap_or_default() - Local::now();
+        assert_eq!(end.num_minutes(), 24);
+
+        timer.set_overtime(true);
+        timer.start();
+        timer.end = Some(Instant::now());
+        timer.tick();
+        assert_eq!(timer.projected_end(), None);
+    }
+
+    #[test]
+    fn test_task_duration() {
+        let mut timer = Timer::default();
+        timer.set_task_duration(Some(Duration::from_mins(50)));
+        assert_eq!(timer.minutes(), 50);
+
+        // A tweak while idle sticks until the task changes
+        timer.add_minutes(1);
+        timer.set_task_duration(Some(Duration::from_mins(50)));
+        assert_eq!(timer.minutes(), 51);
+
+        timer.set_task_duration(None);
+        assert_eq!(timer.minutes(), 25);
+    }
+
+    #[test]
+    fn test_custom_sequence() {
+        let mut timer = Timer::default();
+        timer.set_sequence(vec![
+            (SessionType::Work, Duration::from_mins(52)),
+            (SessionType::ShortBreak, Duration::from_mins(17)),
+        ]);
+        assert_eq!(timer.minutes(), 52);
+        assert_eq!(
+            timer.sequence(),
+            [SessionType::Work, SessionType::ShortBreak]
+        );
+
+        timer.complete_session();
+        assert_eq!((timer.step(), timer.minutes()), (1, 17));
+        timer.complete_session();
+        assert_eq!((timer.step(), timer.minutes()), (0, 52));
+
+        // Sessions missing from the cycle fall back to their configured length
+        timer.set_session_type(SessionType::LongBreak);
+        assert_eq!((timer.step(), timer.minutes()), (0, 15));
+        timer.set_session_type(SessionType::ShortBreak);
+        assert_eq!((timer.step(), timer.minutes()), (1, 17));
+    }
+
+    #[test]
+    fn test_tick_reports_cues() {
+        let mut timer = Timer::default();
+        timer.set_cues(true, Duration::from_mins(2));
+        timer.start();
+        assert_eq!(timer.tick(), None);
+
+        timer.end = Some(Instant::now() + Duration::from_mins(12));
+        assert_eq!(timer.tick(), Some(TimerEvent::Halfway));
+        assert_eq!(timer.tick(), None);
+
+        timer.end = Some(Instant::now() + Duration::from_secs(90));
+        assert_eq!(timer.tick(), Some(TimerEvent::EndingSoon));
+        assert_eq!(timer.tick(), None);
+    }
+
+    #[test]
+    fn test_rewind_gives_back_elapsed_time() {
+        let mut timer = Timer::default();
+        timer.start();
+        timer.end = Some(Instant::now() + Duration::from_mins(15));
+        timer.tick();
+        timer.pause();
+        timer.rewind(Duration::from_mins(4));
+        assert_eq!(timer.minutes(), 18);
+
+        // Never before the start of the session
+        timer.rewind(Duration::from_hours(1));
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+        assert_eq!(timer.minutes(), 25);
+    }
+
+    #[test]
+    fn test_sleep_gap() {
+        fn sleep_ten_minutes(timer: &mut Timer) {
+            timer.last_tick = timer.last_tick.and_then(|(instant, wall)| {
+                Some((instant, wall.checked_sub(Duration::from_mins(10))?))
+            });
+        }
+
+        let mut timer = Timer::default();
+        timer.start();
+        timer.tick();
+        sleep_ten_minutes(&mut timer);
+        assert!(matches!(
+            timer.tick(),
+            Some(TimerEvent::Slept(slept)) if slept >= Duration::from_mins(10)
+        ));
+        assert!(timer.is_paused());
+        assert_eq!(timer.minutes(), 24);
+        timer.fast_forward(Duration::from_mins(10));
+        assert_eq!(timer.minutes(), 14);
+
+        let mut timer = Timer::default();
+        timer.set_sleep_policy(SleepPolicy::Count);
+        timer.start();
+        timer.tick();
+        sleep_ten_minutes(&mut timer);
+        assert_eq!(timer.tick(), None);
+        assert!(timer.is_running());
+        assert_eq!(timer.minutes(), 14);
+    }
+
+    #[test]
+    fn test_add_minute_cap() {
+        let mut timer = Timer::default();
+        timer.set_max_duration(Duration::from_mins(26));
+        timer.add_minutes(1);
+        timer.add_minutes(1);
+        assert_eq!(timer.remaining, Duration::from_mins(26));
+    }
+
+    #[test]
+    fn test_add_five_minutes_stops_at_cap() {
+        let mut timer = Timer::default();
+        timer.set_max_duration(Duration::from_mins(28));
+        timer.add_minutes(1);
+        timer.add_minutes(5);
+        assert_eq!(timer.remaining, Duration::from_mins(28));
+    }
+
+    #[test]
+    fn test_subtract_five_minutes_leaves_one() {
+        let mut timer = Timer::default();
+        timer.start();
+        for _ in 0..5 {
+            timer.subtract_minutes(5);
+        }
+        assert_eq!(timer.remaining, Duration::from_mins(1));
+        assert!(timer.is_running());
     }
 
     #[test]
@@ -297,29 +1192,132 @@ mod tests {
         timer.pause();
         let initial = timer.remaining;
         std::thread::sleep(Duration::from_millis(100));
-        let completed = timer.tick();
-        assert!(!completed);
+        assert_eq!(timer.tick(), None);
         assert_eq!(timer.remaining, initial);
     }
 
     #[test]
-    fn test_session_completion_flow() {
+    fn test_remaining_derived_from_end() {
+        let mut timer = Timer {
+            remaining: Duration::from_secs(10),
+            ..Default::default()
+        };
+        timer.start();
+        let Some(end) = timer.end else {
+            panic!("running timer has an end instant");
+        };
+
+        timer.sync_remaining(end.checked_sub(Duration::from_secs(4)).unwrap_or(end));
+        assert_eq!(timer.remaining, Duration::from_secs(4));
+        assert_eq!(timer.elapsed(), Duration::from_secs(6));
+
+        // A long gap, e.g. a stalled event loop, lands exactly on the end
+        timer.sync_remaining(end + Duration::from_mins(5));
+        assert_eq!(timer.remaining, Duration::ZERO);
+        assert_eq!(timer.elapsed(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_speed_scales_countdown() {
+        let mut timer = Timer {
+            remaining: Duration::from_mins(1),
+            ..Default::default()
+        };
+        timer.set_speed(60);
+        timer.start();
+        let Some(end) = timer.end else {
+            panic!("running timer has an end instant");
+        };
+
+        // Half a real second is half a timer minute
+        timer.sync_remaining(end.checked_sub(Duration::from_millis(500)).unwrap_or(end));
+        assert_eq!(timer.remaining, Duration::from_secs(30));
+        assert_eq!(timer.elapsed(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_grace_period_starts_next_session() {
         let mut timer = Timer::default();
+        timer.set_grace_period(Duration::from_secs(10));
+        timer.start();
+        timer.complete_session();
+        assert!(timer.is_idle());
+        assert!(timer
+            .grace_remaining()
+            .is_some_and(|left| left > Duration::from_secs(9)));
+        assert_eq!(timer.tick(), None);
+
+        // The countdown runs out
+        timer.grace_end = Some(Instant::now());
+        assert_eq!(timer.tick(), Some(TimerEvent::GraceOver));
+        assert!(timer.is_running());
+        assert_eq!(timer.session_type, SessionType::ShortBreak);
+        assert_eq!(timer.grace_remaining(), None);
 
-        // Complete work session → short break
+        // Resetting leaves the next session waiting
+        timer.complete_session();
+        timer.reset();
+        assert_eq!(timer.grace_remaining(), None);
+        assert_eq!(timer.tick(), None);
+        assert!(timer.is_idle());
+    }
+
+    #[test]
+    fn test_snooze_break() {
+        let mut timer = Timer::default();
+        timer.start();
+        timer.complete_session();
+        assert_eq!(timer.sessions_completed, 1);
+
+        // Snoozed while up next, then again just after starting
+        assert_eq!(timer.snooze_break(), Some(1));
+        assert!(timer.is_running());
+        assert_eq!(timer.session_type, SessionType::Work);
+        assert_eq!(timer.remaining(), SNOOZE_LENGTH);
+        assert_eq!(timer.snooze_break(), None);
+        timer.complete_session();
+        assert_eq!(timer.session_type, SessionType::ShortBreak);
+        assert_eq!(timer.sessions_completed, 1);
+        timer.start();
+        assert_eq!(timer.snooze_break(), Some(2));
+        timer.complete_session();
+
+        // Twice is the most
+        assert_eq!(timer.snooze_break(), None);
+        assert_eq!(timer.snoozes(), 2);
+
+        // A break well under way can't be snoozed either
+        timer.skip_session();
+        timer.skip_session();
+        timer.start();
+        timer.elapsed = SNOOZE_WINDOW;
+        assert_eq!(timer.snooze_break(), None);
+    }
+
+    #[test]
+    fn test_session_completion_flow() {
+        let mut timer = Timer {
+            remaining: Duration::from_secs(1),
+            ..Default::default()
+        };
+
+        // Complete work session → short break, interrupted once
+        timer.start();
+        timer.pause();
         timer.start();
-        timer.remaining = Duration::from_secs(1);
         std::thread::sleep(Duration::from_millis(1100));
-        let completed = timer.tick();
-        assert!(completed);
+        assert_eq!(timer.tick(), Some(TimerEvent::Completed));
         assert_eq!(timer.state, TimerState::Idle);
         assert_eq!(timer.session_type, SessionType::ShortBreak);
         assert_eq!(timer.sessions_completed, 1);
         assert_eq!(timer.minutes(), 5);
+        assert_eq!(timer.last_elapsed(), Duration::from_secs(1));
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+        assert_eq!(timer.last_pauses(), 1);
 
         // Complete short break → work
-        timer.start();
         timer.remaining = Duration::from_secs(1);
+        timer.start();
         std::thread::sleep(Duration::from_millis(1100));
         timer.tick();
         assert_eq!(timer.session_type, SessionType::Work);
@@ -327,21 +1325,21 @@ mod tests {
 
         // Complete 2 more work+break cycles (sessions 2-3)
         for _ in 0..2 {
-            timer.start();
             timer.remaining = Duration::from_secs(1);
+            timer.start();
             std::thread::sleep(Duration::from_millis(1100));
             timer.tick();
             assert_eq!(timer.session_type, SessionType::ShortBreak);
-            timer.start();
             timer.remaining = Duration::from_secs(1);
+            timer.start();
             std::thread::sleep(Duration::from_millis(1100));
             timer.tick();
         }
         assert_eq!(timer.sessions_completed, 3);
 
         // 4th work session → long break
-        timer.start();
         timer.remaining = Duration::from_secs(1);
+        timer.start();
         std::thread::sleep(Duration::from_millis(1100));
         timer.tick();
         assert_eq!(timer.sessions_completed, 4);
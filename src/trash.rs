@@ -0,0 +1,238 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::fileio::{split_created, split_done, with_created, with_done};
+use crate::task::{Task, TaskSection};
+
+/// Days a deleted task is kept before it is purged for good
+const KEEP_DAYS: i64 = 30;
+
+/// A deleted task, stored one per line as
+/// `<deleted RFC 3339>\t<section>\t<pomodoros>\t<task>\t<note line>...`, where the task
+/// carries the day it was added and completed as the task file writes them
+#[derive(Debug, Clone)]
+pub struct TrashedTask {
+    /// Shared by the tasks of one delete, so they are restored together
+    pub deleted: DateTime<Local>,
+    pub section: TaskSection,
+    pub task: Task,
+}
+
+impl TrashedTask {
+    fn to_line(&self) -> String {
+        let section = match self.section {
+            TaskSection::Backlog => "backlog",
+            TaskSection::Current => "current",
+            TaskSection::Completed => "completed",
+        };
+        let mut fields = vec![
+            self.deleted.to_rfc3339(),
+            section.to_string(),
+            self.task.pomodoros.to_string(),
+            with_done(
+                &with_created(&self.task.text, self.task.created_on),
+                self.task.completed_on,
+            )
+            .replace('\t', " "),
+        ];
+        fields.extend(self.task.note.lines().map(|line| line.replace('\t', " ")));
+        fields.join("\t")
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let deleted = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+        let section = match fields.next()? {
+            "backlog" => TaskSection::Backlog,
+            "current" => TaskSection::Current,
+            "completed" => TaskSection::Completed,
+            _ => return None,
+        };
+        let pomodoros = fields.next()?.parse().ok()?;
+        let (text, completed_on) = split_done(fields.next().filter(|t| !t.is_empty())?);
+        let (text, created_on) = split_created(text);
+        let note: Vec<&str> = fields.collect();
+        Some(Self {
+            deleted: deleted.with_timezone(&Local),
+            section,
+            task: Task {
+                pomodoros,
+                note: note.join("\n"),
+                completed_on,
+                created_on,
+                ..Task::new(text.to_string())
+            },
+        })
+    }
+}
+
+/// Deleted tasks, kept in `trash.tsv` in the cache directory so a mistaken delete can be
+/// undone later, even after a restart
+#[derive(Default)]
+pub struct Trash {
+    path: Option<PathBuf>,
+    entries: Vec<TrashedTask>,
+}
+
+impl Trash {
+    pub const fn new() -> Self {
+        Self {
+            path: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Load the trash file, starting empty if it is missing and dropping anything past its
+    /// time; unreadable lines are skipped
+    pub fn load(path: PathBuf) -> (Self, Option<String>) {
+        let (entries, error) = match fs::read_to_string(&path) {
+            Ok(content) => (
+                content.lines().filter_map(TrashedTask::parse).collect(),
+                None,
+            ),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Vec::new(), None),
+            Err(e) => (Vec::new(), Some(format!("Failed to load trash: {e}"))),
+        };
+        let mut trash = Self {
+            path: Some(path),
+            entries,
+        };
+        let error = error.or_else(|| {
+            if trash.purge(Local::now()) {
+                trash
+                    .save()
+                    .err()
+                    .map(|e| format!("Failed to purge trash: {e}"))
+            } else {
+                None
+            }
+        });
+        (trash, error)
+    }
+
+    /// Keep tasks deleted together from `section`, purging any past their time
+    pub fn add(&mut self, section: TaskSection, tasks: Vec<Task>) -> Result<(), io::Error> {
+        self.add_from(tasks.into_iter().map(|task| (section, task)).collect())
+    }
+
+    /// Keep tasks deleted together from their sections, so they are restored together
+    pub fn add_from(&mut self, tasks: Vec<(TaskSection, Task)>) -> Result<(), io::Error> {
+        let deleted = Local::now();
+        self.entries
+            .extend(tasks.into_iter().map(|(section, task)| TrashedTask {
+                deleted,
+                section,
+                task,
+            }));
+        self.purge(deleted);
+        self.save()
+    }
+
+    /// Take back the tasks of the most recent delete, in the order they were listed
+    pub fn restore_last(&mut self) -> Result<Vec<TrashedTask>, io::Error> {
+        let Some(last) = self.entries.last().map(|entry| entry.deleted) else {
+            return Ok(Vec::new());
+        };
+        let start = self.entries.partition_point(|entry| entry.deleted < last);
+        let restored = self.entries.split_off(start);
+        self.save()?;
+        Ok(restored)
+    }
+
+    /// Drop tasks deleted more than `KEEP_DAYS` before `now`, returning whether there were any
+    fn purge(&mut self, now: DateTime<Local>) -> bool {
+        let cutoff = now - TimeDelta::days(KEEP_DAYS);
+        // Entries are added as tasks are deleted, so the oldest come first
+        let expired = self.entries.partition_point(|entry| entry.deleted < cutoff);
+        self.entries.drain(..expired);
+        expired > 0
+    }
+
+    fn save(&self) -> Result<(), io::Error> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content: String = self
+            .entries
+            .iter()
+            .map(|entry| entry.to_line() + "\n")
+            .collect();
+        fs::write(path, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_restore_last_delete() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("cache").join("trash.tsv");
+        let (mut trash, error) = Trash::load(path.clone());
+        assert!(error.is_none());
+
+        let task = Task {
+            pomodoros: 2,
+            note: "First\nSecond".to_string(),
+            ..Task::new("Fix parser".to_string())
+        };
+        trash.add(TaskSection::Current, vec![task])?;
+        let batch = ["Plan", "Review"].map(|text| Task::new(text.to_string()));
+        trash.add(TaskSection::Backlog, batch.to_vec())?;
+
+        // Survives a restart, and the last delete comes back whole
+        let (mut trash, _) = Trash::load(path.clone());
+        assert_eq!(trash.entries.len(), 3);
+        let restored = trash.restore_last()?;
+        let texts: Vec<&str> = restored.iter().map(|e| e.task.text.as_str()).collect();
+        assert_eq!(texts, ["Plan", "Review"]);
+        assert_eq!(restored[0].section, TaskSection::Backlog);
+
+        let (mut trash, _) = Trash::load(path);
+        let restored = trash.restore_last()?;
+        assert_eq!(restored[0].section, TaskSection::Current);
+        assert_eq!(restored[0].task.pomodoros, 2);
+        assert_eq!(restored[0].task.note, "First\nSecond");
+        assert!(trash.restore_last()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_keeps_days() -> Result<(), io::Error> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("trash.tsv");
+        let (mut trash, _) = Trash::load(path.clone());
+        let day = |d| NaiveDate::from_ymd_opt(2024, 6, d);
+        let task = Task {
+            created_on: day(1),
+            completed_on: day(5),
+            ..Task::new("Fix parser".to_string())
+        };
+        trash.add(TaskSection::Completed, vec![task])?;
+
+        let (mut trash, _) = Trash::load(path);
+        let restored = trash.restore_last()?;
+        assert_eq!(restored[0].task.text, "Fix parser");
+        assert_eq!(restored[0].task.created_on, day(1));
+        assert_eq!(restored[0].task.completed_on, day(5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge() -> Result<(), io::Error> {
+        let mut trash = Trash::new();
+        trash.add(TaskSection::Backlog, vec![Task::new("Old".to_string())])?;
+        assert!(trash.purge(Local::now() + TimeDelta::days(KEEP_DAYS + 1)));
+        assert!(trash.entries.is_empty());
+        Ok(())
+    }
+}
@@ -1,75 +1,409 @@
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
     Frame,
 };
 
+use std::time::Duration;
+
+use chrono::{Local, TimeDelta};
+
 use ratatui_input_manager::KeyMap;
+use serde::{Deserialize, Serialize};
 
 use crate::app::App;
+use crate::history::format_focused;
 use crate::overlays;
-use crate::panels::{PanelId, TasksPanel, TIMER_MIN_WIDTH};
+use crate::panels::{
+    HistoryPanel, PanelId, TasksPanel, TimerPanel, TimerStatus, TIMER_MIN_HEIGHT, TIMER_MIN_WIDTH,
+};
+use crate::timer::SessionType;
 
-/// Layout regions for timer and tasks panels
+/// Smallest frame the normal layout and overlays are rendered into (timer panel plus borders)
+const MIN_FRAME_WIDTH: u16 = TIMER_MIN_WIDTH + 2;
+const MIN_FRAME_HEIGHT: u16 = TIMER_MIN_HEIGHT + 2 + STATUS_LINE_HEIGHT;
+const STATUS_LINE_HEIGHT: u16 = 1;
+
+/// Layout regions for timer and tasks panels; the tasks area also hosts the history panel
 pub struct AppLayout {
     pub timer: Option<Rect>,
     pub tasks: Option<Rect>,
+    /// One area per plugin panel, carved from the bottom of the timer column
+    pub plugins: Vec<Rect>,
+}
+
+/// Arrangement of the timer and tasks columns, cycled with `L`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutPreset {
+    /// Timer alone, with the focused side panel filling the screen instead
+    TimerOnly,
+    /// Timer and tasks side by side, half each
+    #[default]
+    Split,
+    /// Narrow timer, wide tasks
+    TasksDominant,
+    /// Timer above tasks
+    Stacked,
+}
+
+impl LayoutPreset {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::TimerOnly => Self::Split,
+            Self::Split => Self::TasksDominant,
+            Self::TasksDominant => Self::Stacked,
+            Self::Stacked => Self::TimerOnly,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::TimerOnly => "Timer only",
+            Self::Split => "Split",
+            Self::TasksDominant => "Tasks dominant",
+            Self::Stacked => "Stacked",
+        }
+    }
+
+    /// Timer and tasks areas, or None if the timer would not fit beside the tasks
+    fn split(self, area: Rect) -> Option<(Rect, Rect)> {
+        let chunks = match self {
+            Self::TimerOnly => return None,
+            Self::Split => {
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(area)
+            }
+            Self::TasksDominant => {
+                Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                    .split(area)
+            }
+            Self::Stacked => {
+                Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(area)
+            }
+        };
+        let fits = chunks[0].width >= TIMER_MIN_WIDTH && chunks[0].height >= TIMER_MIN_HEIGHT + 2;
+        fits.then(|| (chunks[0], chunks[1]))
+    }
 }
 
 pub fn create_layout(area: Rect, app: &App) -> AppLayout {
     let (timer_area, tasks_area) = if app.tasks_visible {
-        let content_chunks =
-            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .split(area);
-
-        if content_chunks[0].width < TIMER_MIN_WIDTH {
-            if app.focused_panel == PanelId::Timer {
-                (Some(area), None)
-            } else {
-                (None, Some(area))
-            }
-        } else {
-            (Some(content_chunks[0]), Some(content_chunks[1]))
+        match app.layout_preset.split(area) {
+            Some((timer, tasks)) => (Some(timer), Some(tasks)),
+            None if app.focused_panel == PanelId::Timer => (Some(area), None),
+            None => (None, Some(area)),
         }
     } else {
         (Some(area), None)
     };
 
+    let (timer_area, plugins) = match timer_area {
+        Some(area) if !app.plugin_panels.is_empty() => {
+            let (timer, plugins) = split_plugin_areas(area, app);
+            (Some(timer), plugins)
+        }
+        _ => (timer_area, Vec::new()),
+    };
+
     AppLayout {
         timer: timer_area,
         tasks: tasks_area,
+        plugins,
+    }
+}
+
+/// Replace the whole UI with the timer on one line, above a hint asking for a bigger terminal
+/// when there is room for it
+fn render_too_small(frame: &mut Frame, area: Rect, app: &App) {
+    let hint = Line::styled(
+        format!("{MIN_FRAME_WIDTH}x{MIN_FRAME_HEIGHT} for the full view"),
+        Style::default().fg(Color::DarkGray),
+    );
+    let show_hint = area.height >= 3 && hint.width() <= usize::from(area.width);
+    let rows = if show_hint { 3 } else { 1 };
+    let centered = Layout::vertical([Constraint::Length(rows)])
+        .flex(Flex::Center)
+        .split(area)[0];
+    TimerPanel::render_compact(
+        frame,
+        Rect {
+            height: 1,
+            ..centered
+        },
+        &app.timer,
+        &app.config.theme,
+    );
+    if show_hint {
+        let hint_area = Rect {
+            y: centered.y + 2,
+            height: 1,
+            ..centered
+        };
+        frame.render_widget(Paragraph::new(hint).alignment(Alignment::Center), hint_area);
+    }
+}
+
+/// Footer naming the task file and whether it is in sync with the app
+fn render_status_line(frame: &mut Frame, area: Rect, tasks: &TasksPanel) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut spans = vec![Span::styled(
+        format!(
+            " {}",
+            tasks
+                .file_path()
+                .map_or_else(|| "no file".to_string(), |p| p.display().to_string())
+        ),
+        dim,
+    )];
+    if tasks.file_path().is_some() {
+        let synced = tasks.last_sync().map_or_else(
+            || "not synced this session".to_string(),
+            |t| format!("synced {}", t.format("%H:%M")),
+        );
+        spans.push(Span::styled(format!(" · {synced}"), dim));
+    }
+    if tasks.has_unsynced_changes() {
+        spans.push(Span::styled(
+            " · ● unsynced changes",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Mark do not disturb in the top-right corner of the timer border, returning the width used
+fn render_dnd_indicator(frame: &mut Frame, timer_area: Rect) -> u16 {
+    let label = Line::styled(" 🔕 DND ", Style::default().fg(Color::Yellow));
+    let width = label.width() as u16;
+    // Leave room for the panel title on the left
+    if timer_area.width < width + 12 {
+        return 0;
     }
+    let area = Rect::new(timer_area.right() - width - 1, timer_area.y, width, 1);
+    frame.render_widget(Paragraph::new(label), area);
+    width
+}
+
+/// Count down to the next scheduled session on the top border, left of anything `used` on the right
+fn render_next_scheduled(
+    frame: &mut Frame,
+    timer_area: Rect,
+    used: u16,
+    session: SessionType,
+    until: TimeDelta,
+) {
+    let until = until.to_std().unwrap_or_default() + Duration::from_secs(59);
+    let label = Line::styled(
+        format!(" ⏰ {} in {} ", session.label(), format_focused(until)),
+        Style::default().fg(Color::DarkGray),
+    );
+    let width = label.width() as u16;
+    if timer_area.width < width + used + 12 {
+        return;
+    }
+    let area = Rect::new(
+        timer_area.right() - width - used - 1,
+        timer_area.y,
+        width,
+        1,
+    );
+    frame.render_widget(Paragraph::new(label), area);
+}
+
+/// Show today's focused time on the bottom-left of the timer border
+fn render_focused_today(frame: &mut Frame, timer_area: Rect, focused: Duration) {
+    let label = Line::styled(
+        format!(" {} focused ", format_focused(focused)),
+        Style::default().fg(Color::DarkGray),
+    );
+    let width = label.width() as u16;
+    if timer_area.width < width + 4 || timer_area.height < 2 {
+        return;
+    }
+    let area = Rect::new(timer_area.x + 2, timer_area.bottom() - 1, width, 1);
+    frame.render_widget(Paragraph::new(label), area);
+}
+
+/// Show today's pomodoros, against the daily goal if set, on the bottom-right of the timer border
+fn render_goal_progress(frame: &mut Frame, timer_area: Rect, done: u32, goal: u32) {
+    let (text, color) = match goal {
+        0 => (format!(" 🍅 {done} "), Color::DarkGray),
+        goal if done >= goal => (format!(" 🍅 {done}/{goal} ✓ "), Color::Green),
+        goal => (format!(" 🍅 {done}/{goal} "), Color::DarkGray),
+    };
+    let label = Line::styled(text, Style::default().fg(color));
+    let width = label.width() as u16;
+    // Leave room for the focused time on the left
+    if timer_area.width < width + 20 || timer_area.height < 2 {
+        return;
+    }
+    let area = Rect::new(
+        timer_area.right() - width - 2,
+        timer_area.bottom() - 1,
+        width,
+        1,
+    );
+    frame.render_widget(Paragraph::new(label), area);
+}
+
+/// Stack plugin panels below the timer, dropping any that would squeeze the timer too small
+fn split_plugin_areas(area: Rect, app: &App) -> (Rect, Vec<Rect>) {
+    // Timer panel borders
+    let timer_min = TIMER_MIN_HEIGHT + 2;
+    let mut heights = Vec::new();
+    let mut used = 0u16;
+    for panel in &app.plugin_panels {
+        let h = panel.desired_height();
+        if area.height < timer_min + used + h {
+            break;
+        }
+        heights.push(h);
+        used += h;
+    }
+
+    let constraints = std::iter::once(Constraint::Min(timer_min))
+        .chain(heights.iter().map(|&h| Constraint::Length(h)));
+    let chunks = Layout::vertical(constraints).split(area);
+    (chunks[0], chunks[1..].to_vec())
 }
 
 pub fn render(frame: &mut Frame, app: &mut App) {
-    let layout = create_layout(frame.area(), app);
+    let area = frame.area();
+    // Showing or hiding panels changes it too, not only resizes
+    app.compute_column_layout(area.width);
+    if area.width < MIN_FRAME_WIDTH || area.height < MIN_FRAME_HEIGHT {
+        render_too_small(frame, area, app);
+        return;
+    }
+
+    let [area, status_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(STATUS_LINE_HEIGHT)]).areas(area);
+    render_status_line(frame, status_area, &app.tasks_panel);
+
+    let layout = create_layout(area, app);
+    app.tasks_area = layout.tasks;
 
     if let Some(timer_area) = layout.timer {
         app.timer_panel.render(
             frame,
             timer_area,
             app.focused_panel == PanelId::Timer,
-            &app.timer,
-            app.tasks_panel.active_task(),
+            &TimerStatus {
+                timer: &app.timer,
+                active_task: app.tasks_panel.active_task(),
+                today: app.history.sessions_on(Local::now().date_naive()),
+            },
+            &app.config.theme,
         );
     }
 
+    if let Some(timer_area) = layout.timer {
+        let dnd_width = if app.dnd {
+            render_dnd_indicator(frame, timer_area)
+        } else {
+            0
+        };
+        let now = Local::now().naive_local();
+        if let Some((entry, due)) = app.scheduler.next(now) {
+            render_next_scheduled(frame, timer_area, dnd_width, entry.session, due - now);
+        }
+        render_focused_today(frame, timer_area, app.focused_today());
+        render_goal_progress(
+            frame,
+            timer_area,
+            app.pomodoros_today(),
+            app.config.timer.daily_goal,
+        );
+    }
+
+    for (panel, area) in app.plugin_panels.iter().zip(layout.plugins) {
+        panel.render(frame, area, &app.config.theme);
+    }
+
     if let Some(tasks_area) = layout.tasks {
-        app.tasks_panel
-            .render(frame, tasks_area, app.focused_panel == PanelId::Tasks);
+        if app.focused_panel == PanelId::History {
+            app.history_panel.render(
+                frame,
+                tasks_area,
+                true,
+                &app.history,
+                &app.interruptions,
+                app.tasks_panel.estimate_totals_on(app.history_panel.day()),
+                &app.config.theme,
+            );
+        } else {
+            app.tasks_panel.render(
+                frame,
+                tasks_area,
+                app.focused_panel == PanelId::Tasks,
+                &app.history,
+                &app.config.theme,
+            );
+        }
     }
 
-    // Render overlays
+    render_overlays(frame, app);
+
+    if let Some(ref toast) = app.toast {
+        toast.render(frame, &app.config.theme);
+    }
+}
+
+/// Draw the topmost open overlay, if any
+fn render_overlays(frame: &mut Frame, app: &mut App) {
     if let Some(ref message) = app.error_message {
         overlays::render_error_overlay(frame, message);
+    } else if let Some(ref overlay) = app.session_complete {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.recovery {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.sleep_prompt {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.preset_picker {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.quit_prompt {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.task_switcher {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.schedule_input {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.until_input {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.pause_reason {
+        overlay.render(frame, &app.config.theme);
+    } else if let Some(ref overlay) = app.command_palette {
+        overlay.render(frame, &app.config.theme);
     } else if let Some(input) = app.tasks_panel.task_input_overlay() {
-        input.render(frame);
+        input.render(frame, &app.config.theme);
     } else if let Some(sync) = app.tasks_panel.sync_overlay() {
-        sync.render(frame);
+        sync.render(frame, &app.config.theme);
+    } else if let Some(note) = app.tasks_panel.note_overlay() {
+        note.render(frame, &app.config.theme);
+    } else if let Some(confirm) = app.tasks_panel.confirm_overlay() {
+        confirm.render(frame, &app.config.theme);
+    } else if let Some(filter) = app.tasks_panel.tag_filter_overlay() {
+        filter.render(frame, &app.config.theme);
+    } else if let Some(reset) = app.tasks_panel.reset_overlay() {
+        reset.render(frame, &app.config.theme);
+    } else if let Some(projects) = app.tasks_panel.project_overlay() {
+        projects.render(frame, &app.config.theme);
+    } else if let Some(duplicates) = app.tasks_panel.duplicates_overlay() {
+        duplicates.render(frame, &app.config.theme);
+    } else if let Some(picker) = app.tasks_panel.url_picker() {
+        picker.render(frame, &app.config.theme);
+    } else if let Some(backups) = app.tasks_panel.backup_overlay() {
+        backups.render(frame, &app.config.theme);
     } else if app.shortcuts_visible {
         let keybinds = match app.focused_panel {
             PanelId::Timer => App::KEYBINDS,
             PanelId::Tasks => TasksPanel::KEYBINDS,
+            PanelId::History => HistoryPanel::KEYBINDS,
         };
-        overlays::render_help_overlay(frame, keybinds);
+        app.help_scroll =
+            overlays::render_help_overlay(frame, keybinds, &app.config.theme, app.help_scroll);
     }
 }